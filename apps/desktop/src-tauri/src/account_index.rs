@@ -0,0 +1,170 @@
+//! Public index of the identities stored in the OS keychain, one record per profile.
+//!
+//! The account switcher lists stored accounts from this file so it can render before any
+//! keychain read (which may prompt). Only npubs live here; the secret stays in the keychain
+//! and is read lazily when the user switches to a profile.
+
+use crate::data_root::default_app_data_dir;
+use nostr::{PublicKey, ToBech32};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+const ACCOUNT_INDEX_FILE: &str = "stored_accounts.json";
+
+/// Held across every read-modify-write of the index so concurrent updates neither lose
+/// records nor race on the shared temp file.
+static ACCOUNT_INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredAccountRecord {
+    pub profile_id: String,
+    pub npub: String,
+    pub stored_at_unix_ms: u64,
+}
+
+fn account_index_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(ACCOUNT_INDEX_FILE)
+}
+
+/// Every recorded account; a missing or unreadable index reads as empty.
+pub fn read_account_index(app_data_dir: &Path) -> Vec<StoredAccountRecord> {
+    fs::read_to_string(account_index_path(app_data_dir))
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn write_account_index(app_data_dir: &Path, records: &[StoredAccountRecord]) -> Result<(), String> {
+    fs::create_dir_all(app_data_dir)
+        .map_err(|e| format!("Failed to create account index folder: {e}"))?;
+    let path = account_index_path(app_data_dir);
+    let payload = serde_json::to_vec(records).map_err(|e| e.to_string())?;
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, payload).map_err(|e| format!("Failed to write account index: {e}"))?;
+    fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to write account index: {e}"))
+}
+
+/// Record `public_key` as the identity stored for `profile_id`; unchanged records are not rewritten.
+pub fn record_stored_account(
+    app_data_dir: &Path,
+    profile_id: &str,
+    public_key: &PublicKey,
+) -> Result<(), String> {
+    let npub = public_key.to_bech32().map_err(|e| e.to_string())?;
+    let _guard = ACCOUNT_INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut records = read_account_index(app_data_dir);
+    if records
+        .iter()
+        .any(|record| record.profile_id == profile_id && record.npub == npub)
+    {
+        return Ok(());
+    }
+    records.retain(|record| record.profile_id != profile_id);
+    records.push(StoredAccountRecord {
+        profile_id: profile_id.to_string(),
+        npub,
+        stored_at_unix_ms: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0),
+    });
+    write_account_index(app_data_dir, &records)
+}
+
+pub fn forget_stored_account(app_data_dir: &Path, profile_id: &str) -> Result<(), String> {
+    let _guard = ACCOUNT_INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut records = read_account_index(app_data_dir);
+    let before = records.len();
+    records.retain(|record| record.profile_id != profile_id);
+    if records.len() == before {
+        return Ok(());
+    }
+    write_account_index(app_data_dir, &records)
+}
+
+/// Best-effort `record_stored_account` for call sites that just stored or hydrated a key;
+/// a stale index only affects the switcher, so failures are logged rather than returned.
+pub fn note_stored_account(app: &AppHandle, profile_id: &str, public_key: &PublicKey) {
+    let result = default_app_data_dir(app)
+        .and_then(|dir| record_stored_account(&dir, profile_id, public_key));
+    if let Err(e) = result {
+        log::warn!(
+            "[SESSION] Failed to update account index for profile {}: {}",
+            profile_id,
+            e
+        );
+    }
+}
+
+/// Best-effort `forget_stored_account` for call sites that removed a profile's key.
+pub fn note_forgotten_account(app: &AppHandle, profile_id: &str) {
+    let result = default_app_data_dir(app).and_then(|dir| forget_stored_account(&dir, profile_id));
+    if let Err(e) = result {
+        log::warn!(
+            "[SESSION] Failed to update account index for profile {}: {}",
+            profile_id,
+            e
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_records_replaces_and_forgets_accounts() {
+        let dir = std::env::temp_dir().join(format!("obscur-account-index-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        assert!(read_account_index(&dir).is_empty());
+
+        let first = nostr::Keys::generate().public_key();
+        let second = nostr::Keys::generate().public_key();
+        record_stored_account(&dir, "default", &first).unwrap();
+        record_stored_account(&dir, "work", &second).unwrap();
+        record_stored_account(&dir, "default", &second).unwrap();
+
+        let records = read_account_index(&dir);
+        assert_eq!(records.len(), 2);
+        let default = records.iter().find(|r| r.profile_id == "default").unwrap();
+        assert_eq!(default.npub, second.to_bech32().unwrap());
+
+        forget_stored_account(&dir, "default").unwrap();
+        let records = read_account_index(&dir);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].profile_id, "work");
+        assert!(!fs::read_to_string(account_index_path(&dir))
+            .unwrap()
+            .contains("nsec"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn concurrent_records_are_all_kept() {
+        let dir = std::env::temp_dir().join(format!(
+            "obscur-account-index-concurrent-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+
+        let writers: Vec<_> = (0..8)
+            .map(|i| {
+                let dir = dir.clone();
+                std::thread::spawn(move || {
+                    let key = nostr::Keys::generate().public_key();
+                    record_stored_account(&dir, &format!("profile-{i}"), &key).unwrap();
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        assert_eq!(read_account_index(&dir).len(), 8);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
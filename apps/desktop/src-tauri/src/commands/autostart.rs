@@ -0,0 +1,36 @@
+//! Launch-at-login commands
+
+#[cfg(desktop)]
+use crate::models::autostart::*;
+#[cfg(desktop)]
+use crate::services::autostart::*;
+use tauri::AppHandle;
+
+/// Register or remove the login item and persist the preference
+#[tauri::command]
+pub async fn set_autostart(app: AppHandle, enabled: bool) -> Result<(), String> {
+    #[cfg(desktop)]
+    {
+        set_autostart_enabled(&app, enabled)?;
+        write_autostart_settings(&app, &AutostartSettings { enabled })
+    }
+    #[cfg(not(desktop))]
+    {
+        let _ = (app, enabled);
+        Err("Launch at login is only available on desktop".to_string())
+    }
+}
+
+/// Whether the app is currently registered to launch at login
+#[tauri::command]
+pub async fn get_autostart(app: AppHandle) -> Result<bool, String> {
+    #[cfg(desktop)]
+    {
+        is_autostart_enabled(&app)
+    }
+    #[cfg(not(desktop))]
+    {
+        let _ = app;
+        Ok(false)
+    }
+}
@@ -63,7 +63,7 @@ impl DbState {
         self.close()?;
         if sqlite_path.exists() {
             encrypt_file_in_place(&sqlite_path, key)?;
-            eprintln!("[STORAGE] SQLite encrypted at rest.");
+            log::info!("[STORAGE] SQLite encrypted at rest.");
         }
         Ok(())
     }
@@ -87,7 +87,7 @@ fn sqlite_paths(app: &AppHandle) -> Result<(PathBuf, PathBuf), String> {
 pub fn bootstrap_sqlite_storage(app: &AppHandle, db_state: &DbState) -> Result<(), String> {
     let (_, encrypted_path) = sqlite_paths(app)?;
     if encrypted_path.exists() {
-        eprintln!("[STORAGE] SQLite at-rest envelope detected; waiting for unlock.");
+        log::info!("[STORAGE] SQLite at-rest envelope detected; waiting for unlock.");
         return Ok(());
     }
     db_state.open_plaintext_if_available(app)
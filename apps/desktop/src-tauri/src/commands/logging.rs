@@ -0,0 +1,41 @@
+//! Runtime log level and the recent-log buffer behind the in-app debug console
+
+use crate::logging::{parse_level, recent_logs, LogEntry};
+use log::Level;
+
+const DEFAULT_RECENT_LOG_LIMIT: usize = 200;
+
+/// Set the minimum level logged from now on (`error`, `warn`, `info`, `debug`, `trace`, `off`)
+#[tauri::command]
+pub async fn set_log_level(level: String) -> Result<String, String> {
+    let level = parse_level(&level)?;
+    log::set_max_level(level);
+    log::info!("[LOG] Log level set to {}", level);
+    Ok(level.to_string().to_ascii_lowercase())
+}
+
+/// Current minimum log level
+#[tauri::command]
+pub async fn get_log_level() -> Result<String, String> {
+    Ok(log::max_level().to_string().to_ascii_lowercase())
+}
+
+/// The latest `limit` log records (default 200) at `min_level` or more severe, oldest first.
+/// Secrets are already redacted when a record is stored.
+#[tauri::command]
+pub async fn get_recent_logs(
+    limit: Option<usize>,
+    min_level: Option<String>,
+) -> Result<Vec<LogEntry>, String> {
+    let min_level = match min_level.as_deref().map(parse_level).transpose()? {
+        Some(filter) => match filter.to_level() {
+            Some(level) => level,
+            None => return Ok(Vec::new()), // "off"
+        },
+        None => Level::Trace,
+    };
+    Ok(recent_logs(
+        limit.unwrap_or(DEFAULT_RECENT_LOG_LIMIT),
+        min_level,
+    ))
+}
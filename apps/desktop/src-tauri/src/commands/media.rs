@@ -0,0 +1,323 @@
+//! Remote media fetched natively so the WebView never contacts media hosts itself
+
+use crate::net::NativeNetworkRuntime;
+use base64::Engine;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+const MEDIA_CACHE_DIR: &str = "media";
+const MEDIA_FETCH_TIMEOUT_SECS: u64 = 120;
+const MEDIA_DEFAULT_MAX_BYTES: u64 = 25 * 1024 * 1024;
+const MEDIA_MAX_BYTES_CAP: u64 = 200 * 1024 * 1024;
+/// Larger files must be displayed from `path`; a data URL would bloat the IPC payload
+const MEDIA_DATA_URL_MAX_BYTES: u64 = 10 * 1024 * 1024;
+/// Total size the cache may grow to; least recently used files go first past it
+const MEDIA_CACHE_MAX_BYTES: u64 = 500 * 1024 * 1024;
+
+/// Media types the cache accepts and the file extension each is stored under.
+/// SVG is left out on purpose: it can carry script.
+const MEDIA_TYPES: &[(&str, &str)] = &[
+    ("image/jpeg", "jpg"),
+    ("image/png", "png"),
+    ("image/gif", "gif"),
+    ("image/webp", "webp"),
+    ("image/avif", "avif"),
+    ("video/mp4", "mp4"),
+    ("video/webm", "webm"),
+    ("video/quicktime", "mov"),
+    ("audio/mpeg", "mp3"),
+    ("audio/ogg", "ogg"),
+    ("audio/wav", "wav"),
+    ("audio/webm", "weba"),
+    ("audio/mp4", "m4a"),
+];
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchedMedia {
+    /// Cached file on disk; display it through the asset protocol, which is scoped to
+    /// the media cache folder
+    pub path: String,
+    pub content_type: String,
+    pub size: u64,
+    /// Served from the cache without a network request
+    pub cached: bool,
+    /// Set when `as_data_url` was requested
+    pub data_url: Option<String>,
+}
+
+/// Canonical `(content type, extension)` for an allowlisted media type
+fn media_type(content_type: &str) -> Option<(&'static str, &'static str)> {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    MEDIA_TYPES
+        .iter()
+        .find(|(known, _)| *known == mime)
+        .copied()
+}
+
+/// https only, except plain http to onion services (already end-to-end encrypted by Tor)
+fn validate_media_url(url: &str) -> Result<url::Url, String> {
+    let parsed = url::Url::parse(url.trim()).map_err(|e| format!("Invalid media URL: {e}"))?;
+    let host = parsed.host_str().unwrap_or_default();
+    if host.is_empty() {
+        return Err("Media URL missing host".to_string());
+    }
+    match parsed.scheme() {
+        "https" => Ok(parsed),
+        "http" if host.ends_with(".onion") => Ok(parsed),
+        _ => Err("Only https:// media URLs are allowed".to_string()),
+    }
+}
+
+fn media_cache_key(url: &url::Url) -> String {
+    Sha256::digest(url.as_str().as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn find_cached_media(cache_dir: &Path, key: &str) -> Option<(PathBuf, &'static str)> {
+    MEDIA_TYPES.iter().find_map(|(content_type, extension)| {
+        let path = cache_dir.join(format!("{key}.{extension}"));
+        path.is_file().then_some((path, *content_type))
+    })
+}
+
+/// Mark a cache hit as recently used; eviction goes by modification time
+fn touch_cached_media(path: &Path) {
+    let touched = std::fs::File::options()
+        .write(true)
+        .open(path)
+        .and_then(|file| file.set_modified(std::time::SystemTime::now()));
+    if let Err(e) = touched {
+        log::debug!("[Media] Failed to touch {}: {}", path.display(), e);
+    }
+}
+
+/// Delete least recently used cache files until the folder fits in `max_bytes`, sparing
+/// `keep` (the file just fetched). In-flight `.part` files are left alone.
+fn evict_media_cache(cache_dir: &Path, max_bytes: u64, keep: &Path) {
+    let Ok(entries) = std::fs::read_dir(cache_dir) else {
+        return;
+    };
+    let mut files: Vec<(std::time::SystemTime, u64, PathBuf)> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let path = entry.path();
+            let metadata = entry.metadata().ok()?;
+            let partial = path
+                .extension()
+                .is_some_and(|extension| extension == "part");
+            if !metadata.is_file() || partial {
+                return None;
+            }
+            Some((metadata.modified().ok()?, metadata.len(), path))
+        })
+        .collect();
+    let mut total: u64 = files.iter().map(|(_, size, _)| size).sum();
+    files.sort_by_key(|(modified, _, _)| *modified);
+    for (_, size, path) in files {
+        if total <= max_bytes {
+            break;
+        }
+        if path == keep {
+            continue;
+        }
+        if std::fs::remove_file(&path).is_ok() {
+            total = total.saturating_sub(size);
+        }
+    }
+}
+
+async fn fetched_media(
+    path: PathBuf,
+    content_type: &str,
+    cached: bool,
+    as_data_url: bool,
+) -> Result<FetchedMedia, String> {
+    let size = tokio::fs::metadata(&path)
+        .await
+        .map_err(|e| format!("Failed to read cached media: {e}"))?
+        .len();
+    let data_url = if as_data_url {
+        if size > MEDIA_DATA_URL_MAX_BYTES {
+            return Err(format!(
+                "Media is too large for a data URL ({size} bytes, limit {MEDIA_DATA_URL_MAX_BYTES})"
+            ));
+        }
+        let bytes = tokio::fs::read(&path)
+            .await
+            .map_err(|e| format!("Failed to read cached media: {e}"))?;
+        Some(format!(
+            "data:{content_type};base64,{}",
+            base64::engine::general_purpose::STANDARD.encode(bytes)
+        ))
+    } else {
+        None
+    };
+    Ok(FetchedMedia {
+        path: path.to_string_lossy().to_string(),
+        content_type: content_type.to_string(),
+        size,
+        cached,
+        data_url,
+    })
+}
+
+/// Download media referenced in notes through the app's network route (Tor when enabled)
+/// and cache it under the app cache folder, keyed by the SHA-256 of the URL.
+#[tauri::command]
+pub async fn fetch_media(
+    app: AppHandle,
+    net_runtime: State<'_, NativeNetworkRuntime>,
+    url: String,
+    max_bytes: Option<u64>,
+    as_data_url: Option<bool>,
+) -> Result<FetchedMedia, String> {
+    let url = validate_media_url(&url)?;
+    let as_data_url = as_data_url.unwrap_or(false);
+    let max_bytes = max_bytes
+        .unwrap_or(MEDIA_DEFAULT_MAX_BYTES)
+        .min(MEDIA_MAX_BYTES_CAP);
+    let cache_dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| e.to_string())?
+        .join(MEDIA_CACHE_DIR);
+    let key = media_cache_key(&url);
+    if let Some((path, content_type)) = find_cached_media(&cache_dir, &key) {
+        touch_cached_media(&path);
+        return fetched_media(path, content_type, true, as_data_url).await;
+    }
+
+    let client = net_runtime
+        .build_reqwest_client()
+        .map_err(|error| format!("Failed to create HTTP client: {error}"))?;
+    let mut response = client
+        .get(url.as_str())
+        .timeout(Duration::from_secs(MEDIA_FETCH_TIMEOUT_SECS))
+        .send()
+        .await
+        .map_err(|error| format!("Failed to fetch media: {error}"))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Media fetch failed with status {}",
+            response.status().as_u16()
+        ));
+    }
+    let header_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    let Some((content_type, extension)) = media_type(&header_type) else {
+        return Err(format!("Unsupported media type: {header_type:?}"));
+    };
+    if response.content_length().is_some_and(|len| len > max_bytes) {
+        return Err(format!("Media exceeds the {max_bytes} byte limit"));
+    }
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|error| format!("Failed to read media: {error}"))?
+    {
+        if bytes.len() as u64 + chunk.len() as u64 > max_bytes {
+            return Err(format!("Media exceeds the {max_bytes} byte limit"));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    tokio::fs::create_dir_all(&cache_dir)
+        .await
+        .map_err(|e| format!("Failed to create media cache: {e}"))?;
+    let path = cache_dir.join(format!("{key}.{extension}"));
+    // Unique temp name so concurrent fetches of one URL never interleave writes
+    let tmp_path = cache_dir.join(format!("{key}.{}.part", uuid::Uuid::new_v4()));
+    tokio::fs::write(&tmp_path, &bytes)
+        .await
+        .map_err(|e| format!("Failed to write media cache: {e}"))?;
+    tokio::fs::rename(&tmp_path, &path)
+        .await
+        .map_err(|e| format!("Failed to write media cache: {e}"))?;
+    let (evict_dir, keep) = (cache_dir.clone(), path.clone());
+    let _ = tauri::async_runtime::spawn_blocking(move || {
+        evict_media_cache(&evict_dir, MEDIA_CACHE_MAX_BYTES, &keep)
+    })
+    .await;
+    fetched_media(path, content_type, false, as_data_url).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn media_types_are_allowlisted() {
+        assert_eq!(media_type("image/png"), Some(("image/png", "png")));
+        assert_eq!(
+            media_type("Video/MP4; codecs=avc1"),
+            Some(("video/mp4", "mp4"))
+        );
+        assert_eq!(media_type("image/svg+xml"), None);
+        assert_eq!(media_type("text/html"), None);
+        assert_eq!(media_type(""), None);
+    }
+
+    #[test]
+    fn media_urls_require_https_outside_onion() {
+        assert!(validate_media_url("https://cdn.example.com/a.png").is_ok());
+        assert!(validate_media_url("http://abcdef.onion/a.png").is_ok());
+        assert!(validate_media_url("http://cdn.example.com/a.png").is_err());
+        assert!(validate_media_url("file:///etc/passwd").is_err());
+    }
+
+    #[test]
+    fn cache_evicts_least_recently_used_first() {
+        let dir = std::env::temp_dir().join(format!("obscur-media-cache-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let base = std::time::SystemTime::now() - Duration::from_secs(60);
+        let write = |name: &str, age_secs: u64| {
+            let path = dir.join(name);
+            std::fs::write(&path, [0u8; 10]).unwrap();
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(base + Duration::from_secs(age_secs))
+                .unwrap();
+            path
+        };
+        let oldest = write("a.png", 0);
+        let kept = write("b.png", 1);
+        let newest = write("c.png", 2);
+        let part = write("d.png.part", 3);
+
+        evict_media_cache(&dir, 20, &kept);
+        assert!(!oldest.exists());
+        assert!(kept.exists() && newest.exists() && part.exists());
+
+        evict_media_cache(&dir, 10, &kept);
+        assert!(kept.exists());
+        assert!(!newest.exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn cache_key_is_stable_hex() {
+        let url = url::Url::parse("https://cdn.example.com/a.png").unwrap();
+        let key = media_cache_key(&url);
+        assert_eq!(key.len(), 64);
+        assert_eq!(key, media_cache_key(&url));
+    }
+}
@@ -1,20 +1,28 @@
 // Tauri command handlers
 
 pub mod auth_boot;
-pub mod login_assist;
+pub mod autostart;
 pub mod data_root;
+pub mod db;
+pub mod diagnostics;
 pub mod engine;
-pub mod transport_engine;
-pub mod profile_storage;
+pub mod les;
 pub mod local_save;
-pub mod warmup;
-pub mod db;
+pub mod logging;
+pub mod login_assist;
+pub mod media;
+pub mod network;
+pub mod nip19;
 pub mod notification;
 pub mod profile;
+pub mod profile_storage;
 pub mod session;
+pub mod shortcut;
+pub mod sign_auth;
 pub mod storage_at_rest;
-pub mod les;
 pub mod system;
 pub mod tor;
+pub mod transport_engine;
 pub mod tray;
+pub mod warmup;
 pub mod window;
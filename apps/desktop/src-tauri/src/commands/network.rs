@@ -0,0 +1,110 @@
+//! Native network runtime settings commands
+
+use crate::net::{HttpIdentity, NativeNetworkRuntime};
+use std::collections::HashMap;
+use tauri::State;
+
+/// Set how many redirect hops NIP-96 uploads follow; returns the applied (clamped) value.
+#[tauri::command]
+pub async fn set_upload_redirect_limit(
+    net_runtime: State<'_, NativeNetworkRuntime>,
+    max_redirects: usize,
+) -> Result<usize, String> {
+    Ok(net_runtime.set_upload_max_redirects(max_redirects))
+}
+
+/// Current redirect hop limit for NIP-96 uploads.
+#[tauri::command]
+pub async fn get_upload_redirect_limit(
+    net_runtime: State<'_, NativeNetworkRuntime>,
+) -> Result<usize, String> {
+    Ok(net_runtime.get_upload_max_redirects())
+}
+
+/// Set how many times NIP-96 uploads retry transient failures; returns the applied (clamped) value.
+#[tauri::command]
+pub async fn set_upload_retry_limit(
+    net_runtime: State<'_, NativeNetworkRuntime>,
+    max_retries: u32,
+) -> Result<u32, String> {
+    Ok(net_runtime.set_upload_max_retries(max_retries))
+}
+
+/// Current transient-failure retry limit for NIP-96 uploads.
+#[tauri::command]
+pub async fn get_upload_retry_limit(
+    net_runtime: State<'_, NativeNetworkRuntime>,
+) -> Result<u32, String> {
+    Ok(net_runtime.get_upload_max_retries())
+}
+
+/// Set the largest relay WebSocket frame/message accepted, in bytes; returns the applied
+/// (clamped) value. Relays exceeding it are dropped with an `oversize-frame` status.
+#[tauri::command]
+pub async fn set_max_frame_size(
+    net_runtime: State<'_, NativeNetworkRuntime>,
+    bytes: usize,
+) -> Result<usize, String> {
+    Ok(net_runtime.set_max_frame_size(bytes))
+}
+
+/// Resolve clearnet relay/media hostnames via DNS-over-HTTPS (`None` restores the system resolver).
+/// Has no effect while Tor is enabled: SOCKS5h already keeps lookups off the local resolver.
+#[tauri::command]
+pub async fn set_doh_resolver(
+    net_runtime: State<'_, NativeNetworkRuntime>,
+    url: Option<String>,
+) -> Result<(), String> {
+    net_runtime.set_doh_resolver_url(url)
+}
+
+/// Send direct relay connections from this local IP, e.g. a VPN or WireGuard interface
+/// address (`None` lets the OS route). Returns the applied address; ignored while Tor is enabled.
+#[tauri::command]
+pub async fn set_bind_address(
+    net_runtime: State<'_, NativeNetworkRuntime>,
+    address: Option<String>,
+) -> Result<Option<String>, String> {
+    Ok(net_runtime
+        .set_bind_address(address)?
+        .map(|ip| ip.to_string()))
+}
+
+/// Set the User-Agent (`None` restores the generic browser string) and Accept-Language
+/// (`None` sends none) used for uploads, NIP-05/NIP-11 and media fetches.
+#[tauri::command]
+pub async fn set_http_identity(
+    net_runtime: State<'_, NativeNetworkRuntime>,
+    user_agent: Option<String>,
+    accept_language: Option<String>,
+) -> Result<HttpIdentity, String> {
+    net_runtime.set_http_identity(user_agent, accept_language)
+}
+
+/// Headers currently identifying the app's outbound HTTP requests.
+#[tauri::command]
+pub async fn get_http_identity(
+    net_runtime: State<'_, NativeNetworkRuntime>,
+) -> Result<HttpIdentity, String> {
+    Ok(net_runtime.get_http_identity())
+}
+
+/// Headers sent on every relay WebSocket upgrade, e.g. `Origin`, `User-Agent` or
+/// `Sec-WebSocket-Protocol` for relays that reject tungstenite's bare handshake. Headers
+/// passed to `connect_relay` override these per relay; an empty map clears them.
+/// Returns the header names applied.
+#[tauri::command]
+pub async fn set_handshake_headers(
+    net_runtime: State<'_, NativeNetworkRuntime>,
+    headers: HashMap<String, String>,
+) -> Result<Vec<String>, String> {
+    net_runtime.set_handshake_headers(&headers)
+}
+
+/// Names of the headers currently sent on every relay WebSocket upgrade.
+#[tauri::command]
+pub async fn get_handshake_headers(
+    net_runtime: State<'_, NativeNetworkRuntime>,
+) -> Result<Vec<String>, String> {
+    Ok(net_runtime.get_handshake_header_names())
+}
@@ -0,0 +1,287 @@
+//! NIP-19 bech32 entity helpers (npub, note, nprofile, nevent, naddr)
+
+use crate::profiles::{resolve_profile_for_window, DesktopProfileState};
+use crate::session::SessionState;
+use nostr::nips::nip01::Coordinate;
+use nostr::nips::nip19::{FromBech32, Nip19, Nip19Event, Nip19Profile, ToBech32};
+use nostr::{EventId, Kind, PublicKey, RelayUrl};
+use qrcode::render::svg;
+use qrcode::QrCode;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State, WebviewWindow};
+
+/// Smallest edge of the rendered QR code, in SVG pixels
+const QR_MIN_DIMENSION: u32 = 256;
+
+/// Fields of a NIP-19 entity; which ones are set depends on the entity type
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Nip19Data {
+    #[serde(default)]
+    pub pubkey: Option<String>,
+    #[serde(default)]
+    pub event_id: Option<String>,
+    #[serde(default)]
+    pub kind: Option<u16>,
+    /// `d` tag of an naddr
+    #[serde(default)]
+    pub identifier: Option<String>,
+    /// TLV relay hints (nprofile, nevent, naddr)
+    #[serde(default)]
+    pub relays: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Nip19Decoded {
+    /// "npub", "note", "nprofile", "nevent" or "naddr"
+    pub entity: String,
+    pub data: Nip19Data,
+}
+
+fn parse_pubkey(data: &Nip19Data) -> Result<PublicKey, String> {
+    let pubkey = data
+        .pubkey
+        .as_deref()
+        .ok_or_else(|| "pubkey is required".to_string())?;
+    PublicKey::parse(pubkey.trim()).map_err(|e| format!("Invalid pubkey: {e}"))
+}
+
+fn parse_event_id(data: &Nip19Data) -> Result<EventId, String> {
+    let event_id = data
+        .event_id
+        .as_deref()
+        .ok_or_else(|| "event_id is required".to_string())?;
+    EventId::parse(event_id.trim()).map_err(|e| format!("Invalid event id: {e}"))
+}
+
+fn parse_relays(data: &Nip19Data) -> Result<Vec<RelayUrl>, String> {
+    data.relays
+        .iter()
+        .map(|relay| {
+            RelayUrl::parse(relay.trim()).map_err(|e| format!("Invalid relay hint {relay}: {e}"))
+        })
+        .collect()
+}
+
+fn encode_nip19(entity: &str, data: &Nip19Data) -> Result<String, String> {
+    let encoded = match entity.trim() {
+        "npub" => parse_pubkey(data)?.to_bech32(),
+        "note" => parse_event_id(data)?.to_bech32(),
+        "nprofile" => Nip19Profile {
+            public_key: parse_pubkey(data)?,
+            relays: parse_relays(data)?,
+        }
+        .to_bech32(),
+        "nevent" => {
+            let relays = parse_relays(data)?;
+            let mut event = Nip19Event::new(
+                parse_event_id(data)?,
+                relays.iter().map(|relay| relay.to_string()),
+            );
+            if data.pubkey.is_some() {
+                event = event.author(parse_pubkey(data)?);
+            }
+            if let Some(kind) = data.kind {
+                event = event.kind(Kind::from(kind));
+            }
+            event.to_bech32()
+        }
+        "naddr" => Coordinate {
+            kind: Kind::from(
+                data.kind
+                    .ok_or_else(|| "kind is required for naddr".to_string())?,
+            ),
+            public_key: parse_pubkey(data)?,
+            identifier: data.identifier.clone().unwrap_or_default(),
+            relays: parse_relays(data)?,
+        }
+        .to_bech32(),
+        other => return Err(format!("Unsupported NIP-19 entity type: {other}")),
+    };
+    encoded.map_err(|e| format!("Failed to encode {entity}: {e}"))
+}
+
+fn decode_nip19(entity: &str) -> Result<Nip19Decoded, String> {
+    let trimmed = entity.trim();
+    let bech32 = trimmed.strip_prefix("nostr:").unwrap_or(trimmed);
+    let decoded = Nip19::from_bech32(bech32).map_err(|e| format!("Invalid NIP-19 entity: {e}"))?;
+    let (entity, data) = match decoded {
+        Nip19::Pubkey(public_key) => (
+            "npub",
+            Nip19Data {
+                pubkey: Some(public_key.to_hex()),
+                ..Nip19Data::default()
+            },
+        ),
+        Nip19::EventId(event_id) => (
+            "note",
+            Nip19Data {
+                event_id: Some(event_id.to_hex()),
+                ..Nip19Data::default()
+            },
+        ),
+        Nip19::Profile(profile) => (
+            "nprofile",
+            Nip19Data {
+                pubkey: Some(profile.public_key.to_hex()),
+                relays: profile
+                    .relays
+                    .iter()
+                    .map(|relay| relay.to_string())
+                    .collect(),
+                ..Nip19Data::default()
+            },
+        ),
+        Nip19::Event(event) => (
+            "nevent",
+            Nip19Data {
+                pubkey: event.author.map(|author| author.to_hex()),
+                event_id: Some(event.event_id.to_hex()),
+                kind: event.kind.map(|kind| kind.as_u16()),
+                identifier: None,
+                relays: event.relays,
+            },
+        ),
+        Nip19::Coordinate(coordinate) => (
+            "naddr",
+            Nip19Data {
+                pubkey: Some(coordinate.public_key.to_hex()),
+                event_id: None,
+                kind: Some(coordinate.kind.as_u16()),
+                identifier: Some(coordinate.identifier),
+                relays: coordinate
+                    .relays
+                    .iter()
+                    .map(|relay| relay.to_string())
+                    .collect(),
+            },
+        ),
+        // Secret keys never travel through this helper
+        _ => return Err("Secret key entities are not decoded here".to_string()),
+    };
+    Ok(Nip19Decoded {
+        entity: entity.to_string(),
+        data,
+    })
+}
+
+/// `nostr:` URI for sharing a profile: an npub, or an nprofile when relay hints are given
+fn profile_share_uri(pubkey: &PublicKey, relays: &[String]) -> Result<String, String> {
+    let data = Nip19Data {
+        pubkey: Some(pubkey.to_hex()),
+        relays: relays.to_vec(),
+        ..Nip19Data::default()
+    };
+    let entity = if relays.is_empty() {
+        "npub"
+    } else {
+        "nprofile"
+    };
+    Ok(format!("nostr:{}", encode_nip19(entity, &data)?))
+}
+
+fn qr_svg(data: &str) -> Result<String, String> {
+    let code =
+        QrCode::new(data.as_bytes()).map_err(|e| format!("Failed to encode QR code: {e}"))?;
+    Ok(code
+        .render::<svg::Color>()
+        .min_dimensions(QR_MIN_DIMENSION, QR_MIN_DIMENSION)
+        .quiet_zone(true)
+        .build())
+}
+
+/// Render the active profile as a QR code (SVG markup) of its `nostr:npub1…` URI,
+/// or of an `nostr:nprofile1…` URI carrying `relays` as hints.
+#[tauri::command]
+pub async fn npub_qr_svg(
+    app: AppHandle,
+    window: WebviewWindow,
+    session: State<'_, SessionState>,
+    profiles: State<'_, DesktopProfileState>,
+    relays: Option<Vec<String>>,
+) -> Result<String, String> {
+    let profile_id = resolve_profile_for_window(&app, &profiles, &window).await?;
+    let keys = session
+        .get_keys(&profile_id)
+        .await
+        .ok_or_else(|| "No active session".to_string())?;
+    qr_svg(&profile_share_uri(
+        &keys.public_key(),
+        &relays.unwrap_or_default(),
+    )?)
+}
+
+/// Encode `data` as the NIP-19 entity named by `kind` (npub, note, nprofile, nevent, naddr).
+#[tauri::command]
+pub async fn nip19_encode(kind: String, data: Nip19Data) -> Result<String, String> {
+    encode_nip19(&kind, &data)
+}
+
+/// Decode a NIP-19 entity (optionally `nostr:`-prefixed) into its fields and relay hints.
+#[tauri::command]
+pub async fn nip19_decode(entity: String) -> Result<Nip19Decoded, String> {
+    decode_nip19(&entity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nevent_round_trips_with_relay_hints() {
+        let author = nostr::Keys::generate().public_key();
+        let data = Nip19Data {
+            pubkey: Some(author.to_hex()),
+            event_id: Some(EventId::all_zeros().to_hex()),
+            kind: Some(1),
+            identifier: None,
+            relays: vec!["wss://relay.example.com".to_string()],
+        };
+        let encoded = encode_nip19("nevent", &data).expect("encode");
+        assert!(encoded.starts_with("nevent1"));
+        let decoded = decode_nip19(&format!("nostr:{encoded}")).expect("decode");
+        assert_eq!(decoded.entity, "nevent");
+        assert_eq!(decoded.data.event_id, data.event_id);
+        assert_eq!(decoded.data.pubkey, data.pubkey);
+        assert_eq!(decoded.data.kind, Some(1));
+        assert_eq!(decoded.data.relays.len(), 1);
+        assert!(decoded.data.relays[0].starts_with("wss://relay.example.com"));
+    }
+
+    #[test]
+    fn naddr_round_trips_identifier() {
+        let author = nostr::Keys::generate().public_key();
+        let data = Nip19Data {
+            pubkey: Some(author.to_hex()),
+            kind: Some(30023),
+            identifier: Some("my-article".to_string()),
+            ..Nip19Data::default()
+        };
+        let decoded = decode_nip19(&encode_nip19("naddr", &data).expect("encode")).expect("decode");
+        assert_eq!(decoded.entity, "naddr");
+        assert_eq!(decoded.data.identifier.as_deref(), Some("my-article"));
+        assert_eq!(decoded.data.kind, Some(30023));
+    }
+
+    #[test]
+    fn rejects_secrets_and_unknown_types() {
+        let keys = nostr::Keys::generate();
+        let nsec = keys.secret_key().to_bech32().expect("nsec");
+        assert!(decode_nip19(&nsec).is_err());
+        assert!(encode_nip19("nsec", &Nip19Data::default()).is_err());
+        assert!(encode_nip19("naddr", &Nip19Data::default()).is_err());
+    }
+
+    #[test]
+    fn share_uri_renders_to_svg() {
+        let pubkey = nostr::Keys::generate().public_key();
+        let npub = profile_share_uri(&pubkey, &[]).expect("npub");
+        assert!(npub.starts_with("nostr:npub1"));
+        let relays = vec!["wss://relay.example.com".to_string()];
+        let nprofile = profile_share_uri(&pubkey, &relays).expect("nprofile");
+        assert!(nprofile.starts_with("nostr:nprofile1"));
+
+        let svg = qr_svg(&npub).expect("svg");
+        assert!(svg.contains("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+}
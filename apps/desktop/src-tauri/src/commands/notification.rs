@@ -1,34 +1,93 @@
 //! Notification and tray commands
 
+#[cfg(desktop)]
+use crate::models::window::MAIN_WINDOW_LABEL;
+#[cfg(desktop)]
+use serde::Serialize;
 use serde_json::Value;
 use tauri::AppHandle;
+#[cfg(desktop)]
+use tauri::{Emitter, Manager};
 
-/// Show a system notification
+/// Payload emitted on `notification-clicked`
+#[cfg(desktop)]
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NotificationClickedPayload {
+    tag: Option<String>,
+    deep_link: Option<String>,
+    data: Option<Value>,
+}
+
+/// Bring the main window forward (like the tray click) and hand the payload to the UI
+#[cfg(desktop)]
+fn handle_notification_click(app: &AppHandle, payload: NotificationClickedPayload) {
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+        let _ = window.emit("notification-clicked", payload);
+    }
+}
+
+/// Show a system notification.
+/// On desktop, clicking it focuses the main window and emits `notification-clicked`
+/// with `tag`, `deep_link` and `data` so the UI can jump to the DM/thread.
 #[tauri::command]
 pub async fn show_notification(
     app: AppHandle,
     title: String,
     body: String,
-    _tag: Option<String>,
-    _data: Option<Value>,
+    tag: Option<String>,
+    data: Option<Value>,
     _require_interaction: Option<bool>,
     _actions: Option<Vec<Value>>,
+    deep_link: Option<String>,
 ) -> Result<(), String> {
-    #[cfg(target_os = "windows")]
+    #[cfg(desktop)]
     {
         let mut notification = notify_rust::Notification::new();
         notification.summary(&title).body(&body);
+        #[cfg(target_os = "windows")]
         notification.app_id(&app.config().identifier);
-        return notification
-            .show()
-            .map(|_| ())
-            .map_err(|e| e.to_string());
+        #[cfg(target_os = "macos")]
+        {
+            let _ = notify_rust::set_application(if tauri::is_dev() {
+                "com.apple.Terminal"
+            } else {
+                &app.config().identifier
+            });
+        }
+        // XDG servers only report body clicks when a "default" action is advertised.
+        #[cfg(all(unix, not(target_os = "macos")))]
+        notification.action("default", "Open");
+
+        let handle = notification.show().map_err(|e| e.to_string())?;
+        let payload = NotificationClickedPayload {
+            tag,
+            deep_link,
+            data,
+        };
+        std::thread::spawn(move || {
+            let _ = handle.wait_for_response(|response: &notify_rust::NotificationResponse| {
+                let clicked = match response {
+                    notify_rust::NotificationResponse::Default => true,
+                    notify_rust::NotificationResponse::Action(action) => action == "default",
+                    _ => false,
+                };
+                if clicked {
+                    handle_notification_click(&app, payload);
+                }
+            });
+        });
+        Ok(())
     }
 
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(mobile)]
     {
         use tauri_plugin_notification::NotificationExt;
 
+        let _ = (tag, data, deep_link);
         return app
             .notification()
             .builder()
@@ -1,10 +1,15 @@
 // Session management commands for native authentication
 
-use tauri::{AppHandle, WebviewWindow};
+use crate::account_index;
+use crate::data_root::default_app_data_dir;
+use crate::models::sign_auth::SignAuthState;
 use crate::native_keychain;
-use crate::profiles::{DesktopProfileState, resolve_profile_for_window};
+use crate::profiles::{resolve_profile_for_window, DesktopProfileState};
+use crate::services::sign_auth::{authorize_keychain_unlock, keychain_unlock_requires_auth};
 use crate::session::{SessionResponse, SessionState, SessionStatus};
 use nostr::{Keys, SecretKey, ToBech32};
+use tauri::{AppHandle, Manager, WebviewWindow};
+use zeroize::Zeroizing;
 
 fn normalize_public_key_hex(value: &str) -> Option<String> {
     let trimmed = value.trim();
@@ -32,6 +37,7 @@ fn public_key_hex_from_keychain_secret(key_str: &str) -> Option<String> {
 }
 
 async fn hydrate_profile_session(
+    app: &AppHandle,
     session: &SessionState,
     profile_id: &str,
 ) -> Result<Option<SessionStatus>, String> {
@@ -42,10 +48,18 @@ async fn hydrate_profile_session(
             is_native: true,
         }));
     }
+    let sign_auth = app.state::<SignAuthState>();
+    if keychain_unlock_requires_auth(&sign_auth).await
+        && read_raw_keychain_payload(profile_id)?.is_none()
+    {
+        return Ok(None);
+    }
+    authorize_keychain_unlock(&sign_auth).await?;
     if let Some(nsec) = native_keychain::read_nsec_for_profile(profile_id)? {
         session.set_keys(profile_id, &nsec).await?;
         if let Some(keys) = session.get_keys(profile_id).await {
-            eprintln!(
+            account_index::note_stored_account(app, profile_id, &keys.public_key());
+            log::info!(
                 "[SESSION] Native session re-hydrated from OS keychain for profile {}",
                 profile_id
             );
@@ -83,12 +97,15 @@ async fn find_profile_id_with_matching_keychain_pubkey(
 #[cfg(not(target_os = "android"))]
 fn read_raw_keychain_payload(profile_id: &str) -> Result<Option<String>, String> {
     use keyring::Entry;
-    let canonical = Entry::new(native_keychain::APP_SERVICE, &native_keychain::key_name_for_profile(profile_id))
-        .map_err(|e| e.to_string())?;
+    let canonical = Entry::new(
+        native_keychain::keychain_service(),
+        &native_keychain::key_name_for_profile(profile_id),
+    )
+    .map_err(|e| native_keychain::keyring_error_message(&e))?;
     match canonical.get_password() {
         Ok(payload) => Ok(Some(payload)),
         Err(keyring::Error::NoEntry) => Ok(None),
-        Err(e) => Err(e.to_string()),
+        Err(e) => Err(native_keychain::keyring_error_message(&e)),
     }
 }
 
@@ -129,7 +146,7 @@ pub async fn force_session_restore_for_window(
     let window_label = window.label().to_string();
     let mut profile_id = resolve_profile_for_window(app, profiles, window).await?;
 
-    if let Some(status) = hydrate_profile_session(session, &profile_id).await? {
+    if let Some(status) = hydrate_profile_session(app, session, &profile_id).await? {
         if let Some(expected) = expected_pubkey_hex.as_deref() {
             let expected_normalized = normalize_public_key_hex(expected);
             let active_normalized = status
@@ -165,7 +182,7 @@ pub async fn force_session_restore_for_window(
             find_profile_id_with_matching_keychain_pubkey(profiles.inner(), expected).await?
         {
             if matching_profile_id != profile_id {
-                eprintln!(
+                log::info!(
                     "[SESSION] Rebinding window '{}' from profile {} to {} for keychain restore",
                     window_label, profile_id, matching_profile_id
                 );
@@ -174,7 +191,7 @@ pub async fn force_session_restore_for_window(
                     .await?;
                 profile_id = matching_profile_id;
             }
-            if let Some(status) = hydrate_profile_session(session, &profile_id).await? {
+            if let Some(status) = hydrate_profile_session(app, session, &profile_id).await? {
                 return Ok(status);
             }
         }
@@ -184,7 +201,7 @@ pub async fn force_session_restore_for_window(
         find_any_keychain_profile_id(profiles.inner(), &profile_id).await?
     {
         if matching_profile_id != profile_id {
-            eprintln!(
+            log::info!(
                 "[SESSION] Rebinding window '{}' from profile {} to {} for any-keychain restore",
                 window_label, profile_id, matching_profile_id
             );
@@ -193,7 +210,7 @@ pub async fn force_session_restore_for_window(
                 .await?;
             profile_id = matching_profile_id;
         }
-        if let Some(status) = hydrate_profile_session(session, &profile_id).await? {
+        if let Some(status) = hydrate_profile_session(app, session, &profile_id).await? {
             return Ok(status);
         }
     }
@@ -212,7 +229,11 @@ pub async fn session_status_for_window(
     profiles: &tauri::State<'_, DesktopProfileState>,
 ) -> Result<SessionStatus, String> {
     let profile_id = resolve_profile_for_window(app, profiles, window).await?;
-    if session.get_keys(&profile_id).await.is_none() {
+    // A status query never prompts; with biometric unlock on, hydration waits for an explicit restore.
+    let sign_auth = app.state::<SignAuthState>();
+    if session.get_keys(&profile_id).await.is_none()
+        && !keychain_unlock_requires_auth(&sign_auth).await
+    {
         if let Ok(Some(nsec)) = native_keychain::read_nsec_for_profile(&profile_id) {
             let _ = session.set_keys(&profile_id, &nsec).await;
         }
@@ -270,6 +291,7 @@ pub async fn init_native_session(
     profiles: tauri::State<'_, DesktopProfileState>,
     nsec: String,
 ) -> Result<SessionResponse, String> {
+    let nsec = Zeroizing::new(nsec);
     let profile_id = resolve_profile_for_window(&app, &profiles, &window).await?;
     match session.set_keys(&profile_id, &nsec).await {
         Ok(_pubkey) => {
@@ -277,19 +299,22 @@ pub async fn init_native_session(
                 .get_keys(&profile_id)
                 .await
                 .ok_or_else(|| "Session keys missing after init".to_string())?;
-            let nsec_for_keychain = keys
-                .secret_key()
-                .to_bech32()
-                .map_err(|e| e.to_string())?;
+            let nsec_for_keychain =
+                Zeroizing::new(keys.secret_key().to_bech32().map_err(|e| e.to_string())?);
             native_keychain::write_nsec_for_profile(&profile_id, &nsec_for_keychain)?;
+            account_index::note_stored_account(&app, &profile_id, &keys.public_key());
             if native_keychain::read_nsec_for_profile(&profile_id)?.is_none() {
-                eprintln!(
+                log::info!(
                     "[SESSION] Native session keys active in memory for profile {} (keychain verify pending)",
                     profile_id
                 );
             }
             let npub = keys.public_key().to_bech32().map_err(|e| e.to_string())?;
-            eprintln!("[SESSION] Native session initialized and persisted for {} on profile {}", npub, profile_id);
+            log::info!(
+                "[SESSION] Native session initialized and persisted for {} on profile {}",
+                npub,
+                profile_id
+            );
             Ok(SessionResponse {
                 success: true,
                 npub: Some(npub),
@@ -313,10 +338,26 @@ pub async fn clear_native_session(
 ) -> Result<(), String> {
     let profile_id = resolve_profile_for_window(&app, &profiles, &window).await?;
     session.clear(Some(&profile_id)).await;
-    eprintln!("[SESSION] Native session cleared for profile {}", profile_id);
+    log::info!(
+        "[SESSION] Native session cleared for profile {}",
+        profile_id
+    );
     Ok(())
 }
 
+/// Keep this window's hydrated session alive under the session TTL so the keychain is not
+/// read again. Returns false when there is no live session to keep.
+#[tauri::command]
+pub async fn touch_session(
+    app: AppHandle,
+    window: WebviewWindow,
+    session: tauri::State<'_, SessionState>,
+    profiles: tauri::State<'_, DesktopProfileState>,
+) -> Result<bool, String> {
+    let profile_id = resolve_profile_for_window(&app, &profiles, &window).await?;
+    Ok(session.touch(&profile_id).await)
+}
+
 #[tauri::command]
 pub async fn get_session_status(
     app: AppHandle,
@@ -326,3 +367,40 @@ pub async fn get_session_status(
 ) -> Result<crate::session::SessionStatus, String> {
     session_status_for_window(&app, &window, session.inner(), &profiles).await
 }
+
+/// A keychain-stored identity as shown by the account switcher
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredAccount {
+    pub profile_id: String,
+    pub label: String,
+    pub npub: String,
+    pub stored_at_unix_ms: u64,
+}
+
+/// Stored identities in profile registry order, read from the account index only: no
+/// keychain access, so nothing prompts. The secret is read when `ensure_session` hydrates
+/// the profile the user picks.
+#[tauri::command]
+pub async fn list_stored_accounts(
+    app: AppHandle,
+    profiles: tauri::State<'_, DesktopProfileState>,
+) -> Result<Vec<StoredAccount>, String> {
+    let records = account_index::read_account_index(&default_app_data_dir(&app)?);
+    Ok(profiles
+        .list_profiles()
+        .await
+        .into_iter()
+        .filter_map(|profile| {
+            let record = records
+                .iter()
+                .find(|record| record.profile_id == profile.profile_id)?;
+            Some(StoredAccount {
+                profile_id: profile.profile_id,
+                label: profile.label,
+                npub: record.npub.clone(),
+                stored_at_unix_ms: record.stored_at_unix_ms,
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,46 @@
+//! Global shortcut commands
+
+#[cfg(desktop)]
+use crate::models::shortcut::*;
+#[cfg(desktop)]
+use crate::services::shortcut::*;
+use tauri::AppHandle;
+#[cfg(desktop)]
+use tauri::Manager;
+
+/// Get the accelerator that shows/hides the main window
+#[tauri::command]
+pub async fn get_global_shortcut(app: AppHandle) -> Result<Option<String>, String> {
+    #[cfg(desktop)]
+    {
+        let state = app.state::<GlobalShortcutState>();
+        let current = state.toggle_window.lock().unwrap().clone();
+        Ok(current)
+    }
+    #[cfg(not(desktop))]
+    {
+        let _ = app;
+        Ok(None)
+    }
+}
+
+/// Change the show/hide shortcut (e.g. "CommandOrControl+Shift+O") and persist it
+#[tauri::command]
+pub async fn set_global_shortcut(app: AppHandle, shortcut: String) -> Result<(), String> {
+    #[cfg(desktop)]
+    {
+        let accelerator = shortcut.trim().to_string();
+        register_toggle_window_shortcut(&app, &accelerator)?;
+        write_shortcut_settings(
+            &app,
+            &ShortcutSettings {
+                toggle_window: accelerator,
+            },
+        )
+    }
+    #[cfg(not(desktop))]
+    {
+        let _ = (app, shortcut);
+        Err("Global shortcuts are only available on desktop".to_string())
+    }
+}
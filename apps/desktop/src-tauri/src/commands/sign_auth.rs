@@ -0,0 +1,113 @@
+//! Sign-authorization commands
+
+use crate::models::sign_auth::*;
+use crate::platform_biometric::{probe_biometric_capability, BiometricCapabilityStatus};
+use crate::services::sign_auth::{write_sign_auth_settings, write_timestamp_privacy};
+use crate::session::SessionState;
+use tauri::{AppHandle, State};
+
+/// Turn the OS-auth gate for native signing on or off and set its grace period
+#[tauri::command]
+pub async fn set_sign_auth_settings(
+    app: AppHandle,
+    state: State<'_, SignAuthState>,
+    require_auth_to_sign: bool,
+    grace_period_secs: Option<u64>,
+) -> Result<SignAuthSettings, String> {
+    if require_auth_to_sign && probe_biometric_capability() != BiometricCapabilityStatus::Available
+    {
+        return Err("OS authentication is not available on this device".to_string());
+    }
+
+    let mut settings = state.settings.lock().await;
+    let updated = SignAuthSettings {
+        require_auth_to_sign,
+        require_biometric: settings.require_biometric,
+        grace_period_secs: grace_period_secs
+            .unwrap_or(settings.grace_period_secs)
+            .min(MAX_SIGN_AUTH_GRACE_SECS),
+        session_ttl_secs: settings.session_ttl_secs,
+    };
+    write_sign_auth_settings(&app, &updated)?;
+    *settings = updated.clone();
+    // A changed policy must not ride on an authorization granted under the old one
+    *state.last_authorized.lock().await = None;
+    Ok(updated)
+}
+
+/// Require an OS prompt before the session secret is read from the keychain
+#[tauri::command]
+pub async fn set_require_biometric(
+    app: AppHandle,
+    state: State<'_, SignAuthState>,
+    enabled: bool,
+) -> Result<SignAuthSettings, String> {
+    if enabled && probe_biometric_capability() != BiometricCapabilityStatus::Available {
+        return Err("OS authentication is not available on this device".to_string());
+    }
+
+    let mut settings = state.settings.lock().await;
+    let updated = SignAuthSettings {
+        require_biometric: enabled,
+        ..settings.clone()
+    };
+    write_sign_auth_settings(&app, &updated)?;
+    *settings = updated.clone();
+    *state.last_authorized.lock().await = None;
+    Ok(updated)
+}
+
+/// Set how long hydrated session keys survive without use or `touch_session` (0 = no limit)
+#[tauri::command]
+pub async fn set_session_ttl(
+    app: AppHandle,
+    state: State<'_, SignAuthState>,
+    session: State<'_, SessionState>,
+    ttl_secs: u64,
+) -> Result<SignAuthSettings, String> {
+    let mut settings = state.settings.lock().await;
+    let updated = SignAuthSettings {
+        session_ttl_secs: ttl_secs,
+        ..settings.clone()
+    };
+    write_sign_auth_settings(&app, &updated)?;
+    session.set_idle_ttl_secs(ttl_secs);
+    *settings = updated.clone();
+    Ok(updated)
+}
+
+/// Current sign-authorization settings
+#[tauri::command]
+pub async fn get_sign_auth_settings(
+    state: State<'_, SignAuthState>,
+) -> Result<SignAuthSettings, String> {
+    Ok(state.settings.lock().await.clone())
+}
+
+/// Choose how `created_at` is blurred before native signing (exact by default). Auth,
+/// replaceable, addressable and ephemeral events always keep their exact time
+#[tauri::command]
+pub async fn set_timestamp_privacy(
+    app: AppHandle,
+    state: State<'_, SignAuthState>,
+    mode: TimestampPrivacy,
+) -> Result<TimestampPrivacy, String> {
+    let mode = match mode {
+        TimestampPrivacy::Jitter { secs } => TimestampPrivacy::Jitter {
+            secs: secs.min(MAX_TIMESTAMP_JITTER_SECS),
+        },
+        other => other,
+    };
+    let mut current = state.timestamp_privacy.lock().await;
+    write_timestamp_privacy(&app, mode)?;
+    *current = mode;
+    Ok(mode)
+}
+
+/// Current timestamp privacy mode
+#[tauri::command]
+pub async fn get_timestamp_privacy(
+    state: State<'_, SignAuthState>,
+) -> Result<TimestampPrivacy, String> {
+    Ok(*state.timestamp_privacy.lock().await)
+}
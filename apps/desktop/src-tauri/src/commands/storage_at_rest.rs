@@ -36,9 +36,10 @@ pub async fn desktop_storage_at_rest_unlock(
     storage_keys.set_key(&resolved_profile_id, key);
     db_state.unlock_with_key(&app, &key)?;
     if let Err(error) = native_keychain::write_pdk_for_profile(&resolved_profile_id, &key) {
-        eprintln!(
+        log::warn!(
             "[STORAGE] Failed to persist profile data key to keychain for profile {}: {}",
-            resolved_profile_id, error
+            resolved_profile_id,
+            error
         );
     }
     Ok(())
@@ -181,14 +181,33 @@ pub async fn check_for_updates(app: AppHandle) -> Result<String, String> {
 }
 
 /// Download and install update in-process (Tauri updater; `dialog: false` in config).
+/// With a pinned release key built in, the downloaded artifact must also verify against it.
 #[tauri::command]
 pub async fn install_update(app: AppHandle) -> Result<(), String> {
     match update_channel::build_updater(&app) {
         Ok(updater) => match updater.check().await {
-            Ok(Some(update)) => match update.download_and_install(|_, _| {}, || {}).await {
-                Ok(_) => Ok(()),
-                Err(e) => Err(format!("Failed to install update: {}", e)),
-            },
+            Ok(Some(update)) => {
+                let bytes = update
+                    .download(|_, _| {}, || {})
+                    .await
+                    .map_err(|e| format!("Failed to download update: {}", e))?;
+                if let Some(pubkey) = update_channel::pinned_update_pubkey() {
+                    let signature =
+                        update_channel::pinned_artifact_signature(&update).ok_or_else(|| {
+                            "Update is missing the pinned release signature; refusing to install"
+                                .to_string()
+                        })?;
+                    let verified =
+                        update_channel::verify_artifact_signature(&bytes, &signature, pubkey);
+                    if let Err(error) = verified {
+                        log::error!("[Updater] Rejected update {}: {}", update.version, error);
+                        return Err(format!("{error}; refusing to install"));
+                    }
+                }
+                update
+                    .install(bytes)
+                    .map_err(|e| format!("Failed to install update: {}", e))
+            }
             Ok(None) => Err("No updates available".to_string()),
             Err(e) => Err(format!("Failed to check for updates: {}", e)),
         },
@@ -196,17 +215,23 @@ pub async fn install_update(app: AppHandle) -> Result<(), String> {
     }
 }
 
-/// Get system theme preference
+/// Get system theme preference (`"light"` or `"dark"`) from the native window theme
 #[tauri::command]
-pub async fn get_system_theme() -> Result<String, String> {
+pub async fn get_system_theme(window: WebviewWindow) -> Result<String, String> {
     #[cfg(desktop)]
     {
-        // Use dark-light crate or tauri API
-        // For now return a default
-        Ok("dark".to_string())
+        if let Ok(theme) = window.theme() {
+            return Ok(crate::services::theme::theme_label(theme).to_string());
+        }
+        // Native query unavailable; reuse what the theme watcher last observed
+        let last = window
+            .try_state::<crate::models::theme::SystemThemeState>()
+            .and_then(|state| *state.last.lock().unwrap());
+        Ok(last.unwrap_or("dark").to_string())
     }
     #[cfg(mobile)]
     {
+        let _ = window;
         Ok("dark".to_string())
     }
 }
@@ -248,7 +273,12 @@ pub async fn register_push_token(
     pubkey: String,
     token: String,
 ) -> Result<(), String> {
-    eprintln!("[PUSH] Registering push token for {}: {}", pubkey, token);
+    // The token itself stays out of the logs
+    log::debug!(
+        "[PUSH] Registering push token for {} ({} chars)",
+        pubkey,
+        token.len()
+    );
     Ok(())
 }
 
@@ -275,8 +305,14 @@ pub async fn reset_app_storage(
 
     let app_data_dir = app.path().app_data_dir().ok();
     if let Some(dir) = &app_data_dir {
-        let files_to_remove: [(&str, bool); 2] =
-            [("tor_settings.json", false), ("window_state.json", false)];
+        let files_to_remove: [(&str, bool); 6] = [
+            ("tor_settings.json", false),
+            ("window_state.json", false),
+            ("shortcut_settings.json", false),
+            ("autostart_settings.json", false),
+            ("sign_auth_settings.json", false),
+            ("relays.json", false),
+        ];
         for (name, _) in files_to_remove {
             let path = dir.join(name);
             if path.exists() {
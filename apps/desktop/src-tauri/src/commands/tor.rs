@@ -1,8 +1,10 @@
 use tauri::{AppHandle, Emitter, Manager};
-use tauri_plugin_shell::ShellExt;
 use tauri_plugin_shell::process::CommandEvent;
+use tauri_plugin_shell::ShellExt;
 // use std::sync::Mutex;
-use crate::models::tor::{TorSettings, TorRuntimeStatus, TorState, TorStatusSnapshot};
+use crate::models::tor::{
+    TorRuntimeStatus, TorSettings, TorState, TorStatusSnapshot, DEFAULT_TOR_BOOTSTRAP_TIMEOUT_SECS,
+};
 use crate::net;
 
 const TOR_LOG_BUFFER_LIMIT: usize = 200;
@@ -75,7 +77,7 @@ async fn refresh_tor_runtime_status_from_proxy(state: &TorState) -> Result<bool,
     }
 }
 
-fn build_tor_status_snapshot(state: &TorState) -> Result<TorStatusSnapshot, String> {
+pub fn build_tor_status_snapshot(state: &TorState) -> Result<TorStatusSnapshot, String> {
     let settings = state.settings.lock().map_err(|e| e.to_string())?;
     let runtime_status = state.runtime_status.lock().map_err(|e| e.to_string())?;
     let using_external = state
@@ -85,6 +87,11 @@ fn build_tor_status_snapshot(state: &TorState) -> Result<TorStatusSnapshot, Stri
         .unwrap_or(false);
 
     let ready = *runtime_status == TorRuntimeStatus::Connected;
+    let failure_reason = state
+        .failure_reason
+        .lock()
+        .map(|guard| guard.clone())
+        .unwrap_or(None);
 
     Ok(TorStatusSnapshot {
         state: *runtime_status,
@@ -92,6 +99,7 @@ fn build_tor_status_snapshot(state: &TorState) -> Result<TorStatusSnapshot, Stri
         ready,
         using_external_instance: using_external,
         proxy_url: settings.proxy_url.clone(),
+        failure_reason,
     })
 }
 
@@ -105,6 +113,11 @@ fn set_tor_runtime_status(
         let mut guard = state.runtime_status.lock().map_err(|e| e.to_string())?;
         *guard = status;
     }
+    if status != TorRuntimeStatus::Failed {
+        if let Ok(mut reason) = state.failure_reason.lock() {
+            reason.take();
+        }
+    }
     if let Some(ext) = external {
         let mut guard = state
             .using_external_instance
@@ -127,6 +140,54 @@ pub fn stop_tor_child(state: &TorState) -> Result<bool, String> {
     }
 }
 
+/// Reason shown when bootstrap times out, with the last progress line Tor reported
+fn bootstrap_timeout_reason(timeout_secs: u64, logs: &[String]) -> String {
+    let progress = logs
+        .iter()
+        .rev()
+        .find_map(|line| {
+            let start = line.find("Bootstrapped ")?;
+            Some(line[start..].trim().to_string())
+        })
+        .unwrap_or_else(|| "no bootstrap progress reported".to_string());
+    format!(
+        "Tor did not finish bootstrapping within {timeout_secs}s ({progress}). \
+         The network may be blocking Tor; bridges or an external proxy may get through."
+    )
+}
+
+/// Stop the sidecar `pid` and report `failed` if it is still starting after `timeout_secs`.
+async fn enforce_bootstrap_timeout(app: AppHandle, pid: u32, timeout_secs: u64) {
+    tokio::time::sleep(std::time::Duration::from_secs(timeout_secs)).await;
+    let state = app.state::<TorState>();
+    let still_starting = state
+        .runtime_status
+        .lock()
+        .map(|guard| *guard == TorRuntimeStatus::Starting)
+        .unwrap_or(false);
+    let same_child = state
+        .child
+        .lock()
+        .map(|guard| guard.as_ref().map(|child| child.pid()) == Some(pid))
+        .unwrap_or(false);
+    if !still_starting || !same_child {
+        return;
+    }
+
+    let reason = match state.logs.lock() {
+        Ok(logs) => bootstrap_timeout_reason(timeout_secs, &logs),
+        Err(_) => bootstrap_timeout_reason(timeout_secs, &[]),
+    };
+    log::warn!("[TOR] {reason}");
+    let _ = stop_tor_child(&state);
+    let _ = append_tor_log(&state, reason.clone());
+    if let Ok(mut failure_reason) = state.failure_reason.lock() {
+        *failure_reason = Some(reason.clone());
+    }
+    let _ = set_tor_runtime_status(&app, &state, TorRuntimeStatus::Failed, Some(false));
+    let _ = app.emit("tor-error", reason);
+}
+
 #[tauri::command]
 pub async fn start_tor(
     app: tauri::AppHandle,
@@ -136,6 +197,24 @@ pub async fn start_tor(
         let lock = state.child.lock().map_err(|e| e.to_string())?;
         lock.is_some()
     };
+    let external_proxy = state
+        .settings
+        .lock()
+        .map(|settings| settings.external_proxy)
+        .map_err(|e| e.to_string())?;
+    if external_proxy {
+        let reachable = refresh_tor_runtime_status_from_proxy(&state).await?;
+        let status = if reachable {
+            TorRuntimeStatus::Connected
+        } else {
+            TorRuntimeStatus::Error
+        };
+        set_tor_runtime_status(&app, &state, status, Some(true))?;
+        let message = "External proxy mode: using the configured proxy, bundled Tor not started.";
+        append_tor_log(&state, message)?;
+        let _ = app.emit("tor-log", message);
+        return Ok("Using external proxy".to_string());
+    }
     if already_running {
         let _ = refresh_tor_runtime_status_from_proxy(&state).await;
         let snapshot = build_tor_status_snapshot(&state)?;
@@ -202,7 +281,13 @@ pub async fn start_tor(
                         .lock()
                         .map(|guard| *guard)
                         .unwrap_or(false);
-                    if !using_external_instance {
+                    // A bootstrap timeout already reported why the sidecar went away
+                    let failed = tor_state
+                        .runtime_status
+                        .lock()
+                        .map(|guard| *guard == TorRuntimeStatus::Failed)
+                        .unwrap_or(false);
+                    if !using_external_instance && !failed {
                         let _ = set_tor_runtime_status(
                             &app_handle,
                             &tor_state,
@@ -216,6 +301,7 @@ pub async fn start_tor(
         }
     });
 
+    let pid = child.pid();
     let mut lock = state.child.lock().map_err(|e| e.to_string())?;
     *lock = Some(child);
     drop(lock);
@@ -225,6 +311,14 @@ pub async fn start_tor(
         "Tor sidecar started. Waiting for bootstrap confirmation...",
     );
     set_tor_runtime_status(&app, &state, TorRuntimeStatus::Starting, Some(false))?;
+    let timeout_secs = state
+        .settings
+        .lock()
+        .map(|settings| settings.bootstrap_timeout_secs)
+        .map_err(|e| e.to_string())?;
+    if timeout_secs > 0 {
+        tauri::async_runtime::spawn(enforce_bootstrap_timeout(app.clone(), pid, timeout_secs));
+    }
     Ok("Tor started".to_string())
 }
 
@@ -270,15 +364,35 @@ pub async fn save_tor_settings(
     net_runtime: tauri::State<'_, net::NativeNetworkRuntime>,
     enable_tor: bool,
     proxy_url: String,
+    external_proxy: Option<bool>,
+    bootstrap_timeout_secs: Option<u64>,
 ) -> Result<(), String> {
     let mut settings = state.settings.lock().unwrap();
     settings.enable_tor = enable_tor;
     settings.proxy_url = proxy_url.clone();
+    if let Some(external_proxy) = external_proxy {
+        settings.external_proxy = external_proxy;
+    }
+    if let Some(bootstrap_timeout_secs) = bootstrap_timeout_secs {
+        settings.bootstrap_timeout_secs = bootstrap_timeout_secs;
+    }
 
+    let previous_route = net_runtime.default_route();
     net_runtime.set(enable_tor, proxy_url.clone());
+    if net_runtime.default_route() != previous_route {
+        // Live relay sockets still use the old transport until they are rebuilt
+        let app = app.clone();
+        tauri::async_runtime::spawn(crate::relay::reconnect_for_route_change(app));
+    }
 
     if !enable_tor {
         let _ = set_tor_runtime_status(&app, &state, TorRuntimeStatus::Disconnected, Some(false));
+    } else if settings.external_proxy {
+        // The user's own proxy takes over; a sidecar left running would hold its port
+        if stop_tor_child(&state)? {
+            let _ = append_tor_log(&state, "Tor sidecar stopped: switched to external proxy.");
+        }
+        let _ = set_tor_runtime_status(&app, &state, TorRuntimeStatus::Starting, Some(true));
     }
 
     // Save to file
@@ -295,6 +409,8 @@ pub fn load_tor_settings(app: &tauri::AppHandle) -> TorSettings {
     let default = TorSettings {
         enable_tor: false,
         proxy_url: "socks5h://127.0.0.1:9050".to_string(),
+        external_proxy: false,
+        bootstrap_timeout_secs: DEFAULT_TOR_BOOTSTRAP_TIMEOUT_SECS,
     };
 
     let Ok(app_dir) = app.path().app_data_dir() else {
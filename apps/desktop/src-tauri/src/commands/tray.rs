@@ -1,13 +1,13 @@
 //! Tray and notification commands
 
-use tauri::AppHandle;
-use serde_json::json;
-#[cfg(desktop)]
-use tauri::Manager;
 #[cfg(desktop)]
 use crate::models::tray::*;
 #[cfg(desktop)]
 use crate::services::tray::*;
+use serde_json::json;
+#[cfg(desktop)]
+use tauri::Manager;
+use tauri::{AppHandle, WebviewWindow};
 
 /// Set tray unread badge count
 #[tauri::command]
@@ -35,6 +35,30 @@ pub async fn set_tray_unread_badge_count(
     Ok(())
 }
 
+/// Set the dock/taskbar unread badge for the calling window (0 clears it).
+/// macOS and Linux (Unity launcher) show a native count; Windows gets a taskbar overlay icon.
+#[tauri::command]
+pub async fn set_badge_count(window: WebviewWindow, count: u32) -> Result<(), String> {
+    #[cfg(all(desktop, not(target_os = "windows")))]
+    {
+        let badge = (count > 0).then_some(i64::from(count));
+        window.set_badge_count(badge).map_err(|e| e.to_string())?;
+    }
+    #[cfg(target_os = "windows")]
+    {
+        let overlay = TrayBadgeState::format_badge_label(count)
+            .map(|label| render_badge_overlay_icon(&label));
+        window
+            .set_overlay_icon(overlay)
+            .map_err(|e| e.to_string())?;
+    }
+    #[cfg(mobile)]
+    {
+        let _ = (window, count);
+    }
+    Ok(())
+}
+
 /// Set incoming call state for tray
 #[tauri::command]
 pub async fn set_tray_incoming_call_state(
@@ -1,12 +1,15 @@
 //! Window control commands
 
 #[cfg(desktop)]
-use crate::models::window::{WindowState, MAIN_WINDOW_LABEL, PERSIST_WINDOW_STATE_IN_DEBUG};
-
+use crate::models::window::{
+    AlwaysOnTopSettings, AlwaysOnTopState, CloseBehavior, CloseBehaviorSettings,
+    CloseBehaviorState, WindowState, MAIN_WINDOW_LABEL, PERSIST_WINDOW_STATE_IN_DEBUG,
+};
 #[cfg(desktop)]
-fn is_main_window_label(label: &str) -> bool {
-    label == MAIN_WINDOW_LABEL
-}
+use crate::services::window::{
+    always_on_top_preference, close_behavior_for_window, quit_app, write_always_on_top_settings,
+    write_close_behavior_settings,
+};
 #[cfg(desktop)]
 use tauri::Manager;
 use tauri::{AppHandle, WebviewWindow, Window};
@@ -47,20 +50,63 @@ pub async fn window_unmaximize(window: Window) -> Result<(), String> {
     }
 }
 
-/// Close the window — main window hides (tray); profile windows are destroyed.
+/// Show, unminimize and focus the window
+#[tauri::command]
+pub async fn window_show(window: Window, app: AppHandle) -> Result<(), String> {
+    #[cfg(desktop)]
+    {
+        window.unminimize().map_err(|e| e.to_string())?;
+        window.show().map_err(|e| e.to_string())?;
+        window.set_focus().map_err(|e| e.to_string())?;
+        crate::relay::on_window_visibility_changed(&app, window.label(), true);
+        Ok(())
+    }
+    #[cfg(mobile)]
+    {
+        let _ = window;
+        let _ = app;
+        Ok(())
+    }
+}
+
+/// Hide the window (to the tray for the main window)
+#[tauri::command]
+pub async fn window_hide(window: Window, app: AppHandle) -> Result<(), String> {
+    #[cfg(desktop)]
+    {
+        window.hide().map_err(|e| e.to_string())?;
+        crate::relay::on_window_visibility_changed(&app, window.label(), false);
+        Ok(())
+    }
+    #[cfg(mobile)]
+    {
+        let _ = window;
+        let _ = app;
+        Ok(())
+    }
+}
+
+/// Close the window — main window hides to the tray or quits, per the close behavior
+/// setting; profile windows are destroyed.
 #[tauri::command]
 pub async fn window_close(window: Window, app: AppHandle) -> Result<(), String> {
     #[cfg(desktop)]
     {
+        let Some(behavior) = close_behavior_for_window(&app, window.label()) else {
+            return window.close().map_err(|e| e.to_string());
+        };
         if let Some(webview_window) = app.get_webview_window(window.label()) {
             if let Ok(state) = capture_window_state(&webview_window) {
                 let _ = write_window_state(&app, webview_window.label(), &state);
             }
         }
-        if is_main_window_label(window.label()) {
-            return window.hide().map_err(|e| e.to_string());
+        if behavior == CloseBehavior::Quit {
+            quit_app(&app);
+            return Ok(());
         }
-        return window.close().map_err(|e| e.to_string());
+        window.hide().map_err(|e| e.to_string())?;
+        crate::relay::on_window_visibility_changed(&app, window.label(), false);
+        Ok(())
     }
     #[cfg(mobile)]
     {
@@ -70,6 +116,83 @@ pub async fn window_close(window: Window, app: AppHandle) -> Result<(), String>
     }
 }
 
+/// Get what closing the main window does: "tray" or "quit"
+#[tauri::command]
+pub async fn get_close_behavior(app: AppHandle) -> Result<String, String> {
+    #[cfg(desktop)]
+    {
+        let state = app.state::<CloseBehaviorState>();
+        let behavior = *state.behavior.lock().unwrap();
+        Ok(behavior.as_str().to_string())
+    }
+    #[cfg(not(desktop))]
+    {
+        let _ = app;
+        Ok("quit".to_string())
+    }
+}
+
+/// Set what closing the main window does ("tray" or "quit") and persist it
+#[tauri::command]
+pub async fn set_close_behavior(app: AppHandle, behavior: String) -> Result<(), String> {
+    #[cfg(desktop)]
+    {
+        let close_behavior = CloseBehavior::parse(&behavior)?;
+        write_close_behavior_settings(&app, &CloseBehaviorSettings { close_behavior })?;
+        let state = app.state::<CloseBehaviorState>();
+        *state.behavior.lock().unwrap() = close_behavior;
+        Ok(())
+    }
+    #[cfg(not(desktop))]
+    {
+        let _ = (app, behavior);
+        Err("Close behavior is only configurable on desktop".to_string())
+    }
+}
+
+/// Keep the main window above other windows and persist the choice. While the window is
+/// fullscreen the preference is only stored and takes effect when fullscreen is left.
+#[tauri::command]
+pub async fn set_always_on_top(app: AppHandle, enabled: bool) -> Result<(), String> {
+    #[cfg(desktop)]
+    {
+        write_always_on_top_settings(
+            &app,
+            &AlwaysOnTopSettings {
+                always_on_top: enabled,
+            },
+        )?;
+        *app.state::<AlwaysOnTopState>().enabled.lock().unwrap() = enabled;
+        if let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) {
+            if !window.is_fullscreen().unwrap_or(false) {
+                window
+                    .set_always_on_top(enabled)
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+        Ok(())
+    }
+    #[cfg(not(desktop))]
+    {
+        let _ = (app, enabled);
+        Err("Always on top is only available on desktop".to_string())
+    }
+}
+
+/// Whether the main window is set to stay on top (the saved preference)
+#[tauri::command]
+pub async fn is_always_on_top(app: AppHandle) -> Result<bool, String> {
+    #[cfg(desktop)]
+    {
+        Ok(always_on_top_preference(&app))
+    }
+    #[cfg(not(desktop))]
+    {
+        let _ = app;
+        Ok(false)
+    }
+}
+
 /// Show and focus the invoking window (used after profile boot in secondary windows).
 #[tauri::command]
 pub async fn window_show_and_focus(window: Window, app: AppHandle) -> Result<(), String> {
@@ -149,11 +272,41 @@ pub async fn window_is_maximized(window: Window) -> Result<bool, String> {
     }
 }
 
-/// Set window fullscreen state
+/// Check if window is visible, i.e. shown and not minimized; false while hidden in the tray
+#[tauri::command]
+pub async fn window_is_visible(window: Window) -> Result<bool, String> {
+    #[cfg(desktop)]
+    {
+        let visible = window.is_visible().map_err(|e| e.to_string())?;
+        let minimized = window.is_minimized().map_err(|e| e.to_string())?;
+        Ok(visible && !minimized)
+    }
+    #[cfg(mobile)]
+    {
+        let _ = window;
+        Ok(true)
+    }
+}
+
+/// Set window fullscreen state. A pinned main window leaves the topmost level while
+/// fullscreen, since the two fight on some platforms, and gets it back on exit.
 #[tauri::command]
 pub async fn window_set_fullscreen(window: Window, fullscreen: bool) -> Result<(), String> {
     #[cfg(desktop)]
-    return window.set_fullscreen(fullscreen).map_err(|e| e.to_string());
+    {
+        let pinned =
+            window.label() == MAIN_WINDOW_LABEL && always_on_top_preference(window.app_handle());
+        if pinned && fullscreen {
+            window.set_always_on_top(false).map_err(|e| e.to_string())?;
+        }
+        window
+            .set_fullscreen(fullscreen)
+            .map_err(|e| e.to_string())?;
+        if pinned && !fullscreen {
+            window.set_always_on_top(true).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
     #[cfg(mobile)]
     {
         let _ = window;
@@ -181,12 +334,18 @@ pub fn capture_window_state(window: &WebviewWindow) -> Result<WindowState, Strin
     let position = window.outer_position().map_err(|e| e.to_string())?;
     let size = window.outer_size().map_err(|e| e.to_string())?;
     let maximized = window.is_maximized().map_err(|e| e.to_string())?;
+    let monitor = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .and_then(|monitor| monitor.name().cloned());
     Ok(sanitize_window_state(WindowState {
         x: position.x,
         y: position.y,
         width: size.width,
         height: size.height,
         maximized,
+        monitor,
     }))
 }
 
@@ -554,7 +554,7 @@ fn install_data_root_bind(
                 })
                 .unwrap_or(false);
             if !already_pointer {
-                eprintln!(
+                log::warn!(
                     "[obscur] data root redirect failed ({}); falling back to pointer bind",
                     redirect_error
                 );
@@ -704,7 +704,7 @@ fn copy_migration_file(source: &Path, destination: &Path) -> Result<MigrationCop
                 std::thread::sleep(Duration::from_millis(120));
             }
             Err(error) if is_file_locked_io_error(&error) && is_migration_cache_path(source) => {
-                eprintln!(
+                log::info!(
                     "[obscur] skipping locked browser cache file during migration: {}",
                     source.display()
                 );
@@ -869,7 +869,7 @@ pub fn resolve_webview_profile_workspace(
     let workspace_root = if health.available {
         physical_storage_path(&anchor)
     } else {
-        eprintln!(
+        log::warn!(
             "[obscur] physical data root unavailable ({}); using offline webview workspace at {}",
             health.issue.as_deref().unwrap_or("unknown"),
             recovery_webview_root(&anchor).display()
@@ -890,7 +890,7 @@ pub fn resolve_webview_profile_workspace(
         for category in ["images", "videos", "audio", "files"] {
             let _ = fs::create_dir_all(emergency.join(PROFILE_VAULT_SUBDIR).join(category));
         }
-        eprintln!(
+        log::info!(
             "[obscur] offline webview workspace fell back to {}",
             emergency.display()
         );
@@ -920,7 +920,7 @@ pub fn bootstrap_data_root_authority(app: &AppHandle) -> Result<(), String> {
     let app_data = default_app_data_dir(app)?;
     if let Ok(Some(authority)) = try_heal_data_root(app, &app_data) {
         let physical = physical_storage_path(&app_data);
-        eprintln!(
+        log::info!(
             "[obscur] data root bind healed authority={} anchor={} physical={} mode={}",
             authority.as_config_str(),
             app_data.display(),
@@ -1643,7 +1643,7 @@ pub fn set_data_root_config(
                 )?)
             } else {
                 if migrate_existing && !source_available {
-                    eprintln!(
+                    log::info!(
                         "[obscur] skipping data migration — source folder is unavailable at {}",
                         source_root.display()
                     );
@@ -0,0 +1,154 @@
+//! Password-encrypted key file used when no OS keychain backend is available
+//! (e.g. a minimal Linux install without a Secret Service daemon).
+//!
+//! The key is stored as a NIP-49 `ncryptsec` string under `<app data>/keys/`, so the
+//! on-disk format is the same one users already export and import as backups.
+
+use nostr::nips::nip49::{EncryptedSecretKey, KeySecurity};
+use nostr::{FromBech32, Keys, ToBech32};
+use std::fs;
+use std::path::{Path, PathBuf};
+use zeroize::Zeroizing;
+
+const KEY_FILE_DIR: &str = "keys";
+const KEY_FILE_EXTENSION: &str = "ncryptsec";
+/// scrypt cost (2^16 rounds), the NIP-49 recommended default
+const KEY_FILE_LOG_N: u8 = 16;
+
+/// Error prefix telling the UI to ask for the key file password
+pub const KEYFILE_PASSWORD_REQUIRED_CODE: &str = "KEYFILE_PASSWORD_REQUIRED";
+
+pub const NO_KEYCHAIN_WARNING: &str =
+    "No OS keychain detected. Your key is stored in a password-encrypted file in the app data folder instead; anyone with the file and your password can read it.";
+
+pub fn key_file_path(app_data_dir: &Path, profile_id: &str) -> Result<PathBuf, String> {
+    let valid = !profile_id.is_empty()
+        && profile_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(format!("Invalid profile id for key file: {profile_id}"));
+    }
+    Ok(app_data_dir
+        .join(KEY_FILE_DIR)
+        .join(format!("{profile_id}.{KEY_FILE_EXTENSION}")))
+}
+
+pub fn has_encrypted_key_file(app_data_dir: &Path, profile_id: &str) -> bool {
+    key_file_path(app_data_dir, profile_id)
+        .map(|path| path.is_file())
+        .unwrap_or(false)
+}
+
+/// Encrypt `secret` (nsec or hex) with `password` and write it for `profile_id`.
+pub fn write_encrypted_key_file(
+    app_data_dir: &Path,
+    profile_id: &str,
+    secret: &str,
+    password: &str,
+) -> Result<(), String> {
+    if password.is_empty() {
+        return Err("A password is required to encrypt the key file".to_string());
+    }
+    let keys = Keys::parse(secret).map_err(|e| format!("Invalid secret key: {e}"))?;
+    let encrypted = EncryptedSecretKey::new(
+        keys.secret_key(),
+        password,
+        KEY_FILE_LOG_N,
+        KeySecurity::Medium,
+    )
+    .map_err(|e| format!("Failed to encrypt key file: {e}"))?;
+    let ncryptsec = encrypted
+        .to_bech32()
+        .map_err(|e| format!("Failed to encode key file: {e}"))?;
+
+    let path = key_file_path(app_data_dir, profile_id)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create key file folder: {e}"))?;
+    }
+    let tmp_path = path.with_extension(format!("{KEY_FILE_EXTENSION}.tmp"));
+    fs::write(&tmp_path, ncryptsec.as_bytes())
+        .map_err(|e| format!("Failed to write key file: {e}"))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&tmp_path, fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Failed to restrict key file permissions: {e}"))?;
+    }
+    fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to write key file: {e}"))
+}
+
+/// Decrypt the key file for `profile_id`; `Ok(None)` when no file exists.
+pub fn read_encrypted_key_file(
+    app_data_dir: &Path,
+    profile_id: &str,
+    password: &str,
+) -> Result<Option<Zeroizing<String>>, String> {
+    let path = key_file_path(app_data_dir, profile_id)?;
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Failed to read key file: {e}")),
+    };
+    let encrypted = EncryptedSecretKey::from_bech32(contents.trim())
+        .map_err(|e| format!("Key file is corrupted: {e}"))?;
+    let secret_key = encrypted
+        .to_secret_key(password)
+        .map_err(|_| "Could not decrypt key file: wrong password or corrupted file".to_string())?;
+    Ok(Some(Zeroizing::new(secret_key.to_secret_hex())))
+}
+
+pub fn delete_encrypted_key_file(app_data_dir: &Path, profile_id: &str) -> Result<(), String> {
+    let path = key_file_path(app_data_dir, profile_id)?;
+    match fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to delete key file: {e}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("obscur-key-file-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn key_file_round_trips_with_the_right_password() {
+        let dir = temp_dir("round-trip");
+        let keys = Keys::generate();
+        let secret = keys.secret_key().to_secret_hex();
+
+        write_encrypted_key_file(&dir, "default", &secret, "hunter2").unwrap();
+        assert!(has_encrypted_key_file(&dir, "default"));
+        let stored = fs::read_to_string(key_file_path(&dir, "default").unwrap()).unwrap();
+        assert!(stored.starts_with("ncryptsec"));
+        assert!(!stored.contains(&secret));
+
+        let decrypted = read_encrypted_key_file(&dir, "default", "hunter2")
+            .unwrap()
+            .unwrap();
+        assert_eq!(decrypted.as_str(), secret);
+        assert!(read_encrypted_key_file(&dir, "default", "wrong").is_err());
+
+        delete_encrypted_key_file(&dir, "default").unwrap();
+        assert!(!has_encrypted_key_file(&dir, "default"));
+        assert!(read_encrypted_key_file(&dir, "default", "hunter2")
+            .unwrap()
+            .is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn key_file_path_rejects_traversal() {
+        let dir = temp_dir("path");
+        assert!(key_file_path(&dir, "../escape").is_err());
+        assert!(key_file_path(&dir, "").is_err());
+        assert!(key_file_path(&dir, "profile-1").is_ok());
+    }
+}
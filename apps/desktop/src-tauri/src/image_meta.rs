@@ -0,0 +1,338 @@
+//! Image dimensions and blurhash for NIP-94 `imeta` tags (`dim`, `blurhash`).
+//!
+//! Dimensions come from the container header (PNG, JPEG, GIF, WebP) without decoding
+//! pixels. A blurhash needs pixels, and the only decoder in this crate's dependency
+//! tree is `png`; there is no JPEG, GIF or WebP decoder, so those formats get `dim`
+//! alone and leave the blurhash to the client. The blurhash encoder itself is the
+//! reference algorithm from https://blurha.sh, written out here rather than pulling
+//! in a crate for ~60 lines.
+
+use serde::Serialize;
+
+/// Blurhash components, the size most clients render
+const BLURHASH_X_COMPONENTS: u32 = 4;
+const BLURHASH_Y_COMPONENTS: u32 = 3;
+/// Pixels sampled per axis when computing the blurhash
+const BLURHASH_SAMPLE_EDGE: u32 = 64;
+/// Images larger than this are not decoded for a blurhash
+const MAX_DECODE_PIXELS: u64 = 40_000_000;
+const BASE83_CHARS: &[u8; 83] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz#$%*+,-.:;=?@[]^_{|}~";
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImageMetadata {
+    /// `<width>x<height>`, as used by the NIP-94 `dim` tag
+    pub dim: String,
+    pub blurhash: Option<String>,
+}
+
+/// Dimensions and blurhash for a recognized image MIME type; `None` for anything else
+/// or when the header cannot be read.
+pub fn image_metadata(content_type: &str, bytes: &[u8]) -> Option<ImageMetadata> {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    let (width, height) = match mime.as_str() {
+        "image/png" => png_dimensions(bytes)?,
+        "image/jpeg" | "image/jpg" => jpeg_dimensions(bytes)?,
+        "image/gif" => gif_dimensions(bytes)?,
+        "image/webp" => webp_dimensions(bytes)?,
+        _ => return None,
+    };
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let blurhash = match mime.as_str() {
+        "image/png" => png_blurhash(bytes, width, height),
+        // JPEG, GIF and WebP: no decoder in the tree, see the module docs
+        _ => None,
+    };
+    Some(ImageMetadata {
+        dim: format!("{width}x{height}"),
+        blurhash,
+    })
+}
+
+/// Whether `image_metadata` understands this MIME type
+pub fn is_supported_image_type(content_type: &str) -> bool {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    matches!(
+        mime.as_str(),
+        "image/png" | "image/jpeg" | "image/jpg" | "image/gif" | "image/webp"
+    )
+}
+
+fn png_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    if bytes.len() < 24 || &bytes[..8] != b"\x89PNG\r\n\x1a\n" || &bytes[12..16] != b"IHDR" {
+        return None;
+    }
+    let width = u32::from_be_bytes(bytes[16..20].try_into().ok()?);
+    let height = u32::from_be_bytes(bytes[20..24].try_into().ok()?);
+    Some((width, height))
+}
+
+fn gif_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    if bytes.len() < 10 || !(bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a")) {
+        return None;
+    }
+    let width = u16::from_le_bytes([bytes[6], bytes[7]]);
+    let height = u16::from_le_bytes([bytes[8], bytes[9]]);
+    Some((width.into(), height.into()))
+}
+
+/// Walk JPEG segments to the first start-of-frame marker.
+fn jpeg_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    if !bytes.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+    let mut pos = 2;
+    loop {
+        while *bytes.get(pos)? != 0xFF {
+            pos += 1;
+        }
+        while *bytes.get(pos)? == 0xFF {
+            pos += 1;
+        }
+        let marker = *bytes.get(pos)?;
+        pos += 1;
+        // Standalone markers carry no length
+        if marker == 0x01 || (0xD0..=0xD7).contains(&marker) {
+            continue;
+        }
+        let length = u16::from_be_bytes([*bytes.get(pos)?, *bytes.get(pos + 1)?]) as usize;
+        let is_start_of_frame =
+            matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC);
+        if is_start_of_frame {
+            let height = u16::from_be_bytes([*bytes.get(pos + 3)?, *bytes.get(pos + 4)?]);
+            let width = u16::from_be_bytes([*bytes.get(pos + 5)?, *bytes.get(pos + 6)?]);
+            return Some((width.into(), height.into()));
+        }
+        if marker == 0xD9 || marker == 0xDA || length < 2 {
+            return None;
+        }
+        pos += length;
+    }
+}
+
+fn webp_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    if bytes.len() < 30 || &bytes[..4] != b"RIFF" || &bytes[8..12] != b"WEBP" {
+        return None;
+    }
+    let le24 = |at: usize| {
+        u32::from(bytes[at]) | u32::from(bytes[at + 1]) << 8 | u32::from(bytes[at + 2]) << 16
+    };
+    match &bytes[12..16] {
+        b"VP8 " => {
+            if bytes[23..26] != [0x9D, 0x01, 0x2A] {
+                return None;
+            }
+            let width = u16::from_le_bytes([bytes[26], bytes[27]]) & 0x3FFF;
+            let height = u16::from_le_bytes([bytes[28], bytes[29]]) & 0x3FFF;
+            Some((width.into(), height.into()))
+        }
+        b"VP8L" => {
+            if bytes[20] != 0x2F {
+                return None;
+            }
+            let bits = u32::from_le_bytes(bytes[21..25].try_into().ok()?);
+            Some(((bits & 0x3FFF) + 1, ((bits >> 14) & 0x3FFF) + 1))
+        }
+        b"VP8X" => Some((le24(24) + 1, le24(27) + 1)),
+        _ => None,
+    }
+}
+
+/// Decode a PNG to 8-bit samples and encode a blurhash from a downsampled grid.
+fn png_blurhash(bytes: &[u8], width: u32, height: u32) -> Option<String> {
+    if u64::from(width) * u64::from(height) > MAX_DECODE_PIXELS {
+        return None;
+    }
+    let mut decoder = png::Decoder::new(std::io::Cursor::new(bytes));
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder.read_info().ok()?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut buffer).ok()?;
+    let channels = match frame.color_type {
+        png::ColorType::Grayscale => 1,
+        png::ColorType::GrayscaleAlpha => 2,
+        png::ColorType::Rgb => 3,
+        png::ColorType::Rgba => 4,
+        png::ColorType::Indexed => return None,
+    };
+    let (width, height) = (frame.width, frame.height);
+    let stride = frame.line_size;
+    let sample_width = width.min(BLURHASH_SAMPLE_EDGE);
+    let sample_height = height.min(BLURHASH_SAMPLE_EDGE);
+    let mut pixels = Vec::with_capacity((sample_width * sample_height) as usize);
+    for sy in 0..sample_height {
+        let y = (sy * height / sample_height) as usize;
+        for sx in 0..sample_width {
+            let x = (sx * width / sample_width) as usize;
+            let at = y * stride + x * channels;
+            let pixel = buffer.get(at..at + channels)?;
+            pixels.push(match channels {
+                1 | 2 => [pixel[0]; 3],
+                _ => [pixel[0], pixel[1], pixel[2]],
+            });
+        }
+    }
+    Some(encode_blurhash(&pixels, sample_width, sample_height))
+}
+
+fn srgb_to_linear(value: u8) -> f64 {
+    let v = f64::from(value) / 255.0;
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f64) -> u32 {
+    let v = value.clamp(0.0, 1.0);
+    if v <= 0.003_130_8 {
+        (v * 12.92 * 255.0 + 0.5) as u32
+    } else {
+        ((1.055 * v.powf(1.0 / 2.4) - 0.055) * 255.0 + 0.5) as u32
+    }
+}
+
+fn sign_pow(value: f64, exponent: f64) -> f64 {
+    value.abs().powf(exponent).copysign(value)
+}
+
+fn push_base83(hash: &mut String, value: u32, length: u32) {
+    for i in 1..=length {
+        let digit = (value / 83u32.pow(length - i)) % 83;
+        hash.push(BASE83_CHARS[digit as usize] as char);
+    }
+}
+
+/// Blurhash (https://blurha.sh) of row-major RGB `pixels`.
+fn encode_blurhash(pixels: &[[u8; 3]], width: u32, height: u32) -> String {
+    let mut factors = Vec::with_capacity((BLURHASH_X_COMPONENTS * BLURHASH_Y_COMPONENTS) as usize);
+    for j in 0..BLURHASH_Y_COMPONENTS {
+        for i in 0..BLURHASH_X_COMPONENTS {
+            let normalisation = if i == 0 && j == 0 { 1.0 } else { 2.0 };
+            let mut factor = [0.0f64; 3];
+            for y in 0..height {
+                for x in 0..width {
+                    let basis = (std::f64::consts::PI * f64::from(i) * f64::from(x)
+                        / f64::from(width))
+                    .cos()
+                        * (std::f64::consts::PI * f64::from(j) * f64::from(y) / f64::from(height))
+                            .cos();
+                    let pixel = pixels[(y * width + x) as usize];
+                    for (channel, value) in factor.iter_mut().zip(pixel) {
+                        *channel += basis * srgb_to_linear(value);
+                    }
+                }
+            }
+            let scale = normalisation / f64::from(width * height);
+            factors.push(factor.map(|channel| channel * scale));
+        }
+    }
+
+    let mut hash = String::new();
+    push_base83(
+        &mut hash,
+        (BLURHASH_X_COMPONENTS - 1) + (BLURHASH_Y_COMPONENTS - 1) * 9,
+        1,
+    );
+
+    let (dc, ac) = factors.split_first().expect("at least one component");
+    let maximum_value = if ac.is_empty() {
+        push_base83(&mut hash, 0, 1);
+        1.0
+    } else {
+        let actual_max = ac
+            .iter()
+            .flat_map(|factor| factor.iter())
+            .fold(0.0f64, |max, value| max.max(value.abs()));
+        let quantised_max = (actual_max * 166.0 - 0.5).floor().clamp(0.0, 82.0) as u32;
+        push_base83(&mut hash, quantised_max, 1);
+        f64::from(quantised_max + 1) / 166.0
+    };
+
+    let dc_value =
+        (linear_to_srgb(dc[0]) << 16) + (linear_to_srgb(dc[1]) << 8) + linear_to_srgb(dc[2]);
+    push_base83(&mut hash, dc_value, 4);
+    for factor in ac {
+        let quantise = |value: f64| {
+            (sign_pow(value / maximum_value, 0.5) * 9.0 + 9.5)
+                .floor()
+                .clamp(0.0, 18.0) as u32
+        };
+        let value = quantise(factor[0]) * 19 * 19 + quantise(factor[1]) * 19 + quantise(factor[2]);
+        push_base83(&mut hash, value, 2);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid_png(width: u32, height: u32, rgb: [u8; 3]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut bytes, width, height);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header().unwrap();
+            let data: Vec<u8> = (0..width * height).flat_map(|_| rgb).collect();
+            writer.write_image_data(&data).unwrap();
+        }
+        bytes
+    }
+
+    #[test]
+    fn png_metadata_has_dimensions_and_blurhash() {
+        let metadata = image_metadata("image/png", &solid_png(20, 10, [255, 0, 0])).unwrap();
+        assert_eq!(metadata.dim, "20x10");
+        let blurhash = metadata.blurhash.unwrap();
+        // 4x3 components: size flag, max AC, 4-char DC and 11 two-char ACs
+        assert_eq!(blurhash.len(), 28);
+        assert!(blurhash.starts_with('L'));
+    }
+
+    #[test]
+    fn header_dimensions_for_other_formats() {
+        let gif = b"GIF89a\x40\x01\xf0\x00rest";
+        assert_eq!(
+            image_metadata("image/gif", gif),
+            Some(ImageMetadata {
+                dim: "320x240".to_string(),
+                blurhash: None,
+            })
+        );
+
+        // SOI, APP0 (length 4), SOF0 with height 480, width 640
+        let jpeg = [
+            0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00, 0xFF, 0xC0, 0x00, 0x11, 0x08, 0x01,
+            0xE0, 0x02, 0x80, 0x03,
+        ];
+        let metadata = image_metadata("image/jpeg", &jpeg).unwrap();
+        assert_eq!(metadata.dim, "640x480");
+        assert_eq!(metadata.blurhash, None);
+
+        let mut webp = b"RIFF\0\0\0\0WEBPVP8X\x0a\0\0\0\0\0\0\0".to_vec();
+        webp.extend_from_slice(&[0x7F, 0x02, 0x00, 0xDF, 0x01, 0x00]);
+        assert_eq!(image_metadata("image/webp", &webp).unwrap().dim, "640x480");
+    }
+
+    #[test]
+    fn unsupported_or_corrupt_images_degrade_to_none() {
+        assert!(image_metadata("application/pdf", b"%PDF-1.7").is_none());
+        assert!(image_metadata("image/png", b"not a png").is_none());
+        assert!(image_metadata("image/svg+xml", b"<svg/>").is_none());
+    }
+}
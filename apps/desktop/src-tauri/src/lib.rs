@@ -1,12 +1,10 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 #[cfg(desktop)]
-use tauri::{
-    tray::{TrayIconBuilder, TrayIconEvent},
-};
-use tauri::{Emitter, Manager};
+use tauri::tray::{TrayIconBuilder, TrayIconEvent};
 #[cfg(desktop)]
 use tauri::WebviewWindow;
+use tauri::{Emitter, Manager};
 // use tauri_plugin_updater::UpdaterExt;
 use serde_json::json;
 use std::sync::Mutex;
@@ -14,56 +12,85 @@ use std::sync::Mutex;
 use tauri_plugin_deep_link::DeepLinkExt;
 // use tauri_plugin_shell::process::{CommandChild, CommandEvent};
 // use tauri_plugin_shell::ShellExt;
-mod net;
-mod native_keychain;
-mod keychain_session_envelope;
-mod protocol;
-mod profiles;
+mod account_index;
 mod active_session_leases;
-mod relay;
-mod session;
-mod upload;
-mod wallet;
-mod models;
 mod commands;
-mod update_channel;
 mod data_root;
+mod data_root_bind;
+pub mod dev_shell_freshness;
+#[cfg(not(target_os = "android"))]
+mod encrypted_key_file;
+mod image_meta;
+mod keychain_session_envelope;
+mod local_save_scan;
+mod logging;
+mod models;
+mod native_keychain;
+mod net;
 mod platform_biometric;
 mod profile_web_storage_harvest;
+mod profiles;
+mod protocol;
+mod relay;
+mod services;
+mod session;
 mod storage_at_rest_state;
-mod data_root_bind;
-mod windows_junction;
-mod local_save_scan;
+mod update_channel;
+mod upload;
+mod wallet;
 mod warmup;
-mod services;
-pub mod dev_shell_freshness;
+mod windows_junction;
 
-use profiles::DesktopProfileState;
 use active_session_leases::ActiveSessionLeaseState;
-use session::SessionState;
-use commands::warmup::DesktopWarmupState;
-use commands::tor::{load_tor_settings, start_tor};
 #[cfg(desktop)]
 use commands::tor::stop_tor_child;
+use commands::tor::{load_tor_settings, start_tor};
+use commands::warmup::DesktopWarmupState;
 #[cfg(desktop)]
 use commands::window::{capture_window_state, write_window_state};
+use models::sign_auth::SignAuthState;
+use profiles::DesktopProfileState;
+use services::relay_list::{load_saved_relays, load_subscription_settings};
+use services::sign_auth::{load_sign_auth_settings, load_timestamp_privacy};
+#[cfg(desktop)]
+use services::window::{
+    close_behavior_for_window, load_always_on_top_settings, load_close_behavior_settings, quit_app,
+};
+use session::SessionState;
 
 // Import window models
 #[cfg(desktop)]
 use models::window::{
-    WindowState,
-    PERSIST_WINDOW_STATE_IN_DEBUG,
-    sanitize_window_state,
-    is_reasonable_window_position,
-    reveal_desktop_window,
+    fit_window_state_to_monitors, is_reasonable_window_position, reveal_desktop_window,
+    sanitize_window_state, AlwaysOnTopState, CloseBehavior, CloseBehaviorState, MonitorBounds,
+    WindowState, PERSIST_WINDOW_STATE_IN_DEBUG, WINDOW_STATE_SAVE_DEBOUNCE_MS,
 };
 
 // Import tray models and services
+use models::tor::{TorRuntimeStatus, TorState};
 #[cfg(desktop)]
-use models::tray::{TrayCallState, TrayBadgeState, TRAY_ICON_ID, TRAY_MENU_SHOW_ID, TRAY_MENU_HIDE_ID, TRAY_MENU_ACCEPT_CALL_ID, TRAY_MENU_DECLINE_CALL_ID, TRAY_MENU_QUIT_ID};
+use models::tray::{
+    TrayBadgeState, TrayCallState, TRAY_ICON_ID, TRAY_MENU_ACCEPT_CALL_ID,
+    TRAY_MENU_DECLINE_CALL_ID, TRAY_MENU_HIDE_ID, TRAY_MENU_QUIT_ID, TRAY_MENU_SHOW_ID,
+};
 #[cfg(desktop)]
 use services::tray::*;
-use models::tor::{TorRuntimeStatus, TorState};
+
+// Import global shortcut models and services
+#[cfg(desktop)]
+use models::shortcut::GlobalShortcutState;
+#[cfg(desktop)]
+use services::shortcut::{load_shortcut_settings, register_toggle_window_shortcut};
+
+// Import launch-at-login models and services
+#[cfg(desktop)]
+use models::autostart::AUTOSTART_LAUNCH_ARG;
+#[cfg(desktop)]
+use models::theme::SystemThemeState;
+#[cfg(desktop)]
+use services::autostart::{launched_by_autostart, load_autostart_settings, set_autostart_enabled};
+#[cfg(desktop)]
+use services::theme::{emit_system_theme_if_changed, watch_system_theme};
 
 // Load window state from storage
 #[cfg(desktop)]
@@ -78,9 +105,39 @@ fn load_window_state(app: &tauri::AppHandle) -> Option<WindowState> {
     Some(sanitize_window_state(raw))
 }
 
+// Connected monitors, primary first, for validating a saved position
+#[cfg(desktop)]
+fn connected_monitor_bounds(window: &WebviewWindow) -> Vec<MonitorBounds> {
+    let primary_name = window
+        .primary_monitor()
+        .ok()
+        .flatten()
+        .and_then(|monitor| monitor.name().cloned());
+    let mut monitors: Vec<MonitorBounds> = window
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|monitor| MonitorBounds {
+            name: monitor.name().cloned(),
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+        })
+        .collect();
+    if let Some(index) = monitors
+        .iter()
+        .position(|monitor| monitor.name.is_some() && monitor.name == primary_name)
+    {
+        monitors.swap(0, index);
+    }
+    monitors
+}
+
 // Apply saved window state
 #[cfg(desktop)]
 fn apply_window_state(window: &WebviewWindow, state: WindowState) {
+    let state = fit_window_state_to_monitors(state, &connected_monitor_bounds(window));
     let _ = window.set_resizable(true);
     if state.maximized {
         let _ = window.maximize();
@@ -100,6 +157,7 @@ fn apply_window_state(window: &WebviewWindow, state: WindowState) {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    logging::init();
     let builder = tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_upload::init())
@@ -113,9 +171,26 @@ pub fn run() {
     #[cfg(mobile)]
     let builder = builder.plugin(tauri_plugin_store::Builder::new().build());
 
+    #[cfg(desktop)]
+    let builder = builder
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(
+            tauri_plugin_autostart::Builder::new()
+                .arg(AUTOSTART_LAUNCH_ARG)
+                .build(),
+        );
+
     builder
         .setup(|app| {
-            app.manage(relay::RelayPool::new());
+            native_keychain::init_keychain_service(&app.config().identifier);
+
+            let relay_pool = relay::RelayPool::new();
+            relay_pool.restore_saved_relays(load_saved_relays(app.handle()));
+            relay_pool.set_pause_subscriptions_when_hidden(
+                load_subscription_settings(app.handle()).pause_when_hidden,
+            );
+            app.manage(relay_pool);
+            relay::spawn_resume_watchdog(app.handle().clone());
             let settings = load_tor_settings(&app.handle());
 
             app.manage(net::NativeNetworkRuntime::new(
@@ -124,14 +199,21 @@ pub fn run() {
             ));
 
             // Manage SessionState
-            app.manage(SessionState::new());
+            let sign_auth_settings = load_sign_auth_settings(app.handle());
+            let session_state = SessionState::new();
+            session_state.set_idle_ttl_secs(sign_auth_settings.session_ttl_secs);
+            app.manage(session_state);
+            app.manage(SignAuthState::new(
+                sign_auth_settings,
+                load_timestamp_privacy(app.handle()),
+            ));
             app.manage(DesktopProfileState::new(&app.handle()));
             match ActiveSessionLeaseState::new(&app.handle()) {
                 Ok(lease_state) => {
                     app.manage(lease_state);
                 }
                 Err(error) => {
-                    eprintln!("[obscur] Failed to initialize active session leases: {error}");
+                    log::warn!("[obscur] Failed to initialize active session leases: {error}");
                 }
             }
             app.manage(storage_at_rest_state::StorageAtRestState::new());
@@ -147,7 +229,7 @@ pub fn run() {
                 .join("obscur.sqlite3");
             let db_state = commands::db::DbState::new_lazy(db_path);
             if let Err(error) = commands::db::bootstrap_sqlite_storage(&app.handle(), &db_state) {
-                eprintln!("[obscur] Failed to bootstrap sqlite storage: {error}");
+                log::error!("[obscur] Failed to bootstrap sqlite storage: {error}");
             }
             app.manage(db_state);
             app.manage(DesktopWarmupState::new());
@@ -166,12 +248,13 @@ pub fn run() {
                 child: Mutex::new(None),
                 settings: Mutex::new(settings.clone()),
                 runtime_status: Mutex::new(TorRuntimeStatus::Disconnected),
-                using_external_instance: Mutex::new(false),
+                using_external_instance: Mutex::new(settings.external_proxy),
                 logs: Mutex::new(Vec::new()),
+                failure_reason: Mutex::new(None),
             });
 
-            // Start Tor if enabled
-            if settings.enable_tor {
+            // Start Tor if enabled, unless the user runs their own proxy
+            if settings.enable_tor && !settings.external_proxy {
                 let handle = app.handle().clone();
                 tauri::async_runtime::spawn(async move {
                     let _ = start_tor(handle.clone(), handle.state()).await;
@@ -181,16 +264,14 @@ pub fn run() {
             // Create main window with proxy if enabled
             let app_data_dir = app.path().app_data_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
             let health = crate::data_root::assess_data_root_bind_health(&app_data_dir);
-            let main_data_dir = crate::data_root::resolve_webview_profile_workspace(
-                &app.handle(),
-                "default",
-            )
-            .unwrap_or_else(|error| {
-                eprintln!("[obscur] Failed to resolve webview profile workspace: {error}");
-                crate::data_root::recovery_webview_root(&app_data_dir)
-                    .join("profiles")
-                    .join("default")
-            });
+            let main_data_dir =
+                crate::data_root::resolve_webview_profile_workspace(&app.handle(), "default")
+                    .unwrap_or_else(|error| {
+                        log::warn!("[obscur] Failed to resolve webview profile workspace: {error}");
+                        crate::data_root::recovery_webview_root(&app_data_dir)
+                            .join("profiles")
+                            .join("default")
+                    });
 
             #[cfg(desktop)]
             let _window = {
@@ -203,17 +284,11 @@ pub fn run() {
                         .join("../../pwa/out");
                     match crate::dev_shell_freshness::read_shell_manifest_stamp(&out_dir) {
                         Ok(stamp) => {
-                            eprintln!(
-                                "[obscur] static-dev expected shell stamp: {stamp}"
-                            );
-                            crate::dev_shell_freshness::expected_shell_stamp_init_script(
-                                &stamp,
-                            )
+                            log::debug!("[obscur] static-dev expected shell stamp: {stamp}");
+                            crate::dev_shell_freshness::expected_shell_stamp_init_script(&stamp)
                         }
                         Err(error) => {
-                            eprintln!(
-                                "[obscur] static-dev shell stamp unavailable: {error}"
-                            );
+                            log::warn!("[obscur] static-dev shell stamp unavailable: {error}");
                             String::new()
                         }
                     }
@@ -239,12 +314,13 @@ pub fn run() {
                 .decorations(false)
                 .shadow(true); // We keep window shadow but remove OS border decorations
                 #[cfg(debug_assertions)]
-                let base_builder = if let Some(browser_args) = profiles::main_window_additional_browser_args() {
-                    eprintln!("[obscur] Main window CDP args: {browser_args}");
-                    base_builder.additional_browser_args(&browser_args)
-                } else {
-                    base_builder
-                };
+                let base_builder =
+                    if let Some(browser_args) = profiles::main_window_additional_browser_args() {
+                        log::debug!("[obscur] Main window CDP args: {browser_args}");
+                        base_builder.additional_browser_args(&browser_args)
+                    } else {
+                        base_builder
+                    };
                 #[cfg(debug_assertions)]
                 let window_builder = base_builder
                     .visible(true)
@@ -268,7 +344,7 @@ pub fn run() {
             tauri::async_runtime::spawn(async move {
                 let reset_state = reset_app.state::<DesktopProfileState>();
                 if let Err(error) = reset_state.reset_startup_window_bindings(&reset_app).await {
-                    eprintln!("[PROFILES] Startup window binding reset failed: {error}");
+                    log::warn!("[PROFILES] Startup window binding reset failed: {error}");
                 }
             });
             #[cfg(desktop)]
@@ -289,11 +365,7 @@ pub fn run() {
                     .menu(&menu)
                     .show_menu_on_left_click(false)
                     .on_menu_event(|app, event| match event.id.as_ref() {
-                        TRAY_MENU_QUIT_ID => {
-                            let state = app.state::<TorState>();
-                            let _ = stop_tor_child(&state);
-                            app.exit(0);
-                        }
+                        TRAY_MENU_QUIT_ID => quit_app(app),
                         TRAY_MENU_SHOW_ID => {
                             if let Some(window) = app.get_webview_window("main") {
                                 let _ = window.unminimize();
@@ -304,6 +376,7 @@ pub fn run() {
                         TRAY_MENU_HIDE_ID => {
                             if let Some(window) = app.get_webview_window("main") {
                                 let _ = window.hide();
+                                relay::on_window_visibility_changed(app, window.label(), false);
                             }
                         }
                         TRAY_MENU_ACCEPT_CALL_ID => {
@@ -337,24 +410,74 @@ pub fn run() {
                     .build(app)?;
             }
 
+            #[cfg(desktop)]
+            app.manage(CloseBehaviorState {
+                behavior: Mutex::new(load_close_behavior_settings(app.handle()).close_behavior),
+            });
+            #[cfg(desktop)]
+            app.manage(AlwaysOnTopState {
+                enabled: Mutex::new(load_always_on_top_settings(app.handle()).always_on_top),
+            });
+
+            // Register the show/hide global shortcut
+            #[cfg(desktop)]
+            {
+                app.manage(GlobalShortcutState {
+                    toggle_window: Mutex::new(None),
+                });
+                let shortcut_settings = load_shortcut_settings(app.handle());
+                if let Err(error) =
+                    register_toggle_window_shortcut(app.handle(), &shortcut_settings.toggle_window)
+                {
+                    log::warn!("[SHORTCUT] {error}");
+                }
+            }
+
+            // Re-register the login item so it tracks the current install location
+            #[cfg(desktop)]
+            {
+                if load_autostart_settings(app.handle()).enabled {
+                    if let Err(error) = set_autostart_enabled(app.handle(), true) {
+                        log::warn!("[AUTOSTART] Failed to refresh login item: {error}");
+                    }
+                }
+            }
+
             // Load and apply saved window state
             #[cfg(desktop)]
             {
                 if let Some(state) = load_window_state(&app.handle()) {
                     apply_window_state(&_window, state);
                 }
+                if *app.state::<AlwaysOnTopState>().enabled.lock().unwrap() {
+                    if let Err(error) = _window.set_always_on_top(true) {
+                        log::warn!("[WINDOW] Failed to restore always-on-top: {error}");
+                    }
+                }
 
-                reveal_desktop_window(&_window, "setup");
+                // Login launches stay in the tray until the user opens the window.
+                if launched_by_autostart() {
+                    log::info!("[WINDOW] Launched at login; keeping main window hidden");
+                } else {
+                    reveal_desktop_window(&_window, "setup");
 
-                let failsafe_window = _window.clone();
-                tauri::async_runtime::spawn(async move {
-                    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
-                    if failsafe_window.is_visible().unwrap_or(true) {
-                        return;
-                    }
-                    eprintln!("[WINDOW] Failsafe reveal for main window after startup");
-                    reveal_desktop_window(&failsafe_window, "failsafe");
-                });
+                    let failsafe_window = _window.clone();
+                    tauri::async_runtime::spawn(async move {
+                        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+                        if failsafe_window.is_visible().unwrap_or(true) {
+                            return;
+                        }
+                        log::info!("[WINDOW] Failsafe reveal for main window after startup");
+                        reveal_desktop_window(&failsafe_window, "failsafe");
+                    });
+                }
+            }
+
+            // Follow OS dark/light changes live
+            #[cfg(desktop)]
+            {
+                app.manage(SystemThemeState::default());
+                watch_system_theme(app.handle(), &_window);
             }
 
             // Save window state and intercept close
@@ -362,20 +485,74 @@ pub fn run() {
             {
                 let app_handle = app.handle().clone();
                 let window_clone = _window.clone();
+                // Bumped on every move/resize; a pending save only writes if no newer event arrived.
+                let geometry_generation = std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0));
                 _window.on_window_event(move |event| {
                     match event {
+                        tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) => {
+                            use std::sync::atomic::Ordering;
+                            let generation = geometry_generation.fetch_add(1, Ordering::SeqCst) + 1;
+                            let latest = geometry_generation.clone();
+                            let app_handle = app_handle.clone();
+                            let window = window_clone.clone();
+                            tauri::async_runtime::spawn(async move {
+                                tokio::time::sleep(std::time::Duration::from_millis(
+                                    WINDOW_STATE_SAVE_DEBOUNCE_MS,
+                                ))
+                                .await;
+                                if latest.load(Ordering::SeqCst) != generation {
+                                    return;
+                                }
+                                // Minimized and hidden windows report bogus geometry
+                                if window.is_minimized().unwrap_or(false)
+                                    || !window.is_visible().unwrap_or(true)
+                                {
+                                    return;
+                                }
+                                if let Ok(state) = capture_window_state(&window) {
+                                    let _ = write_window_state(&app_handle, window.label(), &state);
+                                }
+                            });
+                        }
                         tauri::WindowEvent::CloseRequested { api, .. } => {
+                            // Secondary windows close normally
+                            let Some(behavior) =
+                                close_behavior_for_window(&app_handle, window_clone.label())
+                            else {
+                                return;
+                            };
                             if let Ok(state) = capture_window_state(&window_clone) {
                                 let _ = write_window_state(&app_handle, window_clone.label(), &state);
                             }
+                            if behavior == CloseBehavior::Quit {
+                                quit_app(&app_handle);
+                                return;
+                            }
                             // Prevent the window from closing and hide it instead
                             api.prevent_close();
                             let _ = window_clone.hide();
+                            relay::on_window_visibility_changed(
+                                &app_handle,
+                                window_clone.label(),
+                                false,
+                            );
+                        }
+                        // Every path that shows the window also focuses it
+                        tauri::WindowEvent::Focused(true) => {
+                            relay::on_window_visibility_changed(
+                                &app_handle,
+                                window_clone.label(),
+                                true,
+                            );
                         }
                         tauri::WindowEvent::Destroyed => {
+                            relay::on_window_destroyed(&app_handle, window_clone.label());
                             let state = app_handle.state::<TorState>();
                             let _ = stop_tor_child(&state);
                         }
+                        tauri::WindowEvent::ThemeChanged(theme) => {
+                            emit_system_theme_if_changed(&app_handle, *theme);
+                        }
                         _ => {}
                     }
                 });
@@ -411,20 +588,33 @@ pub fn run() {
                     commands::window::window_minimize,
                     commands::window::window_maximize,
                     commands::window::window_unmaximize,
+                    commands::window::window_show,
+                    commands::window::window_hide,
                     commands::window::window_close,
+                    commands::window::get_close_behavior,
+                    commands::window::set_close_behavior,
                     commands::window::window_show_and_focus,
                     commands::window::window_reveal_current,
                     commands::window::desktop_agent_focus_window,
                     commands::window::window_is_maximized,
+                    commands::window::window_is_visible,
                     commands::window::window_set_fullscreen,
                     commands::window::window_is_fullscreen,
+                    commands::window::set_always_on_top,
+                    commands::window::is_always_on_top,
                     commands::window::save_window_state,
                     commands::tray::set_tray_unread_badge_count,
+                    commands::tray::set_badge_count,
                     commands::tray::set_tray_incoming_call_state,
                     commands::tray::desktop_get_incoming_call_state,
                     commands::tray::desktop_incoming_call_action,
+                    commands::shortcut::get_global_shortcut,
+                    commands::shortcut::set_global_shortcut,
+                    commands::autostart::set_autostart,
+                    commands::autostart::get_autostart,
                     commands::system::fetch_remote_text,
                     commands::system::fetch_remote_bytes,
+                    commands::media::fetch_media,
                     commands::system::mesh_http_fetch_via_socks,
                     commands::system::check_for_updates,
                     commands::system::install_update,
@@ -473,6 +663,8 @@ pub fn run() {
                     commands::session::init_native_session,
                     commands::session::clear_native_session,
                     commands::session::get_session_status,
+                    commands::session::list_stored_accounts,
+                    commands::session::touch_session,
                     commands::session::desktop_force_session_restore,
                     commands::auth_boot::auth_boot_snapshot,
                     commands::login_assist::auth_login_assist_read,
@@ -488,23 +680,88 @@ pub fn run() {
                     commands::les::desktop_les_delete,
                     upload::nip96_upload,
                     upload::nip96_upload_v2,
+                    upload::nip96_upload_file,
+                    upload::nip96_upload_batch,
+                    upload::nip96_delete,
+                    upload::sign_nip98,
+                    upload::nip96_cancel_upload,
                     relay::connect_relay,
+                    relay::connect_all,
+                    relay::connect_relays,
+                    relay::set_relay_summary_enabled,
+                    relay::set_relay_event_batching,
+                    relay::set_max_connections,
+                    relay::set_connect_concurrency,
+                    relay::pause_reconnects,
+                    relay::resume_reconnects,
+                    relay::force_reconnect,
+                    relay::force_reconnect_all,
                     relay::probe_relay,
+                    relay::test_relay,
                     relay::disconnect_relay,
+                    relay::disconnect_relay_drain,
                     relay::recycle_relays,
                     relay::publish_event,
                     relay::subscribe_relay,
                     relay::unsubscribe_relay,
+                    relay::pause_subscriptions,
+                    relay::resume_subscriptions,
+                    relay::set_pause_subscriptions_when_hidden,
+                    relay::get_pause_subscriptions_when_hidden,
+                    relay::page_subscription,
                     relay::send_relay_message,
+                    relay::send_relay_message_await,
+                    relay::fetch_events,
+                    relay::fetch_event_by_id,
+                    relay::fetch_events_paged,
+                    relay::get_relay_metrics,
+                    relay::get_subscription_stats,
+                    relay::get_all_relay_metrics,
+                    relay::set_relay_marker,
+                    relay::import_relays_from_nip65,
+                    relay::set_publish_buffer,
+                    relay::publish_to_write_relays,
+                    relay::publish_to_relays,
+                    relay::publish_event_multi,
+                    relay::set_relay_proxy,
+                    relay::clear_relay_proxy,
+                    relay::set_relay_tls_sni,
+                    relay::get_saved_relays,
+                    relay::save_relays,
                     wallet::get_native_npub,
+                    wallet::can_sign,
                     wallet::import_native_nsec,
+                    wallet::import_nsec_from_file,
+                    wallet::import_from_uri,
                     wallet::generate_native_nsec,
                     wallet::sign_event_native,
+                    wallet::create_delegation,
+                    wallet::build_handler_info_event,
+                    wallet::build_handler_recommendation_event,
+                    wallet::build_list_event,
+                    commands::nip19::nip19_encode,
+                    commands::nip19::nip19_decode,
+                    commands::nip19::npub_qr_svg,
+                    commands::sign_auth::set_sign_auth_settings,
+                    commands::sign_auth::set_require_biometric,
+                    commands::sign_auth::get_sign_auth_settings,
+                    commands::sign_auth::set_session_ttl,
+                    commands::sign_auth::set_timestamp_privacy,
+                    commands::sign_auth::get_timestamp_privacy,
                     wallet::logout_native,
+                    wallet::get_key_storage_status,
+                    wallet::check_keychain,
+                    wallet::store_key_in_encrypted_file,
+                    wallet::unlock_encrypted_key_file,
+                    wallet::migrate_keychain,
                     wallet::encrypt_nip04,
                     wallet::decrypt_nip04,
+                    wallet::compute_shared_secret,
                     wallet::encrypt_nip44,
                     wallet::decrypt_nip44,
+                    wallet::decrypt_auto,
+                    wallet::reencrypt_nip04_to_nip44,
+                    wallet::reencrypt_dm,
                     wallet::encrypt_gift_wrap,
                     wallet::decrypt_gift_wrap,
                     wallet::get_session_nsec,
@@ -513,9 +770,25 @@ pub fn run() {
                     commands::tor::get_tor_status,
                     commands::tor::get_tor_logs,
                     commands::tor::save_tor_settings,
+                    commands::network::set_upload_redirect_limit,
+                    commands::network::get_upload_redirect_limit,
+                    commands::network::set_upload_retry_limit,
+                    commands::network::get_upload_retry_limit,
+                    commands::network::set_max_frame_size,
+                    commands::network::set_doh_resolver,
+                    commands::network::set_bind_address,
+                    commands::network::set_http_identity,
+                    commands::network::get_http_identity,
+                    commands::network::set_handshake_headers,
+                    commands::network::get_handshake_headers,
                     commands::system::request_biometric_auth,
                     commands::system::get_biometric_capability,
                     commands::system::mine_pow,
+                    commands::diagnostics::export_diagnostics,
+                    commands::diagnostics::get_diagnostics,
+                    commands::logging::set_log_level,
+                    commands::logging::get_log_level,
+                    commands::logging::get_recent_logs,
                     protocol::protocol_get_identity_root_state,
                     protocol::protocol_get_session_state,
                     protocol::protocol_authorize_device,
@@ -563,11 +836,17 @@ pub fn run() {
             {
                 tauri::generate_handler![
                     commands::tray::set_tray_unread_badge_count,
+                    commands::tray::set_badge_count,
                     commands::tray::set_tray_incoming_call_state,
                     commands::tray::desktop_get_incoming_call_state,
                     commands::tray::desktop_incoming_call_action,
+                    commands::shortcut::get_global_shortcut,
+                    commands::shortcut::set_global_shortcut,
+                    commands::autostart::set_autostart,
+                    commands::autostart::get_autostart,
                     commands::system::fetch_remote_text,
                     commands::system::fetch_remote_bytes,
+                    commands::media::fetch_media,
                     commands::system::mesh_http_fetch_via_socks,
                     commands::system::check_for_updates,
                     commands::system::install_update,
@@ -616,6 +895,8 @@ pub fn run() {
                     commands::session::init_native_session,
                     commands::session::clear_native_session,
                     commands::session::get_session_status,
+                    commands::session::list_stored_accounts,
+                    commands::session::touch_session,
                     commands::session::desktop_force_session_restore,
                     commands::auth_boot::auth_boot_snapshot,
                     commands::login_assist::auth_login_assist_read,
@@ -631,23 +912,83 @@ pub fn run() {
                     commands::les::desktop_les_delete,
                     upload::nip96_upload,
                     upload::nip96_upload_v2,
+                    upload::nip96_upload_file,
+                    upload::nip96_upload_batch,
+                    upload::nip96_delete,
+                    upload::sign_nip98,
+                    upload::nip96_cancel_upload,
                     relay::connect_relay,
+                    relay::connect_all,
+                    relay::connect_relays,
+                    relay::set_relay_summary_enabled,
+                    relay::set_relay_event_batching,
+                    relay::set_max_connections,
+                    relay::set_connect_concurrency,
+                    relay::pause_reconnects,
+                    relay::resume_reconnects,
+                    relay::force_reconnect,
+                    relay::force_reconnect_all,
                     relay::probe_relay,
+                    relay::test_relay,
                     relay::disconnect_relay,
+                    relay::disconnect_relay_drain,
                     relay::recycle_relays,
                     relay::publish_event,
                     relay::subscribe_relay,
                     relay::unsubscribe_relay,
+                    relay::pause_subscriptions,
+                    relay::resume_subscriptions,
+                    relay::set_pause_subscriptions_when_hidden,
+                    relay::get_pause_subscriptions_when_hidden,
+                    relay::page_subscription,
                     relay::send_relay_message,
+                    relay::send_relay_message_await,
+                    relay::fetch_events,
+                    relay::fetch_event_by_id,
+                    relay::fetch_events_paged,
+                    relay::get_relay_metrics,
+                    relay::get_subscription_stats,
+                    relay::get_all_relay_metrics,
+                    relay::set_relay_marker,
+                    relay::import_relays_from_nip65,
+                    relay::set_publish_buffer,
+                    relay::publish_to_write_relays,
+                    relay::publish_to_relays,
+                    relay::publish_event_multi,
+                    relay::set_relay_proxy,
+                    relay::clear_relay_proxy,
+                    relay::set_relay_tls_sni,
+                    relay::get_saved_relays,
+                    relay::save_relays,
                     wallet::get_native_npub,
+                    wallet::can_sign,
                     wallet::import_native_nsec,
+                    wallet::import_nsec_from_file,
+                    wallet::import_from_uri,
                     wallet::generate_native_nsec,
                     wallet::sign_event_native,
+                    wallet::create_delegation,
+                    wallet::build_handler_info_event,
+                    wallet::build_handler_recommendation_event,
+                    wallet::build_list_event,
+                    commands::nip19::nip19_encode,
+                    commands::nip19::nip19_decode,
+                    commands::nip19::npub_qr_svg,
+                    commands::sign_auth::set_sign_auth_settings,
+                    commands::sign_auth::set_require_biometric,
+                    commands::sign_auth::get_sign_auth_settings,
+                    commands::sign_auth::set_session_ttl,
+                    commands::sign_auth::set_timestamp_privacy,
+                    commands::sign_auth::get_timestamp_privacy,
                     wallet::logout_native,
                     wallet::encrypt_nip04,
                     wallet::decrypt_nip04,
+                    wallet::compute_shared_secret,
                     wallet::encrypt_nip44,
                     wallet::decrypt_nip44,
+                    wallet::decrypt_auto,
+                    wallet::reencrypt_nip04_to_nip44,
+                    wallet::reencrypt_dm,
                     wallet::encrypt_gift_wrap,
                     wallet::decrypt_gift_wrap,
                     wallet::get_session_nsec,
@@ -656,9 +997,25 @@ pub fn run() {
                     commands::tor::get_tor_status,
                     commands::tor::get_tor_logs,
                     commands::tor::save_tor_settings,
+                    commands::network::set_upload_redirect_limit,
+                    commands::network::get_upload_redirect_limit,
+                    commands::network::set_upload_retry_limit,
+                    commands::network::get_upload_retry_limit,
+                    commands::network::set_max_frame_size,
+                    commands::network::set_doh_resolver,
+                    commands::network::set_bind_address,
+                    commands::network::set_http_identity,
+                    commands::network::get_http_identity,
+                    commands::network::set_handshake_headers,
+                    commands::network::get_handshake_headers,
                     commands::system::request_biometric_auth,
                     commands::system::get_biometric_capability,
                     commands::system::mine_pow,
+                    commands::diagnostics::export_diagnostics,
+                    commands::diagnostics::get_diagnostics,
+                    commands::logging::set_log_level,
+                    commands::logging::get_log_level,
+                    commands::logging::get_recent_logs,
                     protocol::protocol_get_identity_root_state,
                     protocol::protocol_get_session_state,
                     protocol::protocol_authorize_device,
@@ -0,0 +1,18 @@
+//! Launch-at-login models
+
+#[cfg(desktop)]
+use serde::{Deserialize, Serialize};
+
+/// Persisted launch-at-login preference
+#[cfg(desktop)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct AutostartSettings {
+    pub enabled: bool,
+}
+
+/// Autostart constants
+#[cfg(desktop)]
+pub const AUTOSTART_SETTINGS_FILE: &str = "autostart_settings.json";
+/// Passed on login launches so the app stays hidden in the tray
+#[cfg(desktop)]
+pub const AUTOSTART_LAUNCH_ARG: &str = "--autostart";
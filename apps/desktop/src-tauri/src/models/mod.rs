@@ -1,6 +1,11 @@
 // Shared models and types for desktop commands
 
 pub mod app;
-pub mod window;
-pub mod tray;
+pub mod autostart;
+pub mod relay_list;
+pub mod shortcut;
+pub mod sign_auth;
+pub mod theme;
 pub mod tor;
+pub mod tray;
+pub mod window;
@@ -0,0 +1,49 @@
+//! Persisted relay list models
+
+use crate::relay::RelayMarker;
+use serde::{Deserialize, Serialize};
+
+pub const RELAYS_FILE: &str = "relays.json";
+pub const SUBSCRIPTION_SETTINGS_FILE: &str = "subscription_settings.json";
+
+/// One relay the user wants connected, restored at startup
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedRelay {
+    pub url: String,
+    #[serde(default)]
+    pub marker: RelayMarker,
+    /// Per-relay proxy override: `"direct"` or a `socks5://` / `socks5h://` URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// TLS server name sent instead of the relay host (wss only), e.g. a CDN front
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_sni: Option<String>,
+}
+
+/// Persisted subscription preferences
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionSettings {
+    /// CLOSE a window's subscriptions while it is hidden to the tray, REQ again when shown
+    #[serde(default)]
+    pub pause_when_hidden: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_relay_defaults_marker_and_proxy() {
+        let relays: Vec<SavedRelay> =
+            serde_json::from_str(r#"[{"url":"wss://relay.example"},{"url":"wss://tor.example","marker":"write","proxy":"direct"}]"#)
+                .unwrap();
+        assert_eq!(relays[0].marker, RelayMarker::Both);
+        assert_eq!(relays[0].proxy, None);
+        assert_eq!(relays[1].marker, RelayMarker::Write);
+        assert_eq!(relays[1].proxy.as_deref(), Some("direct"));
+        let json = serde_json::to_string(&relays[0]).unwrap();
+        assert!(!json.contains("proxy"));
+    }
+}
@@ -0,0 +1,25 @@
+//! Global shortcut models
+
+#[cfg(desktop)]
+use serde::{Deserialize, Serialize};
+#[cfg(desktop)]
+use std::sync::Mutex;
+
+/// Persisted global shortcut settings
+#[cfg(desktop)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ShortcutSettings {
+    pub toggle_window: String,
+}
+
+/// Shortcut constants
+#[cfg(desktop)]
+pub const DEFAULT_TOGGLE_WINDOW_SHORTCUT: &str = "CommandOrControl+Shift+O";
+#[cfg(desktop)]
+pub const SHORTCUT_SETTINGS_FILE: &str = "shortcut_settings.json";
+
+/// Accelerator currently registered for toggling the main window
+#[cfg(desktop)]
+pub struct GlobalShortcutState {
+    pub toggle_window: Mutex<Option<String>>,
+}
@@ -0,0 +1,206 @@
+//! Sign-authorization models
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Default window during which one successful OS prompt covers further signs
+pub const DEFAULT_SIGN_AUTH_GRACE_SECS: u64 = 60;
+/// Upper bound for the grace period so an authorization cannot linger indefinitely
+pub const MAX_SIGN_AUTH_GRACE_SECS: u64 = 3600;
+pub const SIGN_AUTH_SETTINGS_FILE: &str = "sign_auth_settings.json";
+/// Error prefix returned when the OS prompt is cancelled or fails
+pub const AUTH_REQUIRED_CODE: &str = "AUTH_REQUIRED";
+pub const TIMESTAMP_PRIVACY_FILE: &str = "timestamp_privacy.json";
+/// Upper bound for timestamp jitter; wider skews get events rejected by relays
+pub const MAX_TIMESTAMP_JITTER_SECS: u64 = 300;
+
+/// Persisted preferences for gating native key use behind OS authentication
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SignAuthSettings {
+    #[serde(default)]
+    pub require_auth_to_sign: bool,
+    /// Prompt before the session secret is read back from the OS keychain
+    #[serde(default)]
+    pub require_biometric: bool,
+    #[serde(default = "default_grace_secs")]
+    pub grace_period_secs: u64,
+    /// Drop hydrated session keys after this long without use or `touch_session`,
+    /// so the keychain is read again; 0 keeps them until logout
+    #[serde(default)]
+    pub session_ttl_secs: u64,
+}
+
+fn default_grace_secs() -> u64 {
+    DEFAULT_SIGN_AUTH_GRACE_SECS
+}
+
+impl Default for SignAuthSettings {
+    fn default() -> Self {
+        Self {
+            require_auth_to_sign: false,
+            require_biometric: false,
+            grace_period_secs: DEFAULT_SIGN_AUTH_GRACE_SECS,
+            session_ttl_secs: 0,
+        }
+    }
+}
+
+/// How `created_at` is blurred before a native sign, so exact timestamps cannot
+/// correlate activity across relays or hint at the user's timezone
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(tag = "mode", rename_all = "camelCase")]
+pub enum TimestampPrivacy {
+    /// Sign the timestamp the client asked for
+    #[default]
+    Exact,
+    /// Truncate to the start of the minute (never moves an event into the future)
+    RoundToMinute,
+    /// Shift by a random offset in `-secs..=secs`
+    #[serde(rename_all = "camelCase")]
+    Jitter { secs: u64 },
+}
+
+impl TimestampPrivacy {
+    /// Apply the mode to `created_at`; `random` feeds the jitter offset
+    pub fn apply(self, created_at: u64, random: u64) -> u64 {
+        match self {
+            Self::Exact => created_at,
+            Self::RoundToMinute => created_at - created_at % 60,
+            Self::Jitter { secs } => {
+                let secs = secs.min(MAX_TIMESTAMP_JITTER_SECS);
+                let offset = random % (2 * secs + 1);
+                created_at.saturating_sub(secs).saturating_add(offset)
+            }
+        }
+    }
+}
+
+/// NIP-98 HTTP auth; servers reject it outside a short freshness window
+pub const HTTP_AUTH_KIND: u16 = 27235;
+/// NIP-42 relay auth; relays reject it outside a short freshness window
+pub const RELAY_AUTH_KIND: u16 = 22242;
+
+/// Whether the timestamp privacy mode may move `created_at` for `kind`. Auth events must
+/// carry the real time, replaceable and addressable events are ordered by it (a blurred
+/// one can lose to the version it replaces), and ephemeral events are never stored.
+pub fn timestamp_privacy_applies(kind: u16) -> bool {
+    let replaceable = kind == 0 || kind == 3 || (10_000..20_000).contains(&kind);
+    let ephemeral = (20_000..30_000).contains(&kind);
+    let addressable = (30_000..40_000).contains(&kind);
+    kind != HTTP_AUTH_KIND && kind != RELAY_AUTH_KIND && !replaceable && !ephemeral && !addressable
+}
+
+/// Sign-authorization state: current settings plus the last successful prompt,
+/// shared by the signing and keychain-unlock gates
+pub struct SignAuthState {
+    pub settings: Mutex<SignAuthSettings>,
+    pub last_authorized: Mutex<Option<Instant>>,
+    pub timestamp_privacy: Mutex<TimestampPrivacy>,
+}
+
+impl SignAuthState {
+    pub fn new(settings: SignAuthSettings, timestamp_privacy: TimestampPrivacy) -> Self {
+        Self {
+            settings: Mutex::new(settings),
+            last_authorized: Mutex::new(None),
+            timestamp_privacy: Mutex::new(timestamp_privacy),
+        }
+    }
+}
+
+/// Whether an authorization obtained at `authorized_at` still covers a sign at `now`
+pub fn within_grace_period(
+    authorized_at: Option<Instant>,
+    grace_period_secs: u64,
+    now: Instant,
+) -> bool {
+    match authorized_at {
+        Some(at) => now.saturating_duration_since(at) < Duration::from_secs(grace_period_secs),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grace_period_covers_recent_authorization_only() {
+        let now = Instant::now();
+        assert!(!within_grace_period(None, 60, now));
+        assert!(within_grace_period(
+            Some(now),
+            60,
+            now + Duration::from_secs(59)
+        ));
+        assert!(!within_grace_period(
+            Some(now),
+            60,
+            now + Duration::from_secs(60)
+        ));
+        assert!(!within_grace_period(Some(now), 0, now));
+    }
+
+    #[test]
+    fn settings_default_missing_fields() {
+        let settings: SignAuthSettings = serde_json::from_str("{}").unwrap();
+        assert!(!settings.require_auth_to_sign);
+        assert!(!settings.require_biometric);
+        assert_eq!(settings.grace_period_secs, DEFAULT_SIGN_AUTH_GRACE_SECS);
+        assert_eq!(settings.session_ttl_secs, 0);
+    }
+
+    #[test]
+    fn timestamp_privacy_modes() {
+        let at = 1_700_000_059;
+        assert_eq!(TimestampPrivacy::Exact.apply(at, 7), at);
+        assert_eq!(TimestampPrivacy::RoundToMinute.apply(at, 7), 1_700_000_040);
+        let jitter = TimestampPrivacy::Jitter { secs: 30 };
+        assert_eq!(jitter.apply(at, 0), at - 30);
+        assert_eq!(jitter.apply(at, 60), at + 30);
+        assert_eq!(jitter.apply(at, 61), at - 30);
+        let capped = TimestampPrivacy::Jitter { secs: 10_000 }.apply(at, u64::MAX);
+        assert!(capped.abs_diff(at) <= MAX_TIMESTAMP_JITTER_SECS);
+        let parsed: TimestampPrivacy =
+            serde_json::from_str(r#"{"mode":"jitter","secs":5}"#).unwrap();
+        assert_eq!(parsed, TimestampPrivacy::Jitter { secs: 5 });
+    }
+
+    #[test]
+    fn timestamp_privacy_blurs_regular_events() {
+        assert!(timestamp_privacy_applies(1));
+        assert!(timestamp_privacy_applies(4));
+        assert!(timestamp_privacy_applies(14));
+        assert!(timestamp_privacy_applies(9_735));
+    }
+
+    #[test]
+    fn timestamp_privacy_skips_auth_events() {
+        assert!(!timestamp_privacy_applies(HTTP_AUTH_KIND));
+        assert!(!timestamp_privacy_applies(RELAY_AUTH_KIND));
+    }
+
+    #[test]
+    fn timestamp_privacy_skips_replaceable_events() {
+        for kind in [0, 3, 10_000, 10_002, 19_999] {
+            assert!(!timestamp_privacy_applies(kind), "kind {kind}");
+        }
+    }
+
+    #[test]
+    fn timestamp_privacy_skips_ephemeral_events() {
+        for kind in [20_000, 24_133, 29_999] {
+            assert!(!timestamp_privacy_applies(kind), "kind {kind}");
+        }
+    }
+
+    #[test]
+    fn timestamp_privacy_skips_addressable_events() {
+        for kind in [30_000, 30_023, 39_999] {
+            assert!(!timestamp_privacy_applies(kind), "kind {kind}");
+        }
+        assert!(timestamp_privacy_applies(40_000));
+    }
+}
@@ -0,0 +1,18 @@
+//! System theme models
+
+#[cfg(desktop)]
+use std::sync::Mutex;
+
+/// Event emitted with `"dark"` or `"light"` when the OS theme flips
+#[cfg(desktop)]
+pub const SYSTEM_THEME_CHANGED_EVENT: &str = "system-theme-changed";
+/// Fallback poll interval for platforms where the native theme signal is unreliable
+#[cfg(desktop)]
+pub const SYSTEM_THEME_POLL_INTERVAL_SECS: u64 = 5;
+
+/// Last theme reported to the frontend, shared by the native signal and the poll
+#[cfg(desktop)]
+#[derive(Default)]
+pub struct SystemThemeState {
+    pub last: Mutex<Option<&'static str>>,
+}
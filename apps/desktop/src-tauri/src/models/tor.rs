@@ -4,11 +4,25 @@ use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
 use tauri_plugin_shell::process::CommandChild;
 
+/// Seconds the bundled Tor gets to reach "Bootstrapped 100%" before it is stopped
+pub const DEFAULT_TOR_BOOTSTRAP_TIMEOUT_SECS: u64 = 60;
+
+fn default_bootstrap_timeout_secs() -> u64 {
+    DEFAULT_TOR_BOOTSTRAP_TIMEOUT_SECS
+}
+
 /// Tor configuration settings
 #[derive(Serialize, Deserialize, Clone)]
 pub struct TorSettings {
     pub enable_tor: bool,
     pub proxy_url: String,
+    /// Route through `proxy_url` as-is (system Tor, I2P, any SOCKS proxy) without
+    /// launching the bundled Tor sidecar
+    #[serde(default)]
+    pub external_proxy: bool,
+    /// 0 waits for bootstrap indefinitely
+    #[serde(default = "default_bootstrap_timeout_secs")]
+    pub bootstrap_timeout_secs: u64,
 }
 
 /// Tor runtime status
@@ -20,6 +34,8 @@ pub enum TorRuntimeStatus {
     Connected,
     Error,
     Stopped,
+    /// Bootstrap did not finish in time; see `TorStatusSnapshot::failure_reason`
+    Failed,
 }
 
 /// Tor status snapshot for UI
@@ -31,6 +47,8 @@ pub struct TorStatusSnapshot {
     pub ready: bool,
     pub using_external_instance: bool,
     pub proxy_url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<String>,
 }
 
 /// Tor process state
@@ -40,4 +58,6 @@ pub struct TorState {
     pub runtime_status: Mutex<TorRuntimeStatus>,
     pub using_external_instance: Mutex<bool>,
     pub logs: Mutex<Vec<String>>,
+    /// Why the last start failed; cleared on the next status change
+    pub failure_reason: Mutex<Option<String>>,
 }
@@ -3,6 +3,8 @@
 #[cfg(desktop)]
 use serde::{Deserialize, Serialize};
 #[cfg(desktop)]
+use std::sync::Mutex;
+#[cfg(desktop)]
 use tauri::WebviewWindow;
 
 /// Window state for persistence
@@ -14,6 +16,76 @@ pub struct WindowState {
     pub width: u32,
     pub height: u32,
     pub maximized: bool,
+    /// Name of the monitor the window was on when saved
+    #[serde(default)]
+    pub monitor: Option<String>,
+}
+
+/// What closing the main window does
+#[cfg(desktop)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CloseBehavior {
+    /// Hide to the tray and keep running in the background
+    #[default]
+    Tray,
+    /// Exit the app
+    Quit,
+}
+
+#[cfg(desktop)]
+impl CloseBehavior {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim() {
+            "tray" => Ok(Self::Tray),
+            "quit" => Ok(Self::Quit),
+            other => Err(format!("Unknown close behavior: {other}")),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Tray => "tray",
+            Self::Quit => "quit",
+        }
+    }
+}
+
+/// Persisted close behavior preference
+#[cfg(desktop)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CloseBehaviorSettings {
+    pub close_behavior: CloseBehavior,
+}
+
+/// Close behavior currently applied to the main window
+#[cfg(desktop)]
+pub struct CloseBehaviorState {
+    pub behavior: Mutex<CloseBehavior>,
+}
+
+/// Persisted always-on-top preference for the main window
+#[cfg(desktop)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct AlwaysOnTopSettings {
+    pub always_on_top: bool,
+}
+
+/// Always-on-top preference; while fullscreen it is stored but not applied
+#[cfg(desktop)]
+pub struct AlwaysOnTopState {
+    pub enabled: Mutex<bool>,
+}
+
+/// Physical bounds of a connected monitor
+#[cfg(desktop)]
+#[derive(Debug, Clone)]
+pub struct MonitorBounds {
+    pub name: Option<String>,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
 }
 
 /// Window constants
@@ -37,6 +109,16 @@ pub const MAX_REASONABLE_WINDOW_HEIGHT: u32 = 8192;
 pub const MAX_REASONABLE_POSITION_ABS: i32 = 20_000;
 #[cfg(desktop)]
 pub const PERSIST_WINDOW_STATE_IN_DEBUG: bool = false;
+#[cfg(desktop)]
+pub const CLOSE_BEHAVIOR_SETTINGS_FILE: &str = "close_behavior_settings.json";
+#[cfg(desktop)]
+pub const ALWAYS_ON_TOP_SETTINGS_FILE: &str = "always_on_top_settings.json";
+/// Quiet period after the last move/resize before geometry is written to disk
+#[cfg(desktop)]
+pub const WINDOW_STATE_SAVE_DEBOUNCE_MS: u64 = 500;
+/// How much of the window must overlap a monitor for a saved position to count as visible
+#[cfg(desktop)]
+pub const MIN_VISIBLE_WINDOW_EDGE: i64 = 100;
 
 /// Validates window position is reasonable
 #[cfg(desktop)]
@@ -57,6 +139,51 @@ pub fn sanitize_window_state(state: WindowState) -> WindowState {
             .height
             .clamp(MIN_WINDOW_HEIGHT, MAX_REASONABLE_WINDOW_HEIGHT),
         maximized: state.maximized,
+        monitor: state.monitor,
+    }
+}
+
+/// Area of the window rectangle that falls on `monitor`
+#[cfg(desktop)]
+fn visible_overlap(state: &WindowState, monitor: &MonitorBounds) -> (i64, i64) {
+    let left = (state.x as i64).max(monitor.x as i64);
+    let top = (state.y as i64).max(monitor.y as i64);
+    let right = (state.x as i64 + state.width as i64).min(monitor.x as i64 + monitor.width as i64);
+    let bottom =
+        (state.y as i64 + state.height as i64).min(monitor.y as i64 + monitor.height as i64);
+    ((right - left).max(0), (bottom - top).max(0))
+}
+
+/// Keep a saved position when enough of the window lands on a connected monitor;
+/// otherwise center it on the monitor it was saved on (or `monitors[0]`, the primary),
+/// shrinking it to fit. Returns the state unchanged when no monitors are known.
+#[cfg(desktop)]
+pub fn fit_window_state_to_monitors(state: WindowState, monitors: &[MonitorBounds]) -> WindowState {
+    let visible = monitors.iter().any(|monitor| {
+        let (width, height) = visible_overlap(&state, monitor);
+        width >= MIN_VISIBLE_WINDOW_EDGE && height >= MIN_VISIBLE_WINDOW_EDGE
+    });
+    if visible {
+        return state;
+    }
+    let saved_monitor = state.monitor.as_deref().and_then(|name| {
+        monitors
+            .iter()
+            .find(|monitor| monitor.name.as_deref() == Some(name))
+    });
+    let Some(target) = saved_monitor.or(monitors.first()) else {
+        return state;
+    };
+
+    let width = state.width.min(target.width);
+    let height = state.height.min(target.height);
+    WindowState {
+        x: target.x + ((target.width - width) / 2) as i32,
+        y: target.y + ((target.height - height) / 2) as i32,
+        width,
+        height,
+        maximized: state.maximized,
+        monitor: target.name.clone(),
     }
 }
 
@@ -64,12 +191,61 @@ pub fn sanitize_window_state(state: WindowState) -> WindowState {
 #[cfg(desktop)]
 pub fn reveal_desktop_window(window: &WebviewWindow, context: &str) {
     if let Err(error) = window.unminimize() {
-        eprintln!("[WINDOW] unminimize failed ({context}): {error}");
+        log::warn!("[WINDOW] unminimize failed ({context}): {error}");
     }
     if let Err(error) = window.show() {
-        eprintln!("[WINDOW] show failed ({context}): {error}");
+        log::warn!("[WINDOW] show failed ({context}): {error}");
     }
     if let Err(error) = window.set_focus() {
-        eprintln!("[WINDOW] set_focus failed ({context}): {error}");
+        log::warn!("[WINDOW] set_focus failed ({context}): {error}");
+    }
+}
+
+#[cfg(all(test, desktop))]
+mod tests {
+    use super::*;
+
+    fn state(x: i32, y: i32, monitor: Option<&str>) -> WindowState {
+        WindowState {
+            x,
+            y,
+            width: 1200,
+            height: 800,
+            maximized: false,
+            monitor: monitor.map(str::to_string),
+        }
+    }
+
+    fn monitor(name: &str, x: i32, width: u32) -> MonitorBounds {
+        MonitorBounds {
+            name: Some(name.to_string()),
+            x,
+            y: 0,
+            width,
+            height: 1080,
+        }
+    }
+
+    #[test]
+    fn visible_position_is_kept() {
+        let monitors = [monitor("laptop", 0, 1920), monitor("dock", 1920, 2560)];
+        let fitted = fit_window_state_to_monitors(state(2200, 100, Some("dock")), &monitors);
+        assert_eq!((fitted.x, fitted.y), (2200, 100));
+    }
+
+    #[test]
+    fn position_on_disconnected_monitor_is_centered_on_primary() {
+        let monitors = [monitor("laptop", 0, 1920)];
+        let fitted = fit_window_state_to_monitors(state(2200, 100, Some("dock")), &monitors);
+        assert_eq!((fitted.x, fitted.y), (360, 140));
+        assert_eq!(fitted.monitor.as_deref(), Some("laptop"));
+    }
+
+    #[test]
+    fn offscreen_window_returns_to_its_saved_monitor_and_shrinks_to_fit() {
+        let monitors = [monitor("laptop", 0, 1920), monitor("small", 1920, 1024)];
+        let fitted = fit_window_state_to_monitors(state(-5000, -5000, Some("small")), &monitors);
+        assert_eq!((fitted.x, fitted.width), (1920, 1024));
+        assert_eq!(fitted.monitor.as_deref(), Some("small"));
     }
 }
@@ -10,10 +10,228 @@ use keyring::Entry;
 #[cfg(not(target_os = "android"))]
 use zeroize::Zeroizing;
 
+/// Keychain service of the stable build; other bundle identifiers get their own service.
 pub const APP_SERVICE: &str = "app.obscur.desktop";
+const KEYCHAIN_SERVICE_ENV_VAR: &str = "OBSCUR_KEYCHAIN_SERVICE";
+static KEYCHAIN_SERVICE: std::sync::OnceLock<String> = std::sync::OnceLock::new();
 const KEY_NAME: &str = "nsec";
 const PDK_KEY_NAME: &str = "pdk";
 const LOGIN_ASSIST_KEY_NAME: &str = "login_assist";
+#[cfg(not(target_os = "android"))]
+const AVAILABILITY_PROBE_KEY_NAME: &str = "availability_probe";
+
+/// Fix the keychain service for this process. `OBSCUR_KEYCHAIN_SERVICE` wins; otherwise the
+/// bundle identifier is used, so a beta build never reads or clobbers the stable build's keys.
+/// Must run before the first keychain access; later calls are ignored.
+pub fn init_keychain_service(bundle_identifier: &str) {
+    let override_service = std::env::var(KEYCHAIN_SERVICE_ENV_VAR).ok();
+    let service = match override_service.as_deref().map(str::trim) {
+        Some(service) if !service.is_empty() => service.to_string(),
+        _ if !bundle_identifier.trim().is_empty() => bundle_identifier.trim().to_string(),
+        _ => APP_SERVICE.to_string(),
+    };
+    if KEYCHAIN_SERVICE.set(service).is_err() {
+        log::info!("[SESSION] Keychain service already initialized; ignoring re-init");
+    }
+}
+
+/// Keychain service for every entry this build reads or writes.
+pub fn keychain_service() -> &'static str {
+    KEYCHAIN_SERVICE
+        .get()
+        .map(String::as_str)
+        .unwrap_or(APP_SERVICE)
+}
+
+/// Why a keychain access failed, so the UI can tell "import a key" from "unlock your keyring".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeychainErrorKind {
+    /// Nothing stored for this profile
+    NoEntry,
+    /// The store exists but is locked or access was denied
+    Locked,
+    /// No keyring service is running (e.g. no Secret Service on a minimal Linux install)
+    NoBackend,
+    PlatformFailure,
+}
+
+impl KeychainErrorKind {
+    const ALL: [KeychainErrorKind; 4] = [
+        KeychainErrorKind::NoEntry,
+        KeychainErrorKind::Locked,
+        KeychainErrorKind::NoBackend,
+        KeychainErrorKind::PlatformFailure,
+    ];
+
+    /// Stable prefix carried by `String` errors from this module
+    pub fn code(self) -> &'static str {
+        match self {
+            KeychainErrorKind::NoEntry => "KEYCHAIN_NO_ENTRY",
+            KeychainErrorKind::Locked => "KEYCHAIN_LOCKED",
+            KeychainErrorKind::NoBackend => "KEYCHAIN_NO_BACKEND",
+            KeychainErrorKind::PlatformFailure => "KEYCHAIN_PLATFORM_FAILURE",
+        }
+    }
+
+    /// Recover the kind from an error string produced by this module
+    pub fn from_error_message(message: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| message.starts_with(kind.code()))
+    }
+}
+
+/// `keychain-access-failed` event payload
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeychainAccessFailed {
+    pub profile_id: String,
+    pub kind: KeychainErrorKind,
+    pub message: String,
+}
+
+/// Secret Service / D-Bus failures that mean no keyring daemon is reachable at all.
+#[cfg(not(target_os = "android"))]
+const NO_BACKEND_MARKERS: [&str; 5] = [
+    "org.freedesktop.secrets",
+    "serviceunknown",
+    "not provided by any .service files",
+    "dbus_session_bus_address",
+    "failed to connect to socket",
+];
+
+#[cfg(not(target_os = "android"))]
+pub fn classify_keyring_error(error: &keyring::Error) -> KeychainErrorKind {
+    match error {
+        keyring::Error::NoEntry => KeychainErrorKind::NoEntry,
+        keyring::Error::NoStorageAccess(_) => KeychainErrorKind::Locked,
+        keyring::Error::PlatformFailure(inner) => {
+            let detail = inner.to_string().to_lowercase();
+            if NO_BACKEND_MARKERS
+                .iter()
+                .any(|marker| detail.contains(marker))
+            {
+                KeychainErrorKind::NoBackend
+            } else {
+                KeychainErrorKind::PlatformFailure
+            }
+        }
+        _ => KeychainErrorKind::PlatformFailure,
+    }
+}
+
+#[cfg(not(target_os = "android"))]
+pub(crate) fn keyring_error_message(error: &keyring::Error) -> String {
+    format!("{}: {}", classify_keyring_error(error).code(), error)
+}
+
+/// Whether a persistent keyring store answers at all; a locked store still counts as
+/// available. Shares `check_keychain`'s probe so both always agree.
+#[cfg(not(target_os = "android"))]
+pub fn keychain_backend_available() -> bool {
+    let check = check_keychain();
+    check.available || check.error_kind == Some(KeychainErrorKind::Locked)
+}
+
+/// Outcome of `check_keychain`'s set/get/delete round-trip
+#[cfg(not(target_os = "android"))]
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeychainCheck {
+    pub available: bool,
+    /// Store the keyring crate was built against, e.g. "secret-service" or "mock"
+    pub backend: &'static str,
+    pub error_kind: Option<KeychainErrorKind>,
+    pub message: Option<String>,
+}
+
+#[cfg(not(target_os = "android"))]
+impl KeychainCheck {
+    fn failed(backend: &'static str, kind: KeychainErrorKind, message: String) -> Self {
+        KeychainCheck {
+            available: false,
+            backend,
+            error_kind: Some(kind),
+            message: Some(message),
+        }
+    }
+}
+
+/// Name of the store behind `Entry::new`. The mock store keeps secrets inside the entry
+/// only, so nothing written there survives a restart.
+#[cfg(not(target_os = "android"))]
+fn keychain_backend_name() -> &'static str {
+    use keyring::credential::CredentialPersistence;
+    match keyring::default::default_credential_builder().persistence() {
+        CredentialPersistence::EntryOnly | CredentialPersistence::ProcessOnly => "mock",
+        CredentialPersistence::UntilReboot => "keyutils",
+        _ if cfg!(target_os = "macos") => "macos-keychain",
+        _ if cfg!(target_os = "ios") => "ios-keychain",
+        _ if cfg!(target_os = "windows") => "windows-credential-manager",
+        _ if cfg!(target_os = "linux") => "secret-service",
+        _ => "unknown",
+    }
+}
+
+/// Write, read back and delete a throwaway entry to tell whether keys can be kept in the
+/// OS keychain at all. Meant for startup, so a missing Secret Service surfaces as a setup
+/// message (with the encrypted-file fallback) instead of a failure inside `ensure_session`.
+#[cfg(not(target_os = "android"))]
+pub fn check_keychain() -> KeychainCheck {
+    let backend = keychain_backend_name();
+    if backend == "mock" {
+        return KeychainCheck::failed(
+            backend,
+            KeychainErrorKind::NoBackend,
+            format!(
+                "{}: This build has no persistent keychain store",
+                KeychainErrorKind::NoBackend.code()
+            ),
+        );
+    }
+    let probe = match Entry::new(keychain_service(), AVAILABILITY_PROBE_KEY_NAME) {
+        Ok(entry) => entry,
+        Err(e) => {
+            return KeychainCheck::failed(
+                backend,
+                classify_keyring_error(&e),
+                keyring_error_message(&e),
+            )
+        }
+    };
+    let value = format!("probe-{}", std::process::id());
+    if let Err(e) = write_password(&probe, &value) {
+        return KeychainCheck::failed(
+            backend,
+            classify_keyring_error(&e),
+            keyring_error_message(&e),
+        );
+    }
+    let read_back = read_password(&probe);
+    let _ = delete_entry(&probe);
+    match read_back {
+        Ok(stored) if stored == value => KeychainCheck {
+            available: true,
+            backend,
+            error_kind: None,
+            message: None,
+        },
+        Ok(_) => KeychainCheck::failed(
+            backend,
+            KeychainErrorKind::PlatformFailure,
+            format!(
+                "{}: Keychain probe did not round-trip",
+                KeychainErrorKind::PlatformFailure.code()
+            ),
+        ),
+        Err(e) => KeychainCheck::failed(
+            backend,
+            classify_keyring_error(&e),
+            keyring_error_message(&e),
+        ),
+    }
+}
 
 pub fn key_name_for_profile(profile_id: &str) -> String {
     format!("{KEY_NAME}::{profile_id}")
@@ -33,26 +251,26 @@ use std::collections::HashMap;
 use std::sync::{LazyLock, Mutex};
 
 #[cfg(not(target_os = "android"))]
-static LOGIN_ASSIST_CACHE: LazyLock<Mutex<HashMap<String, String>>> =
+static LOGIN_ASSIST_CACHE: LazyLock<Mutex<HashMap<String, Zeroizing<String>>>> =
     LazyLock::new(|| Mutex::new(HashMap::new()));
 
 #[cfg(not(target_os = "android"))]
-static SESSION_SECRET_CACHE: LazyLock<Mutex<HashMap<String, String>>> =
+static SESSION_SECRET_CACHE: LazyLock<Mutex<HashMap<String, Zeroizing<String>>>> =
     LazyLock::new(|| Mutex::new(HashMap::new()));
 
 #[cfg(not(target_os = "android"))]
-static PDK_SECRET_CACHE: LazyLock<Mutex<HashMap<String, String>>> =
+static PDK_SECRET_CACHE: LazyLock<Mutex<HashMap<String, Zeroizing<String>>>> =
     LazyLock::new(|| Mutex::new(HashMap::new()));
 
 #[cfg(not(target_os = "android"))]
 fn remember_session_secret_payload(profile_id: &str, secret: &str) {
     if let Ok(mut cache) = SESSION_SECRET_CACHE.lock() {
-        cache.insert(profile_id.to_string(), secret.to_string());
+        cache.insert(profile_id.to_string(), Zeroizing::new(secret.to_string()));
     }
 }
 
 #[cfg(not(target_os = "android"))]
-fn cached_session_secret_payload(profile_id: &str) -> Option<String> {
+fn cached_session_secret_payload(profile_id: &str) -> Option<Zeroizing<String>> {
     SESSION_SECRET_CACHE
         .lock()
         .ok()
@@ -67,22 +285,26 @@ fn forget_session_secret_payload(profile_id: &str) {
 }
 
 #[cfg(not(target_os = "android"))]
-fn decode_stored_session_payload(profile_id: &str, stored: &str) -> Result<Option<String>, String> {
+fn decode_stored_session_payload(
+    profile_id: &str,
+    stored: &str,
+) -> Result<Option<Zeroizing<String>>, String> {
     if keychain_session_envelope::is_wrapped_keychain_payload(stored) {
-        return keychain_session_envelope::unwrap_session_secret_from_keychain(profile_id, stored);
+        return keychain_session_envelope::unwrap_session_secret_from_keychain(profile_id, stored)
+            .map(|secret| secret.map(Zeroizing::new));
     }
     if keychain_session_envelope::is_legacy_plaintext_keychain_secret(stored) {
         let secret_zero = Zeroizing::new(stored.trim().to_string());
         let wrapped =
             keychain_session_envelope::wrap_session_secret_for_keychain(profile_id, &secret_zero)?;
-        let canonical =
-            Entry::new(APP_SERVICE, &key_name_for_profile(profile_id)).map_err(|e| e.to_string())?;
-        write_password(&canonical, &wrapped).map_err(|e| e.to_string())?;
-        eprintln!(
+        let canonical = Entry::new(keychain_service(), &key_name_for_profile(profile_id))
+            .map_err(|e| keyring_error_message(&e))?;
+        write_password(&canonical, &wrapped).map_err(|e| keyring_error_message(&e))?;
+        log::info!(
             "[SESSION] Migrated plaintext keychain entry to wrapped envelope for profile {}",
             profile_id
         );
-        return Ok(Some(secret_zero.to_string()));
+        return Ok(Some(secret_zero));
     }
     Ok(None)
 }
@@ -100,12 +322,12 @@ pub fn pubkey_hex_from_stored_keychain_payload(_payload: &str) -> Option<String>
 #[cfg(not(target_os = "android"))]
 fn remember_login_assist_payload(profile_id: &str, payload: &str) {
     if let Ok(mut cache) = LOGIN_ASSIST_CACHE.lock() {
-        cache.insert(profile_id.to_string(), payload.to_string());
+        cache.insert(profile_id.to_string(), Zeroizing::new(payload.to_string()));
     }
 }
 
 #[cfg(not(target_os = "android"))]
-fn cached_login_assist_payload(profile_id: &str) -> Option<String> {
+fn cached_login_assist_payload(profile_id: &str) -> Option<Zeroizing<String>> {
     LOGIN_ASSIST_CACHE
         .lock()
         .ok()
@@ -140,12 +362,14 @@ fn delete_entry(entry: &Entry) -> Result<(), keyring::Error> {
 }
 
 /// Read nsec for `profile_id`, migrating a legacy keychain entry when found.
+/// The secret is returned in a `Zeroizing` buffer so callers wipe it on drop.
 #[cfg(not(target_os = "android"))]
-pub fn read_nsec_for_profile(profile_id: &str) -> Result<Option<String>, String> {
+pub fn read_nsec_for_profile(profile_id: &str) -> Result<Option<Zeroizing<String>>, String> {
     if let Some(cached) = cached_session_secret_payload(profile_id) {
         return Ok(Some(cached));
     }
-    let canonical = Entry::new(APP_SERVICE, &key_name_for_profile(profile_id)).map_err(|e| e.to_string())?;
+    let canonical = Entry::new(keychain_service(), &key_name_for_profile(profile_id))
+        .map_err(|e| keyring_error_message(&e))?;
     match read_password(&canonical) {
         Ok(stored) => {
             let secret = decode_stored_session_payload(profile_id, &stored)?;
@@ -155,44 +379,81 @@ pub fn read_nsec_for_profile(profile_id: &str) -> Result<Option<String>, String>
             }
         }
         Err(keyring::Error::NoEntry) => {}
-        Err(e) => return Err(e.to_string()),
+        Err(e) => return Err(keyring_error_message(&e)),
     }
 
-    let legacy = Entry::new(APP_SERVICE, &legacy_key_name_for_profile(profile_id)).map_err(|e| e.to_string())?;
+    let legacy = Entry::new(keychain_service(), &legacy_key_name_for_profile(profile_id))
+        .map_err(|e| keyring_error_message(&e))?;
     match read_password(&legacy) {
         Ok(stored) => {
             let secret = decode_stored_session_payload(profile_id, &stored)?;
-            let Some(secret) = secret else {
+            let Some(secret_zero) = secret else {
                 return Ok(None);
             };
-            let secret_zero = Zeroizing::new(secret);
             let wrapped = keychain_session_envelope::wrap_session_secret_for_keychain(profile_id, &secret_zero)?;
-            write_password(&canonical, &wrapped).map_err(|e| e.to_string())?;
+            write_password(&canonical, &wrapped).map_err(|e| keyring_error_message(&e))?;
             let _ = delete_entry(&legacy);
             remember_session_secret_payload(profile_id, &secret_zero);
-            eprintln!(
+            log::info!(
                 "[SESSION] Migrated legacy keychain entry to canonical wrapped envelope for profile {}",
                 profile_id
             );
-            Ok(Some(secret_zero.to_string()))
+            Ok(Some(secret_zero))
         }
         Err(keyring::Error::NoEntry) => Ok(None),
-        Err(e) => Err(e.to_string()),
+        Err(e) => Err(keyring_error_message(&e)),
     }
 }
 
+/// Whether a key entry (canonical or legacy) exists for `profile_id`, without reading the
+/// secret. The account index answers first. Past it, Secret Service and Credential Manager
+/// report an item's attributes without decrypting it; the macOS keychain only hands them
+/// out with the item (and its access prompt), so there an unindexed key counts as absent
+/// until it is next stored or hydrated. Unreachable keychains count as no entry.
+#[cfg(not(target_os = "android"))]
+pub fn has_nsec_entry_for_profile(app_data_dir: &std::path::Path, profile_id: &str) -> bool {
+    if cached_session_secret_payload(profile_id).is_some()
+        || crate::account_index::read_account_index(app_data_dir)
+            .iter()
+            .any(|record| record.profile_id == profile_id)
+    {
+        return true;
+    }
+    if cfg!(any(target_os = "macos", target_os = "ios")) {
+        return false;
+    }
+    [
+        key_name_for_profile(profile_id),
+        legacy_key_name_for_profile(profile_id),
+    ]
+    .iter()
+    .any(|key_name| {
+        Entry::new(keychain_service(), key_name)
+            .and_then(|entry| entry.get_attributes())
+            .is_ok()
+    })
+}
+
 #[cfg(target_os = "android")]
-pub fn read_nsec_for_profile(_profile_id: &str) -> Result<Option<String>, String> {
+pub fn has_nsec_entry_for_profile(_app_data_dir: &std::path::Path, _profile_id: &str) -> bool {
+    false
+}
+
+#[cfg(target_os = "android")]
+pub fn read_nsec_for_profile(
+    _profile_id: &str,
+) -> Result<Option<zeroize::Zeroizing<String>>, String> {
     Ok(None)
 }
 
 #[cfg(not(target_os = "android"))]
 pub fn write_nsec_for_profile(profile_id: &str, nsec: &str) -> Result<(), String> {
     let wrapped = keychain_session_envelope::wrap_session_secret_for_keychain(profile_id, nsec)?;
-    let canonical = Entry::new(APP_SERVICE, &key_name_for_profile(profile_id)).map_err(|e| e.to_string())?;
-    write_password(&canonical, &wrapped).map_err(|e| e.to_string())?;
+    let canonical = Entry::new(keychain_service(), &key_name_for_profile(profile_id))
+        .map_err(|e| keyring_error_message(&e))?;
+    write_password(&canonical, &wrapped).map_err(|e| keyring_error_message(&e))?;
     // Best-effort cleanup of the legacy misnamed entry after a successful login/import.
-    if let Ok(legacy) = Entry::new(APP_SERVICE, &legacy_key_name_for_profile(profile_id)) {
+    if let Ok(legacy) = Entry::new(keychain_service(), &legacy_key_name_for_profile(profile_id)) {
         let _ = delete_entry(&legacy);
     }
     match read_password(&canonical) {
@@ -204,13 +465,13 @@ pub fn write_nsec_for_profile(profile_id: &str, nsec: &str) -> Result<(), String
         Err(keyring::Error::NoEntry) => {
             // Windows Credential Manager can lag between Entry instances; keep in-process cache.
             remember_session_secret_payload(profile_id, nsec);
-            eprintln!(
+            log::info!(
                 "[SESSION] Keychain read-back missed for profile {}; using in-process cache",
                 profile_id
             );
             Ok(())
         }
-        Err(e) => Err(e.to_string()),
+        Err(e) => Err(keyring_error_message(&e)),
     }
 }
 
@@ -219,15 +480,97 @@ pub fn write_nsec_for_profile(_profile_id: &str, _nsec: &str) -> Result<(), Stri
     Ok(())
 }
 
+/// Whether `migrate_nsec_from_entry` may touch `service`/`key`: only this app's own
+/// services (the stable build's and this build's) and this profile's key names, current,
+/// legacy or the pre-profile bare `nsec`. The webview must not reach any other entry.
+#[cfg(not(target_os = "android"))]
+fn is_migratable_entry(profile_id: &str, service: &str, key: &str) -> bool {
+    let own_service = service == APP_SERVICE || service == keychain_service();
+    let own_key = key == KEY_NAME
+        || key == key_name_for_profile(profile_id)
+        || key == legacy_key_name_for_profile(profile_id);
+    own_service && own_key
+}
+
+/// Move a key stored under another of the app's service/key names (an older build's
+/// entry) to this profile's canonical entry, then delete the old one. Returns whether
+/// anything moved; refuses to overwrite a key already stored under the canonical entry.
+#[cfg(not(target_os = "android"))]
+pub fn migrate_nsec_from_entry(
+    profile_id: &str,
+    old_service: &str,
+    old_key: &str,
+) -> Result<bool, String> {
+    let (old_service, old_key) = (old_service.trim(), old_key.trim());
+    if old_service.is_empty() || old_key.is_empty() {
+        return Err("Old keychain service and key name are required".to_string());
+    }
+    if !is_migratable_entry(profile_id, old_service, old_key) {
+        return Err(
+            "Only this app's own keychain entries for this profile can be migrated".to_string(),
+        );
+    }
+    if old_service == keychain_service() && old_key == key_name_for_profile(profile_id) {
+        return Ok(false);
+    }
+    let old_entry = Entry::new(old_service, old_key).map_err(|e| keyring_error_message(&e))?;
+    let stored = match read_password(&old_entry) {
+        Ok(stored) => stored,
+        Err(keyring::Error::NoEntry) => return Ok(false),
+        Err(e) => return Err(keyring_error_message(&e)),
+    };
+    let secret = if keychain_session_envelope::is_wrapped_keychain_payload(&stored) {
+        keychain_session_envelope::unwrap_session_secret_from_keychain(profile_id, &stored)?
+            .map(Zeroizing::new)
+            .ok_or_else(|| {
+                "Old keychain entry could not be unwrapped for this profile".to_string()
+            })?
+    } else if keychain_session_envelope::is_legacy_plaintext_keychain_secret(&stored) {
+        Zeroizing::new(stored.trim().to_string())
+    } else {
+        return Err("Old keychain entry does not hold a recognizable key".to_string());
+    };
+
+    let canonical = Entry::new(keychain_service(), &key_name_for_profile(profile_id))
+        .map_err(|e| keyring_error_message(&e))?;
+    match read_password(&canonical) {
+        Ok(_) => {
+            return Err(
+                "A key is already stored for this profile; log out before migrating".to_string(),
+            )
+        }
+        Err(keyring::Error::NoEntry) => {}
+        Err(e) => return Err(keyring_error_message(&e)),
+    }
+    write_nsec_for_profile(profile_id, &secret)?;
+    delete_entry(&old_entry).map_err(|e| keyring_error_message(&e))?;
+    log::info!(
+        "[SESSION] Migrated keychain entry {}/{} to the canonical entry for profile {}",
+        old_service,
+        old_key,
+        profile_id
+    );
+    Ok(true)
+}
+
+#[cfg(target_os = "android")]
+pub fn migrate_nsec_from_entry(
+    _profile_id: &str,
+    _old_service: &str,
+    _old_key: &str,
+) -> Result<bool, String> {
+    Ok(false)
+}
+
 #[cfg(not(target_os = "android"))]
 fn remember_pdk_payload(profile_id: &str, payload: &str) {
     if let Ok(mut cache) = PDK_SECRET_CACHE.lock() {
-        cache.insert(profile_id.to_string(), payload.to_string());
+        cache.insert(profile_id.to_string(), Zeroizing::new(payload.to_string()));
     }
 }
 
 #[cfg(not(target_os = "android"))]
-fn cached_pdk_payload(profile_id: &str) -> Option<String> {
+fn cached_pdk_payload(profile_id: &str) -> Option<Zeroizing<String>> {
     PDK_SECRET_CACHE
         .lock()
         .ok()
@@ -243,9 +586,13 @@ fn forget_pdk_payload(profile_id: &str) {
 
 #[cfg(not(target_os = "android"))]
 pub fn write_pdk_for_profile(profile_id: &str, key_material: &[u8; 32]) -> Result<(), String> {
-    let wrapped = keychain_session_envelope::wrap_storage_key_material_for_keychain(profile_id, key_material)?;
-    let entry = Entry::new(APP_SERVICE, &pdk_key_name_for_profile(profile_id)).map_err(|e| e.to_string())?;
-    write_password(&entry, &wrapped).map_err(|e| e.to_string())?;
+    let wrapped = keychain_session_envelope::wrap_storage_key_material_for_keychain(
+        profile_id,
+        key_material,
+    )?;
+    let entry = Entry::new(keychain_service(), &pdk_key_name_for_profile(profile_id))
+        .map_err(|e| keyring_error_message(&e))?;
+    write_password(&entry, &wrapped).map_err(|e| keyring_error_message(&e))?;
     remember_pdk_payload(profile_id, &wrapped);
     Ok(())
 }
@@ -255,24 +602,26 @@ pub fn read_pdk_for_profile(profile_id: &str) -> Result<Option<[u8; 32]>, String
     if let Some(cached) = cached_pdk_payload(profile_id) {
         return keychain_session_envelope::unwrap_storage_key_material_from_keychain(profile_id, &cached);
     }
-    let entry = Entry::new(APP_SERVICE, &pdk_key_name_for_profile(profile_id)).map_err(|e| e.to_string())?;
+    let entry = Entry::new(keychain_service(), &pdk_key_name_for_profile(profile_id))
+        .map_err(|e| keyring_error_message(&e))?;
     match read_password(&entry) {
         Ok(payload) => {
             remember_pdk_payload(profile_id, &payload);
             keychain_session_envelope::unwrap_storage_key_material_from_keychain(profile_id, &payload)
         }
         Err(keyring::Error::NoEntry) => Ok(None),
-        Err(e) => Err(e.to_string()),
+        Err(e) => Err(keyring_error_message(&e)),
     }
 }
 
 #[cfg(not(target_os = "android"))]
 pub fn delete_pdk_for_profile(profile_id: &str) -> Result<(), String> {
     forget_pdk_payload(profile_id);
-    let entry = Entry::new(APP_SERVICE, &pdk_key_name_for_profile(profile_id)).map_err(|e| e.to_string())?;
+    let entry = Entry::new(keychain_service(), &pdk_key_name_for_profile(profile_id))
+        .map_err(|e| keyring_error_message(&e))?;
     match delete_entry(&entry) {
         Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
-        Err(e) => Err(e.to_string()),
+        Err(e) => Err(keyring_error_message(&e)),
     }
 }
 
@@ -299,10 +648,11 @@ pub fn delete_nsec_for_profile(profile_id: &str) -> Result<(), String> {
         key_name_for_profile(profile_id),
         legacy_key_name_for_profile(profile_id),
     ] {
-        let entry = Entry::new(APP_SERVICE, &key_name).map_err(|e| e.to_string())?;
+        let entry =
+            Entry::new(keychain_service(), &key_name).map_err(|e| keyring_error_message(&e))?;
         match delete_entry(&entry) {
             Ok(()) | Err(keyring::Error::NoEntry) => {}
-            Err(e) => return Err(e.to_string()),
+            Err(e) => return Err(keyring_error_message(&e)),
         }
     }
     Ok(())
@@ -317,17 +667,20 @@ pub fn delete_nsec_for_profile(_profile_id: &str) -> Result<(), String> {
 #[cfg(not(target_os = "android"))]
 pub fn read_login_assist_for_profile(profile_id: &str) -> Result<Option<String>, String> {
     if let Some(cached) = cached_login_assist_payload(profile_id) {
-        return Ok(Some(cached));
+        return Ok(Some(cached.to_string()));
     }
-    let entry = Entry::new(APP_SERVICE, &login_assist_key_name_for_profile(profile_id))
-        .map_err(|e| e.to_string())?;
+    let entry = Entry::new(
+        keychain_service(),
+        &login_assist_key_name_for_profile(profile_id),
+    )
+    .map_err(|e| keyring_error_message(&e))?;
     match read_password(&entry) {
         Ok(payload) => {
             remember_login_assist_payload(profile_id, &payload);
             Ok(Some(payload))
         }
         Err(keyring::Error::NoEntry) => Ok(None),
-        Err(e) => Err(e.to_string()),
+        Err(e) => Err(keyring_error_message(&e)),
     }
 }
 
@@ -338,9 +691,12 @@ pub fn read_login_assist_for_profile(_profile_id: &str) -> Result<Option<String>
 
 #[cfg(not(target_os = "android"))]
 pub fn write_login_assist_for_profile(profile_id: &str, payload: &str) -> Result<(), String> {
-    let entry = Entry::new(APP_SERVICE, &login_assist_key_name_for_profile(profile_id))
-        .map_err(|e| e.to_string())?;
-    write_password(&entry, payload).map_err(|e| e.to_string())?;
+    let entry = Entry::new(
+        keychain_service(),
+        &login_assist_key_name_for_profile(profile_id),
+    )
+    .map_err(|e| keyring_error_message(&e))?;
+    write_password(&entry, payload).map_err(|e| keyring_error_message(&e))?;
     match read_password(&entry) {
         Ok(stored) if stored == payload => {
             remember_login_assist_payload(profile_id, payload);
@@ -350,13 +706,13 @@ pub fn write_login_assist_for_profile(profile_id: &str, payload: &str) -> Result
         Err(keyring::Error::NoEntry) => {
             // Windows Credential Manager can lag between Entry instances; keep in-process cache.
             remember_login_assist_payload(profile_id, payload);
-            eprintln!(
+            log::info!(
                 "[LOGIN_ASSIST] Keychain read-back missed for profile {}; using in-process cache",
                 profile_id
             );
             Ok(())
         }
-        Err(e) => Err(e.to_string()),
+        Err(e) => Err(keyring_error_message(&e)),
     }
 }
 
@@ -368,11 +724,14 @@ pub fn write_login_assist_for_profile(_profile_id: &str, _payload: &str) -> Resu
 #[cfg(not(target_os = "android"))]
 pub fn delete_login_assist_for_profile(profile_id: &str) -> Result<(), String> {
     forget_login_assist_payload(profile_id);
-    let entry = Entry::new(APP_SERVICE, &login_assist_key_name_for_profile(profile_id))
-        .map_err(|e| e.to_string())?;
+    let entry = Entry::new(
+        keychain_service(),
+        &login_assist_key_name_for_profile(profile_id),
+    )
+    .map_err(|e| keyring_error_message(&e))?;
     match delete_entry(&entry) {
         Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
-        Err(e) => Err(e.to_string()),
+        Err(e) => Err(keyring_error_message(&e)),
     }
 }
 
@@ -380,3 +739,31 @@ pub fn delete_login_assist_for_profile(profile_id: &str) -> Result<(), String> {
 pub fn delete_login_assist_for_profile(_profile_id: &str) -> Result<(), String> {
     Ok(())
 }
+
+#[cfg(all(test, not(target_os = "android")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migration_is_limited_to_the_apps_own_entries() {
+        assert!(is_migratable_entry("default", APP_SERVICE, "nsec"));
+        assert!(is_migratable_entry("default", APP_SERVICE, "nsec::default"));
+        assert!(is_migratable_entry(
+            "default",
+            APP_SERVICE,
+            "nsec:: default"
+        ));
+        assert!(!is_migratable_entry("default", APP_SERVICE, "nsec::work"));
+        assert!(!is_migratable_entry("default", APP_SERVICE, "pdk::default"));
+        assert!(!is_migratable_entry(
+            "default",
+            "com.example.wallet",
+            "nsec"
+        ));
+        assert!(!is_migratable_entry(
+            "default",
+            "login.keychain",
+            "nsec::default"
+        ));
+    }
+}
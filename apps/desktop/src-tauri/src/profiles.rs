@@ -1,12 +1,13 @@
 use serde::{Deserialize, Serialize};
+use std::fs;
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
 use tokio::sync::Mutex;
-use std::fs;
 
+use crate::account_index;
+use crate::data_root::{default_app_data_dir, resolve_effective_data_root};
 use crate::native_keychain;
 use crate::session::SessionState;
-use crate::data_root::{default_app_data_dir, resolve_effective_data_root};
 
 const REGISTRY_FILE: &str = "profiles_registry.json";
 const DEFAULT_PROFILE_ID: &str = "default";
@@ -22,15 +23,22 @@ pub fn clear_profile_webview_data_directory(app: &AppHandle, profile_id: &str) {
     let app_dir = match resolve_effective_data_root(app) {
         Ok(dir) => dir,
         Err(e) => {
-            eprintln!("[PROFILES] Warning: Failed to get app data dir: {}", e);
+            log::warn!("[PROFILES] Warning: Failed to get app data dir: {}", e);
             return;
         }
     };
     let profile_data_dir = app_dir.join("profiles").join(profile_id);
     if profile_data_dir.exists() {
         match std::fs::remove_dir_all(&profile_data_dir) {
-            Ok(_) => eprintln!("[PROFILES] Cleared data directory for profile {}", profile_id),
-            Err(e) => eprintln!("[PROFILES] Warning: Failed to clear data directory for profile {}: {}", profile_id, e),
+            Ok(_) => log::info!(
+                "[PROFILES] Cleared data directory for profile {}",
+                profile_id
+            ),
+            Err(e) => log::warn!(
+                "[PROFILES] Warning: Failed to clear data directory for profile {}: {}",
+                profile_id,
+                e
+            ),
         }
     }
 }
@@ -45,7 +53,10 @@ fn clear_shared_webview_storage(app: &AppHandle) {
     let app_dir = match default_app_data_dir(app) {
         Ok(dir) => dir,
         Err(e) => {
-            eprintln!("[PROFILES] Warning: Failed to get app data dir for shared storage cleanup: {}", e);
+            log::warn!(
+                "[PROFILES] Warning: Failed to get app data dir for shared storage cleanup: {}",
+                e
+            );
             return;
         }
     };
@@ -67,8 +78,12 @@ fn clear_shared_webview_storage(app: &AppHandle) {
         let path = app_dir.join(dir_name);
         if path.exists() {
             match std::fs::remove_dir_all(&path) {
-                Ok(_) => eprintln!("[PROFILES] Cleared shared WebView storage: {}", dir_name),
-                Err(e) => eprintln!("[PROFILES] Warning: Failed to clear shared WebView storage {}: {}", dir_name, e),
+                Ok(_) => log::info!("[PROFILES] Cleared shared WebView storage: {}", dir_name),
+                Err(e) => log::warn!(
+                    "[PROFILES] Warning: Failed to clear shared WebView storage {}: {}",
+                    dir_name,
+                    e
+                ),
             }
         }
     }
@@ -82,14 +97,21 @@ async fn clear_native_credentials_for_profile(app: &AppHandle, profile_id: &str,
 
     // Clear in-memory session
     session.clear(Some(profile_id)).await;
-    eprintln!("[PROFILES] Cleared session for profile {}", profile_id);
+    log::info!("[PROFILES] Cleared session for profile {}", profile_id);
 
     match native_keychain::delete_nsec_for_profile(profile_id) {
-        Ok(()) => eprintln!("[PROFILES] Cleared keychain for profile {}", profile_id),
-        Err(e) => eprintln!("[PROFILES] Warning: Failed to clear keychain for profile {}: {}", profile_id, e),
+        Ok(()) => {
+            account_index::note_forgotten_account(app, profile_id);
+            log::info!("[PROFILES] Cleared keychain for profile {}", profile_id)
+        }
+        Err(e) => log::warn!(
+            "[PROFILES] Warning: Failed to clear keychain for profile {}: {}",
+            profile_id,
+            e
+        ),
     }
     if let Err(e) = native_keychain::delete_login_assist_for_profile(profile_id) {
-        eprintln!(
+        log::warn!(
             "[PROFILES] Warning: Failed to clear login assist for profile {}: {}",
             profile_id, e
         );
@@ -329,7 +351,7 @@ fn migrate_legacy_per_window_webview_data(profile_dir: &std::path::Path) -> Resu
     let (source, _) = candidates
         .last()
         .expect("non-empty candidates checked above");
-    eprintln!(
+    log::info!(
         "[PROFILES] Migrating legacy per-window WebView data from {:?} into shared profile dir {:?}",
         source, profile_dir
     );
@@ -439,7 +461,7 @@ fn profile_window_additional_browser_args() -> Option<String> {
     {
         let port = resolve_dev_cdp_port("OBSCUR_CDP_PROFILE", "9231");
         let args = dev_webview_browser_args(&port);
-        eprintln!("[PROFILES] Profile window CDP args: {args}");
+        log::debug!("[PROFILES] Profile window CDP args: {args}");
         return Some(args);
     }
     #[cfg(not(debug_assertions))]
@@ -499,6 +521,7 @@ fn build_profile_window(app: &AppHandle, binding: &ProfileWindowBinding) -> Resu
         let window_label = binding.window_label.clone();
         window.on_window_event(move |event| {
             if matches!(event, tauri::WindowEvent::Destroyed) {
+                crate::relay::on_window_destroyed(&app_handle, &window_label);
                 let app = app_handle.clone();
                 let label = window_label.clone();
                 tauri::async_runtime::spawn(async move {
@@ -515,7 +538,7 @@ fn build_profile_window(app: &AppHandle, binding: &ProfileWindowBinding) -> Resu
             let _ = reveal_window.unminimize();
             let _ = reveal_window.show();
             let _ = reveal_window.set_focus();
-            eprintln!(
+            log::info!(
                 "[PROFILES] Failsafe reveal for window '{}' after frontend did not call window_reveal_current",
                 reveal_window.label()
             );
@@ -545,7 +568,7 @@ impl DesktopProfileState {
             })
             .await;
             if let Err(error) = migration_result {
-                eprintln!("[PROFILES] Legacy WebView migration task failed: {error}");
+                log::warn!("[PROFILES] Legacy WebView migration task failed: {error}");
             }
         });
 
@@ -681,7 +704,11 @@ impl DesktopProfileState {
         {
             for window_label in &windows_to_close {
                 if let Some(window) = app.get_webview_window(window_label) {
-                    eprintln!("[PROFILES] Closing window {} for deleted profile {}", window_label, profile_id);
+                    log::info!(
+                        "[PROFILES] Closing window {} for deleted profile {}",
+                        window_label,
+                        profile_id
+                    );
                     let _ = window.close();
                 }
             }
@@ -705,15 +732,25 @@ impl DesktopProfileState {
             .filter(|binding| binding.profile_id == profile_id)
             .cloned()
             .collect();
-        state.window_bindings.retain(|binding| binding.profile_id != profile_id);
-        
-        eprintln!("[PROFILES] Removed {} window bindings for deleted profile {}", removed_bindings.len(), profile_id);
-        
+        state
+            .window_bindings
+            .retain(|binding| binding.profile_id != profile_id);
+
+        log::info!(
+            "[PROFILES] Removed {} window bindings for deleted profile {}",
+            removed_bindings.len(),
+            profile_id
+        );
+
         persist_registry(app, &state)?;
         
         // Log removed profile details for diagnostics
         if let Some(profile) = removed_profile {
-            eprintln!("[PROFILES] Removed profile '{}' (ID: {}) from registry", profile.label, profile_id);
+            log::info!(
+                "[PROFILES] Removed profile '{}' (ID: {}) from registry",
+                profile.label,
+                profile_id
+            );
         }
         
         // Clear in-memory session, native keychain, profile data directory, AND shared WebView storage
@@ -724,9 +761,13 @@ impl DesktopProfileState {
         // Clear credentials and per-profile WebView data only. Do not wipe app-level
         // shared WebView dirs here — other profile windows may still be running.
         clear_native_credentials_for_profile(app, profile_id, session).await;
-        
-        eprintln!("[PROFILES] Profile {} removal complete. Windows closed: {}", profile_id, windows_to_close.len());
-        
+
+        log::info!(
+            "[PROFILES] Profile {} removal complete. Windows closed: {}",
+            profile_id,
+            windows_to_close.len()
+        );
+
         // Re-acquire lock to return updated snapshot
         let mut state = self.inner.lock().await;
         let (current_window, _) = ensure_window_binding(&mut state, current_window_label);
@@ -866,7 +907,7 @@ fn focus_existing_live_profile_window(
             let _ = window.set_focus();
         }
         let _ = window;
-        eprintln!(
+        log::info!(
             "[PROFILES] Reused existing window '{}' for profile {}",
             binding.window_label, profile_id
         );
@@ -902,13 +943,13 @@ fn migrate_legacy_webview_data(app: &AppHandle) -> Result<(), String> {
             continue;
         }
 
-        println!(
+        log::info!(
             "[ProfileIsolation] Attempting legacy WebView migration from {:?} to {:?}",
             source_dir, target_eb_webview
         );
 
         if fs::rename(&source_dir, &target_eb_webview).is_ok() {
-            println!("[ProfileIsolation] Migration completed successfully via move.");
+            log::info!("[ProfileIsolation] Migration completed successfully via move.");
             return Ok(());
         }
 
@@ -918,11 +959,11 @@ fn migrate_legacy_webview_data(app: &AppHandle) -> Result<(), String> {
 
         if copy_dir_recursive(&source_dir, &target_eb_webview).is_ok() {
             let _ = fs::remove_dir_all(&source_dir);
-            println!("[ProfileIsolation] Migration completed successfully via copy fallback.");
+            log::info!("[ProfileIsolation] Migration completed successfully via copy fallback.");
             return Ok(());
         }
 
-        eprintln!(
+        log::warn!(
             "[ProfileIsolation] Migration attempt failed for source {:?}; trying next candidate.",
             source_dir
         );
@@ -1,18 +1,25 @@
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tauri::{AppHandle, Emitter, Manager, State, WebviewWindow};
 use tokio::sync::mpsc::error::TrySendError;
-use tokio::sync::mpsc::{self, Sender};
-use tokio::sync::oneshot;
+use tokio::sync::mpsc::{self, Sender, UnboundedSender};
+use tokio::sync::{oneshot, Semaphore};
 use tokio::time::timeout;
 use tokio::time::{sleep, Instant};
-use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use tokio_tungstenite::tungstenite;
+use tokio_tungstenite::tungstenite::error::CapacityError;
+use tokio_tungstenite::tungstenite::protocol::Message;
 
-use crate::net::NativeNetworkRuntime;
+use crate::models::relay_list::{SavedRelay, SubscriptionSettings};
+use crate::net::{
+    parse_tls_sni, parse_upgrade_headers, ConnectionRoute, NativeNetworkRuntime, UpgradeHeaders,
+};
+use crate::services::relay_list::{write_saved_relays, write_subscription_settings};
 
 type MaybeTlsStream = tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>;
 
@@ -22,6 +29,33 @@ const CONNECT_COMMAND_BUDGET_MS: u64 = 18_000;
 const CONNECT_ATTEMPT_TIMEOUT_MS: u64 = 8_000;
 const TOR_CONNECT_RETRY_DELAY_MS: u64 = 500;
 const RELAY_WRITE_SEND_TIMEOUT_MS: u64 = 4_000;
+const FETCH_EVENTS_DEFAULT_TIMEOUT_MS: u64 = 8_000;
+const FETCH_EVENTS_MAX_TIMEOUT_MS: u64 = 30_000;
+const WRITE_RELAY_ACK_TIMEOUT_MS: u64 = 12_000;
+const MAX_PUBLISH_BUFFER_CAPACITY: usize = 500;
+// `disconnect_relay_drain` waits at most this long, below the frontend invoke timeout
+const DISCONNECT_DRAIN_MAX_TIMEOUT_MS: u64 = 15_000;
+const DISCONNECT_DRAIN_POLL_MS: u64 = 25;
+const RECONNECTS_PAUSED_ERROR: &str = "Reconnects paused";
+const CONNECT_QUEUED_MESSAGE: &str = "Queued (connection limit reached)";
+const RELAY_SUMMARY_DEBOUNCE_MS: u64 = 250;
+const RELAY_STATUS_HISTORY_CAPACITY: usize = 100;
+// How long a reconnect to an AUTH-gated relay holds its REQs waiting for NIP-42 AUTH
+const AUTH_RESUBSCRIBE_WAIT_MS: u64 = 10_000;
+// This many relays dropping within MASS_DISCONNECT_WINDOW_MS is treated as a network outage
+const MASS_DISCONNECT_THRESHOLD: usize = 3;
+const MASS_DISCONNECT_WINDOW_MS: u64 = 3_000;
+// Let the remaining drops (and the network) settle before the coordinated reconnect
+const NETWORK_RECOVERY_SETTLE_MS: u64 = 2_000;
+// Start offset added per relay so recovery reconnects go out one after another
+const NETWORK_RECOVERY_STAGGER_MS: u64 = 400;
+// A wall-clock gap this long between watchdog ticks means the machine was suspended
+const RESUME_WATCHDOG_TICK_SECS: u64 = 5;
+const RESUME_GAP_THRESHOLD_SECS: u64 = 30;
+// Stored events are flushed as one `relay-events-batch` per subscription this often,
+// or as soon as a batch reaches RELAY_EVENT_BATCH_MAX_EVENTS
+const RELAY_EVENT_BATCH_INTERVAL_MS: u64 = 100;
+const RELAY_EVENT_BATCH_MAX_EVENTS: usize = 500;
 
 fn enqueue_relay_message(tx: &Sender<Message>, message: Message) -> Result<(), String> {
     match tx.try_send(message) {
@@ -40,10 +74,32 @@ pub struct RelayProbeReport {
     pub tor_enabled: bool,
     pub proxy_url: Option<String>,
     pub dns_ok: bool,
+    /// "system", "doh", "proxy" (resolution delegated to the SOCKS5h proxy), or "skipped"
+    /// (Tor is on but this relay is routed direct; no lookup is made outside the handshake).
+    pub dns_mode: String,
     pub dns_results: Vec<String>,
     pub tcp_ok: bool,
     pub ws_ok: bool,
     pub error: Option<String>,
+    /// Time spent in each stage, including one that failed or timed out; None when the stage
+    /// was skipped (DNS behind a proxy or while Tor is on) or never reached
+    pub dns_ms: Option<u64>,
+    pub tcp_ms: Option<u64>,
+    pub ws_ms: Option<u64>,
+    /// NIP-11 supported NIPs and limitations, when requested and the handshake succeeded;
+    /// None also when the relay serves no information document
+    #[serde(default)]
+    pub capabilities: Option<RelayCapabilities>,
+}
+
+/// Default `probe_relay` stage timeouts; a SOCKS5 handshake gets longer than a direct TCP connect
+const PROBE_DNS_TIMEOUT_MS: u64 = 5_000;
+const PROBE_TCP_TIMEOUT_MS: u64 = 5_000;
+const PROBE_PROXY_TCP_TIMEOUT_MS: u64 = 10_000;
+const PROBE_WS_TIMEOUT_MS: u64 = 10_000;
+
+fn elapsed_ms(started: Instant) -> Option<u64> {
+    Some(started.elapsed().as_millis() as u64)
 }
 
 fn format_ws_error_details(err: &tokio_tungstenite::tungstenite::Error) -> String {
@@ -70,21 +126,115 @@ fn format_ws_connect_error(err: &tokio_tungstenite::tungstenite::Error) -> Strin
     }
 }
 
+/// Canonical relay URL used as the key in every `RelayPool` map: ws/wss only, lowercase
+/// host, default port and fragment dropped, no trailing slash. A bare host gets `wss://`.
+pub fn normalize_relay_url(raw: &str) -> Result<String, String> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return Err("Relay URL is empty".to_string());
+    }
+    let with_scheme = if trimmed.contains("://") {
+        trimmed.to_string()
+    } else {
+        format!("wss://{}", trimmed)
+    };
+    // `Url` already lowercases the scheme and host and omits the scheme's default port
+    let mut parsed = url::Url::parse(&with_scheme)
+        .map_err(|e| format!("Invalid relay URL {}: {}", trimmed, e))?;
+    if !matches!(parsed.scheme(), "ws" | "wss") {
+        return Err(format!("Relay URL must use ws:// or wss://: {}", trimmed));
+    }
+    if parsed.host_str().is_none_or(str::is_empty) {
+        return Err(format!("Relay URL missing host: {}", trimmed));
+    }
+    parsed.set_fragment(None);
+    let path = parsed.path().trim_end_matches('/').to_string();
+    parsed.set_path(&path);
+    let mut normalized = parsed.to_string();
+    if parsed.path() == "/" && parsed.query().is_none() {
+        normalized.pop();
+    }
+    Ok(normalized)
+}
+
+/// Normalize a relay URL list, dropping duplicates that only differed cosmetically.
+fn normalize_relay_urls(urls: &[String]) -> Result<Vec<String>, String> {
+    let mut seen = HashSet::new();
+    let mut normalized = Vec::with_capacity(urls.len());
+    for url in urls {
+        let url = normalize_relay_url(url)?;
+        if seen.insert(url.clone()) {
+            normalized.push(url);
+        }
+    }
+    Ok(normalized)
+}
+
+/// Parse a per-relay proxy override: `"direct"` bypasses any global proxy,
+/// otherwise a `socks5://` or `socks5h://` URL with a host.
+fn parse_relay_proxy(proxy: &str) -> Result<ConnectionRoute, String> {
+    let trimmed = proxy.trim();
+    if trimmed.eq_ignore_ascii_case("direct") {
+        return Ok(ConnectionRoute::Direct);
+    }
+    let parsed = url::Url::parse(trimmed).map_err(|e| format!("Invalid proxy URL: {e}"))?;
+    if parsed.scheme() != "socks5" && parsed.scheme() != "socks5h" {
+        return Err("Relay proxy must be \"direct\" or a socks5:// / socks5h:// URL".to_string());
+    }
+    if parsed.host_str().is_none_or(str::is_empty) {
+        return Err("Proxy URL missing host".to_string());
+    }
+    Ok(ConnectionRoute::Socks5(trimmed.to_string()))
+}
+
+/// Where `probe_relay` resolves the relay host. While Tor is on the probe makes no lookup
+/// of its own: a relay whose override routes it around the proxy is only resolved by the
+/// handshake, exactly as its real connection would be ("skipped").
+fn probe_dns_mode(proxied: bool, tor_enabled: bool, doh: bool) -> &'static str {
+    if proxied {
+        "proxy"
+    } else if tor_enabled {
+        "skipped"
+    } else if doh {
+        "doh"
+    } else {
+        "system"
+    }
+}
+
+// Command: Step through DNS, TCP and the WebSocket handshake for `url`, reporting where it
+// fails and how long each stage took. Each stage timeout can be raised for slow links.
+// With `include_capabilities`, a reachable relay's NIP-11 document is fetched as well.
 #[tauri::command]
 pub async fn probe_relay(
+    state: State<'_, RelayPool>,
     net_runtime: State<'_, NativeNetworkRuntime>,
     url: String,
+    dns_timeout_ms: Option<u64>,
+    tcp_timeout_ms: Option<u64>,
+    ws_timeout_ms: Option<u64>,
+    include_capabilities: Option<bool>,
 ) -> Result<RelayProbeReport, String> {
+    let dns_timeout = Duration::from_millis(dns_timeout_ms.unwrap_or(PROBE_DNS_TIMEOUT_MS));
+    let ws_timeout = Duration::from_millis(ws_timeout_ms.unwrap_or(PROBE_WS_TIMEOUT_MS));
     let parsed = url::Url::parse(&url).map_err(|e| e.to_string())?;
     let scheme = parsed.scheme().to_string();
     let host = parsed.host_str().map(|s| s.to_string());
     let port = parsed.port_or_known_default();
     let tor_enabled = net_runtime.is_tor_enabled();
-    let proxy_url = if tor_enabled {
-        Some(net_runtime.get_proxy_url())
-    } else {
+    let route = state.route_for(&url, &net_runtime);
+    let headers = state.headers_for(&url);
+    let tls_sni = state.tls_sni_for(&url);
+    let proxy_url = match &route {
+        ConnectionRoute::Socks5(proxy_url) => Some(proxy_url.clone()),
+        ConnectionRoute::Direct => None,
+    };
+    let doh_url = if proxy_url.is_some() {
         None
+    } else {
+        net_runtime.get_doh_resolver_url()
     };
+    let dns_mode = probe_dns_mode(proxy_url.is_some(), tor_enabled, doh_url.is_some());
 
     let mut report = RelayProbeReport {
         url: url.clone(),
@@ -92,12 +242,17 @@ pub async fn probe_relay(
         host: host.clone(),
         port,
         tor_enabled,
-        proxy_url,
+        proxy_url: proxy_url.clone(),
         dns_ok: false,
+        dns_mode: dns_mode.to_string(),
         dns_results: Vec::new(),
         tcp_ok: false,
         ws_ok: false,
         error: None,
+        dns_ms: None,
+        tcp_ms: None,
+        ws_ms: None,
+        capabilities: None,
     };
 
     let Some(host_value) = host else {
@@ -109,73 +264,424 @@ pub async fn probe_relay(
         return Ok(report);
     };
 
-    let dns_lookup = timeout(
-        Duration::from_secs(5),
-        tokio::net::lookup_host((host_value.as_str(), port_value)),
-    )
-    .await;
-    match dns_lookup {
-        Ok(Ok(addrs)) => {
-            let results: Vec<String> = addrs.map(|a| a.to_string()).collect();
-            report.dns_ok = !results.is_empty();
-            report.dns_results = results;
-        }
-        Ok(Err(e)) => {
-            report.error = Some(format!("DNS failed: {}", e));
-            return Ok(report);
+    if let Some(proxy_url) = proxy_url {
+        // Never touch the local resolver when proxied: the SOCKS5h proxy resolves the hostname.
+        report.dns_ok = true;
+        let tcp_started = Instant::now();
+        let tcp_connect = timeout(
+            Duration::from_millis(tcp_timeout_ms.unwrap_or(PROBE_PROXY_TCP_TIMEOUT_MS)),
+            NativeNetworkRuntime::connect_tcp_via_socks5(&parsed, &proxy_url),
+        )
+        .await;
+        report.tcp_ms = elapsed_ms(tcp_started);
+        match tcp_connect {
+            Ok(Ok(_stream)) => {
+                report.tcp_ok = true;
+            }
+            Ok(Err(e)) => {
+                report.error = Some(format!("TCP connect via proxy failed: {}", e));
+                return Ok(report);
+            }
+            Err(_) => {
+                report.error = Some("TCP connect via proxy timeout".to_string());
+                return Ok(report);
+            }
         }
-        Err(_) => {
-            report.error = Some("DNS timeout".to_string());
-            return Ok(report);
+    } else if dns_mode == "skipped" {
+        // Direct override with Tor on: a separate lookup and dial here would announce the
+        // relay beyond what its own connection does, so go straight to the handshake.
+    } else {
+        let dns_started = Instant::now();
+        let resolved: Vec<std::net::SocketAddr> = if let Some(doh_url) = doh_url.as_deref() {
+            let doh_lookup = timeout(
+                dns_timeout,
+                crate::net::resolve_host_via_doh(doh_url, &host_value),
+            )
+            .await;
+            report.dns_ms = elapsed_ms(dns_started);
+            match doh_lookup {
+                Ok(Ok(ips)) => ips
+                    .into_iter()
+                    .map(|ip| std::net::SocketAddr::new(ip, port_value))
+                    .collect(),
+                Ok(Err(e)) => {
+                    report.error = Some(format!("DNS failed: {}", e));
+                    return Ok(report);
+                }
+                Err(_) => {
+                    report.error = Some("DNS timeout".to_string());
+                    return Ok(report);
+                }
+            }
+        } else {
+            let dns_lookup = timeout(
+                dns_timeout,
+                tokio::net::lookup_host((host_value.as_str(), port_value)),
+            )
+            .await;
+            report.dns_ms = elapsed_ms(dns_started);
+            match dns_lookup {
+                Ok(Ok(addrs)) => addrs.collect(),
+                Ok(Err(e)) => {
+                    report.error = Some(format!("DNS failed: {}", e));
+                    return Ok(report);
+                }
+                Err(_) => {
+                    report.error = Some("DNS timeout".to_string());
+                    return Ok(report);
+                }
+            }
+        };
+        report.dns_ok = !resolved.is_empty();
+        report.dns_results = resolved.iter().map(|a| a.to_string()).collect();
+
+        let tcp_started = Instant::now();
+        let bind_address = net_runtime.direct_bind_address();
+        let tcp_connect = timeout(
+            Duration::from_millis(tcp_timeout_ms.unwrap_or(PROBE_TCP_TIMEOUT_MS)),
+            async {
+                match bind_address {
+                    Some(local) => crate::net::connect_tcp_from(local, &resolved).await,
+                    None => tokio::net::TcpStream::connect(resolved.as_slice()).await,
+                }
+            },
+        )
+        .await;
+        report.tcp_ms = elapsed_ms(tcp_started);
+        match tcp_connect {
+            Ok(Ok(_stream)) => {
+                report.tcp_ok = true;
+            }
+            Ok(Err(e)) => {
+                report.error = Some(format!("TCP connect failed: {}", e));
+                return Ok(report);
+            }
+            Err(_) => {
+                report.error = Some("TCP connect timeout".to_string());
+                return Ok(report);
+            }
         }
     }
 
-    let tcp_connect = timeout(
-        Duration::from_secs(5),
-        tokio::net::TcpStream::connect((host_value.as_str(), port_value)),
+    let ws_started = Instant::now();
+    let ws_connect = timeout(
+        ws_timeout,
+        net_runtime.connect_websocket_via_route(&parsed, &route, &headers, tls_sni.as_deref()),
     )
     .await;
-    match tcp_connect {
-        Ok(Ok(_stream)) => {
+    report.ws_ms = elapsed_ms(ws_started);
+    match ws_connect {
+        Ok(Ok(mut ws)) => {
+            report.ws_ok = true;
+            // A completed handshake resolved and reached the host, skipped stages included
+            report.dns_ok = true;
             report.tcp_ok = true;
+            let _ = ws.close(None).await;
         }
         Ok(Err(e)) => {
-            report.error = Some(format!("TCP connect failed: {}", e));
-            return Ok(report);
+            report.error = Some(format!(
+                "WS connect failed: {}",
+                format_ws_error_details(&e)
+            ));
         }
         Err(_) => {
-            report.error = Some("TCP connect timeout".to_string());
-            return Ok(report);
+            report.error = Some("WS connect timeout".to_string());
         }
     }
+    if report.ws_ok && include_capabilities.unwrap_or(false) {
+        report.capabilities = fetch_relay_information(&net_runtime, &parsed).await;
+    }
 
-    let ws_connect = timeout(
-        Duration::from_secs(10),
-        net_runtime.connect_websocket(&parsed),
+    Ok(report)
+}
+
+/// Leading-zero bits we can still mine in reasonable time on a desktop CPU (NIP-13).
+const RELAY_TEST_MAX_POW_DIFFICULTY: u32 = 28;
+const RELAY_TEST_TIMEOUT_MS: u64 = 10_000;
+const RELAY_TEST_SUBSCRIPTION_ID: &str = "obscur-relay-test";
+
+/// NIP-11 fields the "add relay" dialog cares about
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayCapabilities {
+    pub name: Option<String>,
+    pub software: Option<String>,
+    pub supported_nips: Vec<u64>,
+    pub auth_required: bool,
+    pub payment_required: bool,
+    pub min_pow_difficulty: Option<u32>,
+    pub max_message_length: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayTestReport {
+    pub url: String,
+    pub ws_ok: bool,
+    /// None when the relay serves no NIP-11 document
+    pub capabilities: Option<RelayCapabilities>,
+    /// The relay sent a NIP-42 challenge during the handshake
+    pub auth_challenged: bool,
+    /// Outcome of answering the challenge; None when no challenge arrived
+    pub auth_ok: Option<bool>,
+    pub auth_message: Option<String>,
+    /// A probe REQ reached EOSE
+    pub req_ok: bool,
+    pub closed_reason: Option<String>,
+    pub pow_satisfiable: bool,
+    pub error: Option<String>,
+}
+
+fn relay_information_url(relay_url: &url::Url) -> Option<url::Url> {
+    let mut info_url = relay_url.clone();
+    let scheme = match relay_url.scheme() {
+        "wss" => "https",
+        "ws" => "http",
+        _ => return None,
+    };
+    info_url.set_scheme(scheme).ok()?;
+    Some(info_url)
+}
+
+fn parse_relay_information(document: &Value) -> RelayCapabilities {
+    let text = |key: &str| {
+        document
+            .get(key)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+    let limitation = document.get("limitation");
+    let limit_flag = |key: &str| {
+        limitation
+            .and_then(|limits| limits.get(key))
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+    };
+    let limit_number = |key: &str| {
+        limitation
+            .and_then(|limits| limits.get(key))
+            .and_then(Value::as_u64)
+    };
+    RelayCapabilities {
+        name: text("name"),
+        software: text("software"),
+        supported_nips: document
+            .get("supported_nips")
+            .and_then(Value::as_array)
+            .map(|nips| nips.iter().filter_map(Value::as_u64).collect())
+            .unwrap_or_default(),
+        auth_required: limit_flag("auth_required"),
+        payment_required: limit_flag("payment_required"),
+        min_pow_difficulty: limit_number("min_pow_difficulty").map(|bits| bits as u32),
+        max_message_length: limit_number("max_message_length"),
+    }
+}
+
+async fn fetch_relay_information(
+    net_runtime: &NativeNetworkRuntime,
+    relay_url: &url::Url,
+) -> Option<RelayCapabilities> {
+    let info_url = relay_information_url(relay_url)?;
+    let client = net_runtime.build_reqwest_client().ok()?;
+    let response = client
+        .get(info_url)
+        .header("Accept", "application/nostr+json")
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let document = response.json::<Value>().await.ok()?;
+    Some(parse_relay_information(&document))
+}
+
+// Command: Full handshake against a relay (NIP-11, NIP-42 AUTH, a probe REQ) on a throwaway
+// socket; nothing is added to the pool, so the "add relay" dialog can validate first.
+#[tauri::command]
+pub async fn test_relay(
+    app: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, RelayPool>,
+    net_runtime: State<'_, NativeNetworkRuntime>,
+    session: State<'_, crate::session::SessionState>,
+    profiles: State<'_, crate::profiles::DesktopProfileState>,
+    url: String,
+) -> Result<RelayTestReport, String> {
+    let parsed = url::Url::parse(url.trim()).map_err(|e| e.to_string())?;
+    if !matches!(parsed.scheme(), "ws" | "wss") {
+        return Err("Relay URL must use ws:// or wss://".to_string());
+    }
+    let relay_url = nostr::RelayUrl::parse(parsed.as_str()).map_err(|e| e.to_string())?;
+    let capabilities = fetch_relay_information(&net_runtime, &parsed).await;
+    let pow_satisfiable = capabilities
+        .as_ref()
+        .and_then(|info| info.min_pow_difficulty)
+        .is_none_or(|bits| bits <= RELAY_TEST_MAX_POW_DIFFICULTY);
+    let mut report = RelayTestReport {
+        url: parsed.to_string(),
+        ws_ok: false,
+        capabilities,
+        auth_challenged: false,
+        auth_ok: None,
+        auth_message: None,
+        req_ok: false,
+        closed_reason: None,
+        pow_satisfiable,
+        error: None,
+    };
+
+    let route = state.route_for(parsed.as_str(), &net_runtime);
+    let headers = state.headers_for(parsed.as_str());
+    let tls_sni = state.tls_sni_for(parsed.as_str());
+    let mut ws = match timeout(
+        Duration::from_millis(CONNECT_ATTEMPT_TIMEOUT_MS),
+        net_runtime.connect_websocket_via_route(&parsed, &route, &headers, tls_sni.as_deref()),
     )
-    .await;
-    match ws_connect {
-        Ok(Ok(mut ws)) => {
-            report.ws_ok = true;
-            let _ = ws.close(None).await;
-        }
+    .await
+    {
+        Ok(Ok(ws)) => ws,
         Ok(Err(e)) => {
             report.error = Some(format!(
                 "WS connect failed: {}",
                 format_ws_error_details(&e)
             ));
+            return Ok(report);
         }
         Err(_) => {
             report.error = Some("WS connect timeout".to_string());
+            return Ok(report);
+        }
+    };
+    report.ws_ok = true;
+
+    let keys = match crate::profiles::resolve_profile_for_window(&app, &profiles, &window).await {
+        Ok(profile_id) => session.get_keys(&profile_id).await,
+        Err(_) => None,
+    };
+    let probe_req = serde_json::json!([
+        "REQ",
+        RELAY_TEST_SUBSCRIPTION_ID,
+        { "kinds": [0], "limit": 1 }
+    ])
+    .to_string();
+    let mut auth_event_id: Option<String> = None;
+    let mut req_retried = false;
+    let deadline = Instant::now() + Duration::from_millis(RELAY_TEST_TIMEOUT_MS);
+
+    if ws
+        .send(Message::Text(probe_req.clone().into()))
+        .await
+        .is_err()
+    {
+        report.error = Some("Relay closed the connection before the probe REQ".to_string());
+        return Ok(report);
+    }
+    loop {
+        let handshake_done = (report.req_ok || report.closed_reason.is_some())
+            && (!report.auth_challenged || report.auth_ok.is_some());
+        if handshake_done {
+            break;
+        }
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let text = match timeout(remaining, ws.next()).await {
+            Ok(Some(Ok(Message::Text(text)))) => text,
+            Ok(Some(Ok(Message::Close(_)))) | Ok(None) => {
+                report.error = Some("Relay closed the connection during the handshake".to_string());
+                break;
+            }
+            Ok(Some(Ok(_))) => continue,
+            Ok(Some(Err(e))) => {
+                report.error = Some(format!("WS read failed: {}", format_ws_error_details(&e)));
+                break;
+            }
+            Err(_) => {
+                report.error = Some("Handshake timed out".to_string());
+                break;
+            }
+        };
+        let Ok(Value::Array(frame)) = serde_json::from_str::<Value>(text.as_str()) else {
+            continue;
+        };
+        match frame.first().and_then(Value::as_str) {
+            Some("AUTH") => {
+                report.auth_challenged = true;
+                let Some(challenge) = frame.get(1).and_then(Value::as_str) else {
+                    continue;
+                };
+                let Some(keys) = keys.as_ref() else {
+                    report.auth_ok = Some(false);
+                    report.auth_message = Some("No active session to answer AUTH".to_string());
+                    continue;
+                };
+                // The AUTH event is signed with the session key, so it waits on the sign gate
+                let sign_auth = app.state::<crate::models::sign_auth::SignAuthState>();
+                if let Err(message) =
+                    crate::services::sign_auth::authorize_signing(&sign_auth).await
+                {
+                    report.auth_ok = Some(false);
+                    report.auth_message = Some(message);
+                    continue;
+                }
+                let event = nostr::EventBuilder::auth(challenge, relay_url.clone())
+                    .sign_with_keys(keys)
+                    .map_err(|e| e.to_string())?;
+                auth_event_id = Some(event.id.to_hex());
+                let auth_frame = serde_json::json!(["AUTH", event]).to_string();
+                if ws.send(Message::Text(auth_frame.into())).await.is_err() {
+                    report.error = Some("Relay closed the connection during AUTH".to_string());
+                    break;
+                }
+            }
+            Some("OK") if frame.get(1).and_then(Value::as_str) == auth_event_id.as_deref() => {
+                let accepted = frame.get(2).and_then(Value::as_bool).unwrap_or(false);
+                report.auth_ok = Some(accepted);
+                report.auth_message = frame.get(3).and_then(Value::as_str).map(str::to_string);
+                // Relays that refused the REQ before AUTH get one retry once authenticated
+                let auth_gated = report
+                    .closed_reason
+                    .as_deref()
+                    .is_some_and(|reason| reason.starts_with("auth-required:"));
+                if accepted && auth_gated && !req_retried {
+                    req_retried = true;
+                    report.closed_reason = None;
+                    let _ = ws.send(Message::Text(probe_req.clone().into())).await;
+                }
+            }
+            Some("EOSE")
+                if frame.get(1).and_then(Value::as_str) == Some(RELAY_TEST_SUBSCRIPTION_ID) =>
+            {
+                report.req_ok = true;
+            }
+            Some("CLOSED")
+                if frame.get(1).and_then(Value::as_str) == Some(RELAY_TEST_SUBSCRIPTION_ID) =>
+            {
+                report.closed_reason = Some(
+                    frame
+                        .get(2)
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_string(),
+                );
+            }
+            _ => {}
         }
     }
 
+    if report.req_ok {
+        let close = serde_json::json!(["CLOSE", RELAY_TEST_SUBSCRIPTION_ID]).to_string();
+        let _ = ws.send(Message::Text(close.into())).await;
+    }
+    let _ = ws.close(None).await;
     Ok(report)
 }
 
 // Type alias for Relay URL
 type RelayUrl = String;
 type PendingAckKey = (String, RelayUrl, String);
+type SubCollectorKey = (String, RelayUrl, String);
+type EventBatchKey = (String, String);
 
 #[derive(Debug)]
 pub struct RelayPublishAck {
@@ -194,15 +700,372 @@ pub struct RelayMessage {
     pub payload: Value, // Raw JSON message from relay
 }
 
+/// `relay-events-batch` payload: stored events for one subscription, in arrival order
+#[derive(Debug, Serialize, Clone)]
+pub struct RelayEventBatch {
+    pub sub_id: String,
+    pub events: Vec<RelayMessage>,
+}
+
+/// Connection state carried by `relay-status` events, tagged by `kind`.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RelayStatus {
+    Starting,
+    Connected,
+    Disconnected,
+    /// Down while the pool reconnects it itself: after a network route change, or in a
+    /// staggered recovery after the network dropped
+    Reconfiguring,
+    Error {
+        error: String,
+        /// Connect attempts made before giving up, when the connect retried
+        #[serde(skip_serializing_if = "Option::is_none")]
+        attempt: Option<u32>,
+        /// Machine-readable cause, e.g. `oversize-frame`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reason: Option<&'static str>,
+    },
+}
+
+impl RelayStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            RelayStatus::Starting => "starting",
+            RelayStatus::Connected => "connected",
+            RelayStatus::Disconnected => "disconnected",
+            RelayStatus::Reconfiguring => "reconfiguring",
+            RelayStatus::Error { .. } => "error",
+        }
+    }
+}
+
+/// `relay-status` error reason when a relay sends a frame above the configured cap
+pub const RELAY_OVERSIZE_FRAME_REASON: &str = "oversize-frame";
+
+/// `relay-status` payload: `{ url, status, kind, error?, attempt? }`.
+/// `status` mirrors `kind` so listeners written against the old payload keep working.
+#[derive(Debug, Serialize, Clone)]
+struct RelayStatusEvent<'a> {
+    url: &'a str,
+    status: &'static str,
+    #[serde(flatten)]
+    kind: &'a RelayStatus,
+}
+
+fn emit_relay_status(app: &AppHandle, window_label: &str, url: &str, status: RelayStatus) {
+    if let Some(window) = app.get_webview_window(window_label) {
+        let _ = window.emit(
+            "relay-status",
+            RelayStatusEvent {
+                url,
+                status: status.as_str(),
+                kind: &status,
+            },
+        );
+    }
+    if let Some(pool) = app.try_state::<RelayPool>() {
+        pool.note_status(app, window_label, url, &status);
+    }
+}
+
+/// `relay-pool-summary` payload: one window's relay counts, sent debounced when
+/// enabled with `set_relay_summary_enabled`
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct RelayPoolSummary {
+    pub connected: usize,
+    pub connecting: usize,
+    pub disconnected: usize,
+    /// Saved relays plus every relay the window connected and has not disconnected
+    pub desired: usize,
+    /// Desired relays that are down after an error
+    pub problems: Vec<RelayProblem>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct RelayProblem {
+    pub url: String,
+    pub error: String,
+}
+
+fn window_relays<'a>(
+    keys: impl Iterator<Item = &'a (String, RelayUrl)>,
+    window_label: &str,
+) -> HashSet<RelayUrl> {
+    keys.filter(|key| key.0 == window_label)
+        .map(|key| key.1.clone())
+        .collect()
+}
+
+fn summarize_relays(
+    desired: &HashSet<RelayUrl>,
+    connected: &HashSet<RelayUrl>,
+    connecting: &HashSet<RelayUrl>,
+    errors: &HashMap<RelayUrl, String>,
+) -> RelayPoolSummary {
+    let connected_count = desired
+        .iter()
+        .filter(|url| connected.contains(*url))
+        .count();
+    let connecting_count = desired
+        .iter()
+        .filter(|url| !connected.contains(*url) && connecting.contains(*url))
+        .count();
+    let mut problems: Vec<RelayProblem> = desired
+        .iter()
+        .filter(|url| !connected.contains(*url))
+        .filter_map(|url| {
+            errors.get(url).map(|error| RelayProblem {
+                url: url.clone(),
+                error: error.clone(),
+            })
+        })
+        .collect();
+    problems.sort_by(|a, b| a.url.cmp(&b.url));
+    RelayPoolSummary {
+        connected: connected_count,
+        connecting: connecting_count,
+        disconnected: desired.len() - connected_count - connecting_count,
+        desired: desired.len(),
+        problems,
+    }
+}
+
 // Persistent state for a relay (survives disconnections)
 #[derive(Default)]
 struct RelayState {
-    subscriptions: HashMap<String, Value>, // sub_id -> filters
+    subscriptions: HashMap<String, Vec<Value>>, // sub_id -> filters, sent in one REQ
+    // sub_id -> oldest `created_at` delivered, the `page_subscription` cursor
+    oldest_seen: HashMap<String, u64>,
+    connect_count: u64,
+    marker: RelayMarker,
+    // The relay sent an AUTH challenge or an auth-required CLOSED on some connection
+    auth_required: bool,
+    // Outbound EVENTs held while the relay is reconnecting (capacity 0 = buffering off)
+    publish_buffer_capacity: usize,
+    pending_publishes: VecDeque<String>,
+    // Subscriptions whose stored events are done (EOSE) since their last REQ
+    eose_received: HashSet<String>,
+    // sub_id -> events delivered since the subscription was (re)placed
+    subscription_stats: HashMap<String, SubscriptionStats>,
+}
+
+/// Events one subscription has delivered on a relay, for `get_subscription_stats`
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct SubscriptionStats {
+    pub events: u64,
+    /// Unix ms when the latest EVENT arrived; None while the subscription is silent
+    pub last_event_at: Option<u64>,
+}
+
+/// NIP-65 relay list marker
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RelayMarker {
+    Read,
+    Write,
+    #[default]
+    Both,
+}
+
+impl RelayMarker {
+    fn allows_read(self) -> bool {
+        self != RelayMarker::Write
+    }
+
+    fn allows_write(self) -> bool {
+        self != RelayMarker::Read
+    }
+}
+
+/// One entry of a NIP-65 (kind 10002) relay list
+#[derive(Debug, Serialize, Clone)]
+pub struct Nip65Relay {
+    pub url: String,
+    pub marker: RelayMarker,
+    /// Set when `import_relays_from_nip65` was asked to connect
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connected: Option<bool>,
+}
+
+/// Latest NIP-65 relay list published by a pubkey
+#[derive(Debug, Serialize, Clone)]
+pub struct Nip65RelayList {
+    pub event_id: String,
+    pub created_at: u64,
+    pub relays: Vec<Nip65Relay>,
+}
+
+/// Per-relay outcome of `publish_to_write_relays`
+#[derive(Debug, Serialize, Clone)]
+pub struct RelayPublishResult {
+    pub url: String,
+    pub ok: bool,
+    pub message: Option<String>,
+}
+
+/// Per-relay outcome of `publish_to_relays`
+#[derive(Debug, Serialize, Clone)]
+pub struct RelayPublishOutcome {
+    pub url: String,
+    /// The relay answered OK with `true`
+    pub accepted: bool,
+    /// The relay's OK message, or why no verdict arrived
+    pub message: Option<String>,
+}
+
+/// Verdict for one relay in `publish_event_multi`
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RelayDeliveryStatus {
+    /// OK with `true`
+    Accepted,
+    /// OK with `false`
+    Rejected,
+    /// No OK before the deadline
+    Timeout,
+    /// Never sent: invalid URL, not connected, read-only, or the send failed
+    Failed,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RelayDelivery {
+    pub status: RelayDeliveryStatus,
+    pub message: Option<String>,
+}
+
+// Traffic counters for one live connection, shared with its read/write tasks
+struct RelayConnectionMetrics {
+    messages_sent: AtomicU64,
+    messages_received: AtomicU64,
+    bytes_received: AtomicU64,
+    connected_since: u64,    // unix ms
+    last_pong_at: AtomicU64, // unix ms, 0 = no Pong yet
+}
+
+impl RelayConnectionMetrics {
+    fn new() -> Self {
+        RelayConnectionMetrics {
+            messages_sent: AtomicU64::new(0),
+            messages_received: AtomicU64::new(0),
+            bytes_received: AtomicU64::new(0),
+            connected_since: now_unix_ms(),
+            last_pong_at: AtomicU64::new(0),
+        }
+    }
+
+    fn record_sent(&self) {
+        self.messages_sent.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_pong(&self) {
+        self.last_pong_at.store(now_unix_ms(), Ordering::Relaxed);
+    }
+
+    fn record_received(&self, bytes: usize) {
+        self.messages_received.fetch_add(1, Ordering::Relaxed);
+        self.bytes_received
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+}
+
+/// NIP-42 state of one connection; a reconnect starts unauthenticated with a new challenge
+#[derive(Default)]
+struct ConnectionAuth {
+    // Id of the AUTH event sent on this connection, matched against the relay's OK
+    event_id: Mutex<Option<String>>,
+    authenticated: AtomicBool,
+    authenticated_notify: tokio::sync::Notify,
+}
+
+impl ConnectionAuth {
+    fn record_ok(&self, event_id: &str, ok: bool) {
+        if ok && self.event_id.lock().unwrap().as_deref() == Some(event_id) {
+            self.authenticated.store(true, Ordering::SeqCst);
+            self.authenticated_notify.notify_one();
+        }
+    }
 }
 
 // Active relay connection (ephemeral)
 struct RelayConnection {
     tx: Sender<Message>,
+    metrics: Arc<RelayConnectionMetrics>,
+    auth: Arc<ConnectionAuth>,
+    reconnect_count: u64,
+}
+
+impl RelayConnection {
+    fn metrics_snapshot(&self, url: &str) -> RelayMetrics {
+        RelayMetrics {
+            url: url.to_string(),
+            messages_sent: self.metrics.messages_sent.load(Ordering::Relaxed),
+            messages_received: self.metrics.messages_received.load(Ordering::Relaxed),
+            bytes_received: self.metrics.bytes_received.load(Ordering::Relaxed),
+            connected_since: self.metrics.connected_since,
+            reconnect_count: self.reconnect_count,
+            authenticated: self.auth.authenticated.load(Ordering::SeqCst),
+            last_pong_at: match self.metrics.last_pong_at.load(Ordering::Relaxed) {
+                0 => None,
+                at => Some(at),
+            },
+        }
+    }
+}
+
+/// Per-relay throughput counters for the diagnostics view
+#[derive(Debug, Serialize, Clone)]
+pub struct RelayMetrics {
+    pub url: String,
+    pub messages_sent: u64,
+    pub messages_received: u64,
+    pub bytes_received: u64,
+    pub connected_since: u64,      // unix ms
+    pub reconnect_count: u64,      // successful connects after the first, for this window
+    pub authenticated: bool,       // NIP-42 AUTH accepted on this connection
+    pub last_pong_at: Option<u64>, // unix ms of the last Pong, for keepalive tracking
+}
+
+/// Relay pool state captured by `export_diagnostics`; carries no message contents
+#[derive(Debug, Serialize)]
+pub struct RelayPoolDiagnostics {
+    pub connections: Vec<WindowRelayMetrics>,
+    /// Connects still handshaking
+    pub pending_connects: Vec<WindowRelay>,
+    pub saved: Vec<SavedRelay>,
+    pub reconnects_paused: bool,
+    /// Latest `relay-status` changes across windows, oldest first
+    pub status_history: Vec<RelayStatusTransition>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RelayStatusTransition {
+    pub at: u64, // unix ms
+    pub window: String,
+    pub url: String,
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WindowRelayMetrics {
+    pub window: String,
+    #[serde(flatten)]
+    pub metrics: RelayMetrics,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WindowRelay {
+    pub window: String,
+    pub url: String,
+}
+
+fn now_unix_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
 }
 
 // Manage all relay connections and their persistent states
@@ -210,7 +1073,59 @@ pub struct RelayPool {
     // Keys are (window_label, relay_url)
     connections: Arc<Mutex<HashMap<(String, RelayUrl), RelayConnection>>>,
     states: Arc<Mutex<HashMap<(String, RelayUrl), RelayState>>>,
+    // Relays each window asked to connect and has not disconnected since; the only ones
+    // reconnect paths dial besides the saved list. Keys are (window_label, relay_url)
+    wanted: Arc<Mutex<HashSet<(String, RelayUrl)>>>,
     pending_acks: Arc<Mutex<HashMap<PendingAckKey, PendingRelayAck>>>,
+    // One-shot subscriptions owned by `fetch_events`; their frames bypass the webview.
+    // Keys are (window_label, relay_url, sub_id)
+    sub_collectors: Arc<Mutex<HashMap<SubCollectorKey, UnboundedSender<Value>>>>,
+    // Per-relay proxy overrides, shared across windows. Keys are relay_url.
+    proxy_overrides: Arc<Mutex<HashMap<RelayUrl, ConnectionRoute>>>,
+    // Per-relay TLS server name overrides, validated with `parse_tls_sni`
+    tls_sni_overrides: Arc<Mutex<HashMap<RelayUrl, String>>>,
+    // Per-relay WebSocket upgrade headers, shared across windows. Memory only: values
+    // may be secrets, so they are never written to relays.json
+    upgrade_headers: Arc<Mutex<HashMap<RelayUrl, UpgradeHeaders>>>,
+    // Connects currently handshaking. Keys are (window_label, relay_url)
+    connects_inflight: Arc<Mutex<HashSet<(String, RelayUrl)>>>,
+    // Relays the user wants connected, mirrored to relays.json; shared across windows
+    saved: Arc<Mutex<Vec<SavedRelay>>>,
+    // Set by `pause_reconnects`; automatic connects fail fast and in-flight ones abort
+    reconnects_paused: Arc<AtomicBool>,
+    // Last error per (window_label, relay_url), cleared once the relay connects
+    last_errors: Arc<Mutex<HashMap<(String, RelayUrl), String>>>,
+    // Windows that enabled `relay-pool-summary`, and those with an emit already scheduled
+    summary_windows: Arc<Mutex<HashSet<String>>>,
+    summary_scheduled: Arc<Mutex<HashSet<String>>>,
+    // Unexpected connection drops inside MASS_DISCONNECT_WINDOW_MS, oldest first
+    recent_drops: Arc<Mutex<VecDeque<Instant>>>,
+    // A coordinated network-recovery reconnect is pending
+    recovery_scheduled: Arc<AtomicBool>,
+    // Windows whose subscriptions are paused: kept in `states`, CLOSEd on the relays
+    subscriptions_paused: Arc<Mutex<HashMap<String, SubscriptionPause>>>,
+    // Persisted setting: pause a window's subscriptions while it is hidden
+    pause_subscriptions_when_hidden: Arc<AtomicBool>,
+    // Windows that receive stored events as `relay-events-batch`
+    event_batch_windows: Arc<Mutex<HashSet<String>>>,
+    // Buffered stored events. Keys are (window_label, sub_id)
+    event_batches: Arc<Mutex<HashMap<EventBatchKey, Vec<RelayMessage>>>>,
+    // Cap on open plus handshaking connections across all windows (0 = no cap)
+    max_connections: Arc<AtomicUsize>,
+    // Connects turned away by the cap, oldest first; started as slots free up
+    connect_queue: Arc<Mutex<VecDeque<(String, RelayUrl)>>>,
+    // Latest status changes for diagnostics, capped at RELAY_STATUS_HISTORY_CAPACITY
+    status_history: Arc<Mutex<VecDeque<RelayStatusTransition>>>,
+    // Handshakes bulk connect paths may run at once, across all windows. Replaced (not
+    // resized) by `set_connect_concurrency`; connects holding the old one finish normally
+    bulk_connect_gate: Arc<Mutex<Arc<Semaphore>>>,
+}
+
+/// Why a window's subscriptions are paused; showing the window only undoes `Hidden`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SubscriptionPause {
+    Manual,
+    Hidden,
 }
 
 impl RelayPool {
@@ -218,18 +1133,739 @@ impl RelayPool {
         RelayPool {
             connections: Arc::new(Mutex::new(HashMap::new())),
             states: Arc::new(Mutex::new(HashMap::new())),
+            wanted: Arc::new(Mutex::new(HashSet::new())),
             pending_acks: Arc::new(Mutex::new(HashMap::new())),
+            sub_collectors: Arc::new(Mutex::new(HashMap::new())),
+            proxy_overrides: Arc::new(Mutex::new(HashMap::new())),
+            tls_sni_overrides: Arc::new(Mutex::new(HashMap::new())),
+            upgrade_headers: Arc::new(Mutex::new(HashMap::new())),
+            connects_inflight: Arc::new(Mutex::new(HashSet::new())),
+            saved: Arc::new(Mutex::new(Vec::new())),
+            reconnects_paused: Arc::new(AtomicBool::new(false)),
+            last_errors: Arc::new(Mutex::new(HashMap::new())),
+            summary_windows: Arc::new(Mutex::new(HashSet::new())),
+            summary_scheduled: Arc::new(Mutex::new(HashSet::new())),
+            recent_drops: Arc::new(Mutex::new(VecDeque::new())),
+            recovery_scheduled: Arc::new(AtomicBool::new(false)),
+            subscriptions_paused: Arc::new(Mutex::new(HashMap::new())),
+            pause_subscriptions_when_hidden: Arc::new(AtomicBool::new(false)),
+            event_batch_windows: Arc::new(Mutex::new(HashSet::new())),
+            event_batches: Arc::new(Mutex::new(HashMap::new())),
+            max_connections: Arc::new(AtomicUsize::new(0)),
+            connect_queue: Arc::new(Mutex::new(VecDeque::new())),
+            status_history: Arc::new(Mutex::new(VecDeque::new())),
+            bulk_connect_gate: Arc::new(Mutex::new(Arc::new(Semaphore::new(
+                DEFAULT_CONNECT_CONCURRENCY,
+            )))),
         }
     }
 
-    pub async fn publish_event_with_ack(
-        &self,
-        window_label: &str,
-        relay_url: &str,
-        event_json: Value,
+    /// Seed the saved relay list loaded at startup, applying its proxy overrides.
+    pub fn restore_saved_relays(&self, relays: Vec<SavedRelay>) {
+        let mut seen = HashSet::new();
+        let relays: Vec<SavedRelay> = relays
+            .into_iter()
+            .filter_map(|relay| match normalize_relay_url(&relay.url) {
+                Ok(url) if seen.insert(url.clone()) => Some(SavedRelay { url, ..relay }),
+                Ok(_) => None,
+                Err(e) => {
+                    log::warn!("[NativeRelay] Dropping saved relay: {}", e);
+                    None
+                }
+            })
+            .collect();
+        let mut overrides = self.proxy_overrides.lock().unwrap();
+        for relay in &relays {
+            match relay.proxy.as_deref().map(parse_relay_proxy) {
+                Some(Ok(route)) => {
+                    overrides.insert(relay.url.clone(), route);
+                }
+                Some(Err(e)) => log::warn!(
+                    "[NativeRelay] Ignoring saved proxy for {}: {}",
+                    relay.url,
+                    e
+                ),
+                None => {}
+            }
+        }
+        drop(overrides);
+        let mut tls_sni_overrides = self.tls_sni_overrides.lock().unwrap();
+        for relay in &relays {
+            match relay.tls_sni.as_deref().map(parse_tls_sni) {
+                Some(Ok(tls_sni)) => {
+                    tls_sni_overrides.insert(relay.url.clone(), tls_sni);
+                }
+                Some(Err(e)) => log::warn!(
+                    "[NativeRelay] Ignoring saved TLS SNI for {}: {}",
+                    relay.url,
+                    e
+                ),
+                None => {}
+            }
+        }
+        drop(tls_sni_overrides);
+        *self.saved.lock().unwrap() = relays;
+    }
+
+    /// Remember relay errors for the summary and schedule one if the window wants it.
+    fn note_status(&self, app: &AppHandle, window_label: &str, url: &str, status: &RelayStatus) {
+        let key = (window_label.to_string(), url.to_string());
+        {
+            let mut history = self.status_history.lock().unwrap();
+            if history.len() >= RELAY_STATUS_HISTORY_CAPACITY {
+                history.pop_front();
+            }
+            history.push_back(RelayStatusTransition {
+                at: now_unix_ms(),
+                window: window_label.to_string(),
+                url: url.to_string(),
+                status: status.as_str(),
+                error: match status {
+                    RelayStatus::Error { error, .. } => Some(error.clone()),
+                    _ => None,
+                },
+            });
+        }
+        match status {
+            RelayStatus::Error { error, .. } => {
+                self.last_errors.lock().unwrap().insert(key, error.clone());
+            }
+            RelayStatus::Connected => {
+                self.last_errors.lock().unwrap().remove(&key);
+            }
+            _ => {}
+        }
+        self.schedule_summary(app, window_label);
+    }
+
+    /// Emit `relay-pool-summary` to the window after the debounce delay, coalescing
+    /// every change made in the meantime into one event.
+    fn schedule_summary(&self, app: &AppHandle, window_label: &str) {
+        if !self.summary_windows.lock().unwrap().contains(window_label) {
+            return;
+        }
+        if !self
+            .summary_scheduled
+            .lock()
+            .unwrap()
+            .insert(window_label.to_string())
+        {
+            return;
+        }
+        let app = app.clone();
+        let window_label = window_label.to_string();
+        tauri::async_runtime::spawn(async move {
+            sleep(Duration::from_millis(RELAY_SUMMARY_DEBOUNCE_MS)).await;
+            let pool = app.state::<RelayPool>();
+            pool.summary_scheduled.lock().unwrap().remove(&window_label);
+            let summary = pool.summary(&window_label);
+            if let Some(window) = app.get_webview_window(&window_label) {
+                let _ = window.emit("relay-pool-summary", summary);
+            }
+        });
+    }
+
+    fn summary(&self, window_label: &str) -> RelayPoolSummary {
+        let connected = window_relays(self.connections.lock().unwrap().keys(), window_label);
+        let connecting = window_relays(self.connects_inflight.lock().unwrap().iter(), window_label);
+        let mut desired = window_relays(self.wanted.lock().unwrap().iter(), window_label);
+        desired.extend(
+            self.saved
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|relay| relay.url.clone()),
+        );
+        desired.extend(connected.iter().cloned());
+        desired.extend(connecting.iter().cloned());
+        let errors: HashMap<RelayUrl, String> = self
+            .last_errors
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(key, _)| key.0 == window_label)
+            .map(|(key, error)| (key.1.clone(), error.clone()))
+            .collect();
+        summarize_relays(&desired, &connected, &connecting, &errors)
+    }
+
+    /// Deliver an EVENT/EOSE/CLOSED frame (or any other) to the window. With batching on,
+    /// a subscription's stored events are buffered; live events after EOSE bypass the buffer,
+    /// and EOSE or CLOSED flushes it first so the frontend sees them after the batch.
+    fn emit_relay_frame(
+        &self,
+        app: &AppHandle,
+        window: &WebviewWindow,
+        window_label: &str,
+        url: &str,
+        frame: Value,
+    ) {
+        let Some((kind, sub_id)) = subscription_frame(&frame) else {
+            emit_relay_event(window, url, frame);
+            return;
+        };
+        let sub_id = sub_id.to_string();
+        let eose_received = {
+            let mut states = self.states.lock().unwrap();
+            let relay_state = states.get_mut(&(window_label.to_string(), url.to_string()));
+            match (kind, relay_state) {
+                ("EOSE", Some(relay_state)) => {
+                    relay_state.eose_received.insert(sub_id.clone());
+                    true
+                }
+                (_, Some(relay_state)) => relay_state.eose_received.contains(&sub_id),
+                (_, None) => false,
+            }
+        };
+        if !self
+            .event_batch_windows
+            .lock()
+            .unwrap()
+            .contains(window_label)
+        {
+            emit_relay_event(window, url, frame);
+            return;
+        }
+
+        let key = (window_label.to_string(), sub_id);
+        if kind == "EVENT" && !eose_received {
+            let message = RelayMessage {
+                relay_url: url.to_string(),
+                payload: frame,
+            };
+            let (first, full) = {
+                let mut batches = self.event_batches.lock().unwrap();
+                let batch = batches.entry(key.clone()).or_default();
+                batch.push(message);
+                (
+                    batch.len() == 1,
+                    batch.len() >= RELAY_EVENT_BATCH_MAX_EVENTS,
+                )
+            };
+            if full {
+                self.flush_event_batch(app, &key);
+            } else if first {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    sleep(Duration::from_millis(RELAY_EVENT_BATCH_INTERVAL_MS)).await;
+                    app.state::<RelayPool>().flush_event_batch(&app, &key);
+                });
+            }
+            return;
+        }
+        self.flush_event_batch(app, &key);
+        emit_relay_event(window, url, frame);
+    }
+
+    /// Emit whatever is buffered for `(window_label, sub_id)` as one `relay-events-batch`.
+    fn flush_event_batch(&self, app: &AppHandle, key: &EventBatchKey) {
+        let Some(events) = self.event_batches.lock().unwrap().remove(key) else {
+            return;
+        };
+        if let Some(window) = app.get_webview_window(&key.0) {
+            let _ = window.emit(
+                "relay-events-batch",
+                RelayEventBatch {
+                    sub_id: key.1.clone(),
+                    events,
+                },
+            );
+        }
+    }
+
+    /// Count a connection the relay or network dropped; a burst of them starts a recovery.
+    fn record_drop(&self, app: &AppHandle) {
+        let now = Instant::now();
+        let burst = {
+            let mut drops = self.recent_drops.lock().unwrap();
+            drops.push_back(now);
+            while drops.front().is_some_and(|at| {
+                now.duration_since(*at) > Duration::from_millis(MASS_DISCONNECT_WINDOW_MS)
+            }) {
+                drops.pop_front();
+            }
+            drops.len() >= MASS_DISCONNECT_THRESHOLD
+        };
+        if burst {
+            self.schedule_network_recovery(app, "mass disconnect");
+        }
+    }
+
+    /// Reconnect every dropped relay in every window once the network settles, staggered,
+    /// instead of each relay retrying on its own. No-op while a recovery is pending.
+    pub fn schedule_network_recovery(&self, app: &AppHandle, reason: &str) {
+        if self.recovery_scheduled.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        log::info!("[NativeRelay] Network recovery scheduled ({})", reason);
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            sleep(Duration::from_millis(NETWORK_RECOVERY_SETTLE_MS)).await;
+            reconnect_after_network_recovery(app).await;
+        });
+    }
+
+    /// Replay a relay's persisted subscriptions on its connection, unless the window has
+    /// them paused. Returns the number of REQs sent.
+    fn replay_subscriptions(&self, key: &(String, RelayUrl), tx: &Sender<Message>) -> usize {
+        if self
+            .subscriptions_paused
+            .lock()
+            .unwrap()
+            .contains_key(&key.0)
+        {
+            return 0;
+        }
+        let subs_to_re = self
+            .states
+            .lock()
+            .unwrap()
+            .get_mut(key)
+            .map(|s| {
+                // Replayed REQs send their stored events again
+                s.eose_received.clear();
+                s.subscriptions.clone()
+            })
+            .unwrap_or_default();
+        let mut sent = 0;
+        for (sub_id, filters) in subs_to_re {
+            let frame = req_frame(&sub_id, &filters);
+            if enqueue_relay_message(tx, Message::Text(frame.into())).is_ok() {
+                sent += 1;
+                log::debug!("Auto-resubscribed to {} on {}", sub_id, key.1);
+            }
+        }
+        sent
+    }
+
+    fn window_connections(&self, window_label: &str) -> Vec<((String, RelayUrl), Sender<Message>)> {
+        self.connections
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(key, _)| key.0 == window_label)
+            .map(|(key, connection)| (key.clone(), connection.tx.clone()))
+            .collect()
+    }
+
+    /// CLOSE every subscription of a window on its live relays, keeping them in `states`.
+    /// Returns the number of CLOSEs sent; 0 when already paused.
+    fn pause_window_subscriptions(&self, window_label: &str, cause: SubscriptionPause) -> usize {
+        {
+            let mut paused = self.subscriptions_paused.lock().unwrap();
+            if paused.contains_key(window_label) {
+                // A manual pause outlives the window being shown again
+                if cause == SubscriptionPause::Manual {
+                    paused.insert(window_label.to_string(), cause);
+                }
+                return 0;
+            }
+            paused.insert(window_label.to_string(), cause);
+        }
+        let mut closed = 0;
+        for (key, tx) in self.window_connections(window_label) {
+            let sub_ids: Vec<String> = self
+                .states
+                .lock()
+                .unwrap()
+                .get(&key)
+                .map(|s| s.subscriptions.keys().cloned().collect())
+                .unwrap_or_default();
+            for sub_id in sub_ids {
+                let msg_json = serde_json::json!(["CLOSE", sub_id]);
+                if enqueue_relay_message(&tx, Message::Text(msg_json.to_string().into())).is_ok() {
+                    closed += 1;
+                }
+            }
+        }
+        closed
+    }
+
+    /// Lift a window's pause and re-issue its REQs. With `only_hidden`, a manual pause is
+    /// left alone. Returns the number of REQs sent.
+    fn resume_window_subscriptions(&self, window_label: &str, only_hidden: bool) -> usize {
+        {
+            let mut paused = self.subscriptions_paused.lock().unwrap();
+            match paused.get(window_label) {
+                None => return 0,
+                Some(SubscriptionPause::Manual) if only_hidden => return 0,
+                Some(_) => {
+                    paused.remove(window_label);
+                }
+            }
+        }
+        self.window_connections(window_label)
+            .iter()
+            .map(|(key, tx)| self.replay_subscriptions(key, tx))
+            .sum()
+    }
+
+    pub fn set_pause_subscriptions_when_hidden(&self, enabled: bool) {
+        self.pause_subscriptions_when_hidden
+            .store(enabled, Ordering::SeqCst);
+    }
+
+    /// Connection slots left under `max_connections`; `usize::MAX` when uncapped
+    fn free_connection_slots(&self) -> usize {
+        let max = self.max_connections.load(Ordering::SeqCst);
+        if max == 0 {
+            return usize::MAX;
+        }
+        let used =
+            self.connections.lock().unwrap().len() + self.connects_inflight.lock().unwrap().len();
+        max.saturating_sub(used)
+    }
+
+    /// Whether a connect started by `trigger` must wait for `resume_reconnects`
+    fn holds_back(&self, trigger: ConnectTrigger) -> bool {
+        trigger == ConnectTrigger::Automatic && self.reconnects_paused.load(Ordering::SeqCst)
+    }
+
+    /// Start up to `slots` queued connects, skipping relays that connected in the meantime.
+    fn connect_queued_relays(&self, app: &AppHandle, slots: usize) {
+        let mut started = 0;
+        while started < slots {
+            let Some(key) = self.connect_queue.lock().unwrap().pop_front() else {
+                return;
+            };
+            if self.connections.lock().unwrap().contains_key(&key)
+                || self.connects_inflight.lock().unwrap().contains(&key)
+            {
+                continue;
+            }
+            started += 1;
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let (window_label, url) = key;
+                let state = app.state::<RelayPool>();
+                let net_runtime = app.state::<NativeNetworkRuntime>();
+                if let Err(e) = connect_relay_internal(
+                    app.clone(),
+                    window_label,
+                    url.clone(),
+                    state,
+                    net_runtime,
+                    ConnectTrigger::Automatic,
+                )
+                .await
+                {
+                    log::warn!("[NativeRelay] Queued connect failed for {}: {}", url, e);
+                }
+            });
+        }
+    }
+
+    /// Record that a window wants `url` connected, so reconnect paths bring it back.
+    fn mark_wanted(&self, window_label: &str, url: &str) {
+        self.wanted
+            .lock()
+            .unwrap()
+            .insert((window_label.to_string(), url.to_string()));
+    }
+
+    /// Relays a window wants (saved or connected there) that are neither connected nor
+    /// connecting. Only `wanted` relays when `include_saved` is false.
+    fn pending_relays(&self, window_label: &str, include_saved: bool) -> Vec<RelayUrl> {
+        let wanted = self.wanted.lock().unwrap();
+        let saved = self.saved.lock().unwrap();
+        let connections = self.connections.lock().unwrap();
+        let inflight = self.connects_inflight.lock().unwrap();
+        let saved_urls = saved
+            .iter()
+            .filter(|_| include_saved)
+            .map(|relay| relay.url.clone());
+        let known = window_relays(wanted.iter(), window_label)
+            .into_iter()
+            .chain(saved_urls)
+            .collect::<HashSet<_>>();
+        known
+            .into_iter()
+            .filter(|url| {
+                let key = (window_label.to_string(), url.clone());
+                !connections.contains_key(&key) && !inflight.contains(&key)
+            })
+            .collect()
+    }
+
+    /// Move a subscription's paging cursor below the oldest event of a fetched page, so the
+    /// next `page_subscription` continues from there.
+    fn note_page_cursor(&self, window_label: &str, url: &str, sub_id: &str, events: &[Value]) {
+        let Some(created_at) = events
+            .iter()
+            .filter_map(|event| event.get("created_at").and_then(Value::as_u64))
+            .min()
+        else {
+            return;
+        };
+        let key = (window_label.to_string(), url.to_string());
+        if let Some(relay_state) = self.states.lock().unwrap().get_mut(&key) {
+            let oldest = relay_state
+                .oldest_seen
+                .entry(sub_id.to_string())
+                .or_insert(created_at);
+            *oldest = (*oldest).min(created_at);
+        }
+    }
+
+    /// Stop wanting a relay in a window: no reconnect path dials it again. Unregisters and
+    /// returns its live connection, if any, for the caller to close.
+    fn release_relay(&self, key: &(String, RelayUrl)) -> Option<Sender<Message>> {
+        self.wanted.lock().unwrap().remove(key);
+        self.connect_queue
+            .lock()
+            .unwrap()
+            .retain(|queued| queued != key);
+        self.connections.lock().unwrap().remove(key).map(|c| c.tx)
+    }
+
+    /// Forget everything kept for a window that no longer exists and close its connections.
+    fn forget_window(&self, window_label: &str) -> Vec<(RelayUrl, Sender<Message>)> {
+        self.wanted
+            .lock()
+            .unwrap()
+            .retain(|key| key.0 != window_label);
+        self.states
+            .lock()
+            .unwrap()
+            .retain(|key, _| key.0 != window_label);
+        self.connect_queue
+            .lock()
+            .unwrap()
+            .retain(|key| key.0 != window_label);
+        self.last_errors
+            .lock()
+            .unwrap()
+            .retain(|key, _| key.0 != window_label);
+        self.subscriptions_paused
+            .lock()
+            .unwrap()
+            .remove(window_label);
+        self.summary_windows.lock().unwrap().remove(window_label);
+        self.event_batch_windows
+            .lock()
+            .unwrap()
+            .remove(window_label);
+        let mut connections = self.connections.lock().unwrap();
+        let keys: Vec<(String, RelayUrl)> = connections
+            .keys()
+            .filter(|key| key.0 == window_label)
+            .cloned()
+            .collect();
+        keys.into_iter()
+            .filter_map(|key| connections.remove(&key).map(|conn| (key.1, conn.tx)))
+            .collect()
+    }
+
+    /// Snapshot of every window's connections for a diagnostics export.
+    pub fn diagnostics(&self) -> RelayPoolDiagnostics {
+        let mut connections: Vec<WindowRelayMetrics> = self
+            .connections
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|((window, url), connection)| WindowRelayMetrics {
+                window: window.clone(),
+                metrics: connection.metrics_snapshot(url),
+            })
+            .collect();
+        connections.sort_by(|a, b| (&a.window, &a.metrics.url).cmp(&(&b.window, &b.metrics.url)));
+        let mut pending_connects: Vec<WindowRelay> = self
+            .connects_inflight
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(window, url)| WindowRelay {
+                window: window.clone(),
+                url: url.clone(),
+            })
+            .collect();
+        pending_connects.sort_by(|a, b| (&a.window, &a.url).cmp(&(&b.window, &b.url)));
+        RelayPoolDiagnostics {
+            connections,
+            pending_connects,
+            saved: self.saved.lock().unwrap().clone(),
+            reconnects_paused: self.reconnects_paused.load(Ordering::SeqCst),
+            status_history: self
+                .status_history
+                .lock()
+                .unwrap()
+                .iter()
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Give a window's first state for a saved relay the saved marker. Returns whether `url` is saved.
+    fn seed_state_from_saved(&self, window_label: &str, url: &str) -> bool {
+        let Some(marker) = self
+            .saved
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|relay| relay.url == url)
+            .map(|relay| relay.marker)
+        else {
+            return false;
+        };
+        self.states
+            .lock()
+            .unwrap()
+            .entry((window_label.to_string(), url.to_string()))
+            .or_insert_with(|| RelayState {
+                marker,
+                ..RelayState::default()
+            });
+        true
+    }
+
+    /// Update the saved list and write it to disk; write failures are logged, not fatal.
+    fn update_saved_relays(
+        &self,
+        app: &AppHandle,
+        update: impl FnOnce(&mut Vec<SavedRelay>) -> bool,
+    ) {
+        let snapshot = {
+            let mut saved = self.saved.lock().unwrap();
+            if !update(&mut saved) {
+                return;
+            }
+            saved.clone()
+        };
+        if let Err(e) = write_saved_relays(app, &snapshot) {
+            log::warn!("[NativeRelay] Failed to save relay list: {}", e);
+        }
+    }
+
+    /// Route for `url`: its proxy override if one is set, else the global network setting.
+    pub fn route_for(&self, url: &str, net_runtime: &NativeNetworkRuntime) -> ConnectionRoute {
+        let url = normalize_relay_url(url).unwrap_or_else(|_| url.to_string());
+        self.proxy_overrides
+            .lock()
+            .unwrap()
+            .get(&url)
+            .cloned()
+            .unwrap_or_else(|| net_runtime.default_route())
+    }
+
+    /// Upgrade headers set for `url` through `connect_relay`, empty when none.
+    pub fn headers_for(&self, url: &str) -> UpgradeHeaders {
+        let url = normalize_relay_url(url).unwrap_or_else(|_| url.to_string());
+        self.upgrade_headers
+            .lock()
+            .unwrap()
+            .get(&url)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// TLS server name override for `url`, None to use the relay host.
+    pub fn tls_sni_for(&self, url: &str) -> Option<String> {
+        let url = normalize_relay_url(url).unwrap_or_else(|_| url.to_string());
+        self.tls_sni_overrides.lock().unwrap().get(&url).cloned()
+    }
+
+    fn marker_for(&self, window_label: &str, url: &str) -> RelayMarker {
+        let key = (window_label.to_string(), url.to_string());
+        self.states
+            .lock()
+            .unwrap()
+            .get(&key)
+            .map(|relay_state| relay_state.marker)
+            .unwrap_or_default()
+    }
+
+    /// Cap the publishes held for `relay_url` while it reconnects (0 disables); returns the
+    /// applied capacity
+    fn set_publish_buffer(
+        &self,
+        window_label: &str,
+        relay_url: &str,
+        capacity: usize,
+    ) -> Result<usize, String> {
+        let relay_url = normalize_relay_url(relay_url)?;
+        let capacity = capacity.min(MAX_PUBLISH_BUFFER_CAPACITY);
+        let mut states = self.states.lock().unwrap();
+        let relay_state = states
+            .entry((window_label.to_string(), relay_url))
+            .or_default();
+        relay_state.publish_buffer_capacity = capacity;
+        relay_state.pending_publishes.truncate(capacity);
+        Ok(capacity)
+    }
+
+    /// Send an EVENT frame on the open connection to the (normalized) `relay_url`, or hold
+    /// it in the relay's publish buffer while it reconnects
+    fn send_or_buffer_publish(
+        &self,
+        window_label: &str,
+        relay_url: &str,
+        msg_str: String,
+    ) -> Result<String, String> {
+        let key = (window_label.to_string(), relay_url.to_string());
+
+        // Check the connection while holding the state lock so a concurrent connect
+        // either sees the queued event when it flushes or is visible here.
+        let tx = {
+            let mut states = self.states.lock().unwrap();
+            let tx = {
+                let connections = self.connections.lock().unwrap();
+                connections.get(&key).map(|c| c.tx.clone())
+            };
+            match (tx, states.get_mut(&key)) {
+                (Some(tx), _) => tx,
+                (None, Some(relay_state)) if relay_state.publish_buffer_capacity > 0 => {
+                    if relay_state.pending_publishes.len() >= relay_state.publish_buffer_capacity {
+                        return Err("Not connected (publish buffer full)".to_string());
+                    }
+                    relay_state.pending_publishes.push_back(msg_str);
+                    return Ok("Queued".to_string());
+                }
+                (None, _) => return Err("Not connected".to_string()),
+            }
+        };
+
+        enqueue_relay_message(&tx, Message::Text(msg_str.into()))?;
+        Ok("Published".to_string())
+    }
+
+    pub async fn publish_event_with_ack(
+        &self,
+        window_label: &str,
+        relay_url: &str,
+        event_json: Value,
         ack_timeout: Duration,
     ) -> Result<RelayPublishAck, String> {
+        let relay_url = normalize_relay_url(relay_url)?;
         let event_id = extract_event_id(&event_json)?;
+        let payload = serde_json::json!(["EVENT", event_json]);
+        let ack = self
+            .send_awaiting_ok(
+                window_label,
+                &relay_url,
+                event_id,
+                payload.to_string(),
+                ack_timeout,
+            )
+            .await?;
+        if ack.ok {
+            Ok(ack)
+        } else {
+            Err(ack
+                .message
+                .unwrap_or_else(|| "Relay rejected event (NIP-20 OK=false).".to_string()))
+        }
+    }
+
+    /// Send a raw EVENT/AUTH frame and wait for the OK carrying `event_id`. A rejection is
+    /// returned as an ack with `ok: false`; only transport failures and timeouts are errors.
+    async fn send_awaiting_ok(
+        &self,
+        window_label: &str,
+        relay_url: &str,
+        event_id: String,
+        frame: String,
+        ack_timeout: Duration,
+    ) -> Result<RelayPublishAck, AwaitOkError> {
         let key = (window_label.to_string(), relay_url.to_string());
         let tx = {
             let connections = self.connections.lock().unwrap();
@@ -238,7 +1874,9 @@ impl RelayPool {
                 .map(|connection| connection.tx.clone())
         };
         let Some(tx) = tx else {
-            return Err("No writable relay connection".to_string());
+            return Err(AwaitOkError::Failed(
+                "No writable relay connection".to_string(),
+            ));
         };
 
         let pending_key = (window_label.to_string(), relay_url.to_string(), event_id);
@@ -254,33 +1892,50 @@ impl RelayPool {
             pending_acks.insert(pending_key.clone(), PendingRelayAck { sender: ack_tx });
         }
 
-        let payload = serde_json::json!(["EVENT", event_json]);
-        if let Err(error) = enqueue_relay_message(&tx, Message::Text(payload.to_string().into())) {
+        if let Err(error) = enqueue_relay_message(&tx, Message::Text(frame.into())) {
             let mut pending_acks = self.pending_acks.lock().unwrap();
             pending_acks.remove(&pending_key);
-            return Err(error);
+            return Err(AwaitOkError::Failed(error));
         }
 
         match timeout(ack_timeout, ack_rx).await {
-            Ok(Ok(ack)) => {
-                if ack.ok {
-                    Ok(ack)
-                } else {
-                    Err(ack
-                        .message
-                        .unwrap_or_else(|| "Relay rejected event (NIP-20 OK=false).".to_string()))
-                }
-            }
-            Ok(Err(_)) => Err("Relay acknowledgement channel closed.".to_string()),
+            Ok(Ok(ack)) => Ok(ack),
+            Ok(Err(_)) => Err(AwaitOkError::Failed(
+                "Relay acknowledgement channel closed.".to_string(),
+            )),
             Err(_) => {
                 let mut pending_acks = self.pending_acks.lock().unwrap();
                 pending_acks.remove(&pending_key);
-                Err("Timeout waiting for OK response".to_string())
+                Err(AwaitOkError::Timeout)
             }
         }
     }
 }
 
+/// Why `send_awaiting_ok` got no OK verdict; a relay rejection is an ack, not an error
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum AwaitOkError {
+    /// No OK before the deadline
+    Timeout,
+    /// The frame never went out, or the connection went away before the OK
+    Failed(String),
+}
+
+impl std::fmt::Display for AwaitOkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AwaitOkError::Timeout => f.write_str("Timeout waiting for OK response"),
+            AwaitOkError::Failed(message) => f.write_str(message),
+        }
+    }
+}
+
+impl From<AwaitOkError> for String {
+    fn from(error: AwaitOkError) -> Self {
+        error.to_string()
+    }
+}
+
 fn extract_event_id(event_json: &Value) -> Result<String, String> {
     let Some(event_id) = event_json.get("id").and_then(Value::as_str) else {
         return Err("Malformed event payload: missing event id".to_string());
@@ -291,6 +1946,84 @@ fn extract_event_id(event_json: &Value) -> Result<String, String> {
     Ok(event_id.to_string())
 }
 
+/// How long after a publish a NOTICE is attributed to that event
+const NOTICE_CORRELATION_WINDOW_MS: u64 = 5_000;
+
+/// NIP-01 machine-readable prefixes relays put in front of OK/CLOSED/NOTICE messages
+const RELAY_MESSAGE_PREFIXES: [&str; 9] = [
+    "duplicate",
+    "pow",
+    "blocked",
+    "rate-limited",
+    "invalid",
+    "restricted",
+    "auth-required",
+    "payment-required",
+    "error",
+];
+
+/// Last EVENT written to a relay, for attributing a following NOTICE
+struct RecentPublish {
+    event_id: String,
+    sent_at_ms: u64,
+}
+
+/// `relay-notice` event payload
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayNotice {
+    pub relay_url: String,
+    pub message: String,
+    /// Prefix such as `restricted` or `rate-limited`, when the relay used one
+    pub reason: Option<String>,
+    /// Event published to this relay just before the NOTICE; a heuristic, not an ack
+    pub related_event_id: Option<String>,
+}
+
+fn published_event_id(frame: &str) -> Option<String> {
+    framed_event_id(frame, "EVENT")
+}
+
+/// Id of the event in a client `["AUTH", <event>]` frame
+fn auth_event_id(frame: &str) -> Option<String> {
+    framed_event_id(frame, "AUTH")
+}
+
+fn framed_event_id(frame: &str, verb: &str) -> Option<String> {
+    if !frame.starts_with(&format!("[\"{verb}\"")) {
+        return None;
+    }
+    let value = serde_json::from_str::<Value>(frame).ok()?;
+    extract_event_id(value.get(1)?).ok()
+}
+
+/// A relay `["AUTH", <challenge>]`, or a CLOSED whose reason is `auth-required:`
+fn requests_auth(value: &Value) -> bool {
+    match value.as_array().map(Vec::as_slice) {
+        Some([Value::String(verb), Value::String(_)]) => verb == "AUTH",
+        Some([Value::String(verb), _, Value::String(reason), ..]) if verb == "CLOSED" => {
+            relay_message_reason(reason).as_deref() == Some("auth-required")
+        }
+        _ => false,
+    }
+}
+
+fn parse_notice_payload(value: &Value) -> Option<String> {
+    let array = value.as_array()?;
+    if array.first()?.as_str()? != "NOTICE" {
+        return None;
+    }
+    Some(array.get(1)?.as_str()?.to_string())
+}
+
+fn relay_message_reason(message: &str) -> Option<String> {
+    let (prefix, _) = message.split_once(':')?;
+    let prefix = prefix.trim();
+    RELAY_MESSAGE_PREFIXES
+        .contains(&prefix)
+        .then(|| prefix.to_string())
+}
+
 fn parse_ok_payload(value: &Value) -> Option<(String, bool, Option<String>)> {
     let array = value.as_array()?;
     if array.first()?.as_str()? != "OK" {
@@ -328,6 +2061,98 @@ fn resolve_pending_ack(
     }
 }
 
+fn emit_relay_event(window: &WebviewWindow, url: &str, frame: Value) {
+    let _ = window.emit(
+        "relay-event",
+        RelayMessage {
+            relay_url: url.to_string(),
+            payload: frame,
+        },
+    );
+}
+
+/// `(kind, sub_id)` of an EVENT/EOSE/CLOSED frame
+fn subscription_frame(frame: &Value) -> Option<(&str, &str)> {
+    let array = frame.as_array()?;
+    let kind = array.first()?.as_str()?;
+    if !matches!(kind, "EVENT" | "EOSE" | "CLOSED") {
+        return None;
+    }
+    Some((kind, array.get(1)?.as_str()?))
+}
+
+/// Collector registered for this EVENT/EOSE/CLOSED frame's subscription, if any.
+fn collector_for_frame(
+    sub_collectors: &Arc<Mutex<HashMap<SubCollectorKey, UnboundedSender<Value>>>>,
+    window_label: &str,
+    relay_url: &str,
+    frame: &Value,
+) -> Option<UnboundedSender<Value>> {
+    let (_, sub_id) = subscription_frame(frame)?;
+    let key = (
+        window_label.to_string(),
+        relay_url.to_string(),
+        sub_id.to_string(),
+    );
+    sub_collectors.lock().unwrap().get(&key).cloned()
+}
+
+/// `(sub_id, created_at)` of an `["EVENT", sub_id, event]` frame
+fn event_frame_cursor(frame: &Value) -> Option<(&str, u64)> {
+    let array = frame.as_array()?;
+    if array.first()?.as_str()? != "EVENT" {
+        return None;
+    }
+    let sub_id = array.get(1)?.as_str()?;
+    let created_at = array.get(2)?.get("created_at")?.as_u64()?;
+    Some((sub_id, created_at))
+}
+
+/// `["REQ", sub_id, filter1, filter2, …]`
+fn req_frame(sub_id: &str, filters: &[Value]) -> String {
+    let mut frame = Vec::with_capacity(filters.len() + 2);
+    frame.push(Value::from("REQ"));
+    frame.push(Value::from(sub_id));
+    frame.extend(filters.iter().cloned());
+    Value::Array(frame).to_string()
+}
+
+/// Filters for `subscribe_relay`: `filters` when given, else the legacy single `filter`
+/// (an array there is taken as a list of filters). Every filter must be an object.
+fn subscription_filters(
+    filter: Option<Value>,
+    filters: Option<Vec<Value>>,
+) -> Result<Vec<Value>, String> {
+    let filters = match (filters, filter) {
+        (Some(filters), _) => filters,
+        (None, Some(Value::Array(filters))) => filters,
+        (None, Some(filter)) => vec![filter],
+        (None, None) => Vec::new(),
+    };
+    if filters.is_empty() {
+        return Err("At least one filter is required".to_string());
+    }
+    if !filters.iter().all(Value::is_object) {
+        return Err("Each filter must be a JSON object".to_string());
+    }
+    Ok(filters)
+}
+
+/// `filters` bounded to events before `oldest`
+fn paged_filters(filters: &[Value], oldest: u64, limit: usize) -> Vec<Value> {
+    filters
+        .iter()
+        .map(|filter| {
+            let mut filter = filter.clone();
+            if let Some(object) = filter.as_object_mut() {
+                object.insert("until".to_string(), Value::from(oldest.saturating_sub(1)));
+                object.insert("limit".to_string(), Value::from(limit));
+            }
+            filter
+        })
+        .collect()
+}
+
 fn fail_pending_acks_for_scope_relay(
     pending_acks: &Arc<Mutex<HashMap<PendingAckKey, PendingRelayAck>>>,
     window_label: &str,
@@ -356,6 +2181,14 @@ fn fail_pending_acks_for_scope_relay(
     }
 }
 
+/// What started a connect. `pause_reconnects` only holds back automatic ones (network
+/// recovery, route changes, queued connects); a connect a command asked for goes through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConnectTrigger {
+    Explicit,
+    Automatic,
+}
+
 // Command: Connect to a relay
 // Internal: Connect to a relay for a specific window
 async fn connect_relay_internal(
@@ -364,162 +2197,236 @@ async fn connect_relay_internal(
     url: String,
     state: State<'_, RelayPool>,
     net_runtime: State<'_, NativeNetworkRuntime>,
+    trigger: ConnectTrigger,
 ) -> Result<String, String> {
+    let url = normalize_relay_url(&url)?;
     let key = (window_label.clone(), url.clone());
 
     // Check if already connected
     {
         let connections = state.connections.lock().unwrap();
         if connections.contains_key(&key) {
-            if let Some(window) = app.get_webview_window(&window_label) {
-                let _ = window.emit(
-                    "relay-status",
-                    serde_json::json!({
-                        "url": url,
-                        "status": "connected"
-                    }),
-                );
-            }
+            emit_relay_status(&app, &window_label, &url, RelayStatus::Connected);
             return Ok("Already connected".to_string());
         }
     }
 
+    if state.holds_back(trigger) {
+        return Err(RECONNECTS_PAUSED_ERROR.to_string());
+    }
+
     // Parse URL
     let relay_url = url::Url::parse(&url).map_err(|e| e.to_string())?;
 
-    println!("[NativeRelay] connect_relay url={}", url);
-    println!("[NativeRelay] Tor enabled={}", net_runtime.is_tor_enabled());
-    if net_runtime.is_tor_enabled() {
-        println!("[NativeRelay] Tor proxy={}", net_runtime.get_proxy_url());
+    // Reserve a slot under the connection cap; past it the relay stays desired and
+    // waits in the queue until a connection closes
+    {
+        let connections = state.connections.lock().unwrap();
+        let mut inflight = state.connects_inflight.lock().unwrap();
+        let max = state.max_connections.load(Ordering::SeqCst);
+        if max > 0 && !inflight.contains(&key) && connections.len() + inflight.len() >= max {
+            let mut queue = state.connect_queue.lock().unwrap();
+            if !queue.contains(&key) {
+                queue.push_back(key.clone());
+            }
+            return Ok(CONNECT_QUEUED_MESSAGE.to_string());
+        }
+        inflight.insert(key.clone());
+    }
+    let _inflight = InflightConnect {
+        app: app.clone(),
+        inflight: state.connects_inflight.clone(),
+        connections: state.connections.clone(),
+        key: key.clone(),
+    };
+
+    let route = state.route_for(&url, &net_runtime);
+    let headers = state.headers_for(&url);
+    let tls_sni = state.tls_sni_for(&url);
+
+    log::debug!("[NativeRelay] connect_relay url={}", url);
+    log::debug!("[NativeRelay] Tor enabled={}", net_runtime.is_tor_enabled());
+    if let ConnectionRoute::Socks5(proxy_url) = &route {
+        log::debug!("[NativeRelay] Tor proxy={}", proxy_url);
     }
 
     // Attempt connection
-    let ws_stream: tokio_tungstenite::WebSocketStream<MaybeTlsStream> = if net_runtime
-        .is_tor_enabled()
-    {
-        println!("[NativeRelay] Relay scheme={}", relay_url.scheme());
-        if let Some(window) = app.get_webview_window(&window_label) {
-            let _ = window.emit(
-                "relay-status",
-                serde_json::json!({
-                    "url": url,
-                    "status": "starting"
-                }),
-            );
-        }
-        let budget = Duration::from_millis(CONNECT_COMMAND_BUDGET_MS);
-        let attempt_timeout_cap = Duration::from_millis(CONNECT_ATTEMPT_TIMEOUT_MS);
-        let retry_delay = Duration::from_millis(TOR_CONNECT_RETRY_DELAY_MS);
-        let deadline = Instant::now() + budget;
-        let mut attempts: u32 = 0;
-        let mut last_error_message: Option<String> = None;
-        let mut connected_stream: Option<tokio_tungstenite::WebSocketStream<MaybeTlsStream>> = None;
-        while Instant::now() < deadline {
-            attempts = attempts.saturating_add(1);
-            let remaining = deadline.saturating_duration_since(Instant::now());
-            let attempt_timeout = remaining.min(attempt_timeout_cap);
-            match timeout(attempt_timeout, net_runtime.connect_websocket(&relay_url)).await {
-                Ok(Ok(stream)) => {
-                    connected_stream = Some(stream);
-                    last_error_message = None;
+    let ws_stream: tokio_tungstenite::WebSocketStream<MaybeTlsStream> =
+        if matches!(route, ConnectionRoute::Socks5(_)) {
+            log::debug!("[NativeRelay] Relay scheme={}", relay_url.scheme());
+            emit_relay_status(&app, &window_label, &url, RelayStatus::Starting);
+            let budget = Duration::from_millis(CONNECT_COMMAND_BUDGET_MS);
+            let attempt_timeout_cap = Duration::from_millis(CONNECT_ATTEMPT_TIMEOUT_MS);
+            let retry_delay = Duration::from_millis(TOR_CONNECT_RETRY_DELAY_MS);
+            let deadline = Instant::now() + budget;
+            let mut attempts: u32 = 0;
+            let mut last_error_message: Option<String> = None;
+            let mut connected_stream: Option<tokio_tungstenite::WebSocketStream<MaybeTlsStream>> =
+                None;
+            while Instant::now() < deadline {
+                if state.holds_back(trigger) {
+                    last_error_message = Some(RECONNECTS_PAUSED_ERROR.to_string());
                     break;
                 }
-                Ok(Err(err)) => {
-                    let error_message = format_ws_connect_error(&err);
-                    println!(
-                        "[NativeRelay] Tor connect attempt {} failed: {}",
-                        attempts, error_message
-                    );
-                    last_error_message = Some(error_message);
-                }
-                Err(_) => {
-                    let error_message =
-                        format!("attempt timed out after {}ms", attempt_timeout.as_millis());
-                    println!(
-                        "[NativeRelay] Tor connect attempt {} failed: {}",
-                        attempts, error_message
-                    );
-                    last_error_message = Some(error_message);
+                attempts = attempts.saturating_add(1);
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                let attempt_timeout = remaining.min(attempt_timeout_cap);
+                match timeout(
+                    attempt_timeout,
+                    net_runtime.connect_websocket_via_route(
+                        &relay_url,
+                        &route,
+                        &headers,
+                        tls_sni.as_deref(),
+                    ),
+                )
+                .await
+                {
+                    Ok(Ok(stream)) => {
+                        connected_stream = Some(stream);
+                        last_error_message = None;
+                        break;
+                    }
+                    Ok(Err(err)) => {
+                        let error_message = format_ws_connect_error(&err);
+                        log::warn!(
+                            "[NativeRelay] Tor connect attempt {} failed: {}",
+                            attempts,
+                            error_message
+                        );
+                        last_error_message = Some(error_message);
+                    }
+                    Err(_) => {
+                        let error_message =
+                            format!("attempt timed out after {}ms", attempt_timeout.as_millis());
+                        log::warn!(
+                            "[NativeRelay] Tor connect attempt {} failed: {}",
+                            attempts,
+                            error_message
+                        );
+                        last_error_message = Some(error_message);
+                    }
                 }
-            }
 
-            if Instant::now() + retry_delay >= deadline {
-                break;
+                if Instant::now() + retry_delay >= deadline {
+                    break;
+                }
+                sleep(retry_delay).await;
             }
-            sleep(retry_delay).await;
-        }
-        if let Some(stream) = connected_stream {
-            stream
-        } else {
-            let message =
-                last_error_message.unwrap_or_else(|| "Unknown Tor connect error".to_string());
-            let final_error = format!(
-                "Tor proxy connect failed after {} attempt(s) within {}ms: {}",
-                attempts, CONNECT_COMMAND_BUDGET_MS, message
-            );
-            if let Some(window) = app.get_webview_window(&window_label) {
-                let _ = window.emit(
-                    "relay-status",
-                    serde_json::json!({
-                        "url": url,
-                        "status": "error",
-                        "error": final_error
-                    }),
+            if let Some(stream) = connected_stream {
+                stream
+            } else {
+                let message =
+                    last_error_message.unwrap_or_else(|| "Unknown Tor connect error".to_string());
+                let final_error = format!(
+                    "Tor proxy connect failed after {} attempt(s) within {}ms: {}",
+                    attempts, CONNECT_COMMAND_BUDGET_MS, message
                 );
+                emit_relay_status(
+                    &app,
+                    &window_label,
+                    &url,
+                    RelayStatus::Error {
+                        error: final_error.clone(),
+                        attempt: Some(attempts),
+                        reason: None,
+                    },
+                );
+                return Err(final_error);
             }
-            return Err(final_error);
-        }
-    } else {
-        let connect_timeout = Duration::from_millis(CONNECT_COMMAND_BUDGET_MS);
-        match timeout(connect_timeout, connect_async(relay_url.as_str())).await {
-            Ok(Ok((stream, _response))) => stream,
-            Ok(Err(e)) => {
-                let message = format_ws_connect_error(&e);
-                if let Some(window) = app.get_webview_window(&window_label) {
-                    let _ = window.emit(
-                        "relay-status",
-                        serde_json::json!({
-                            "url": url,
-                            "status": "error",
-                            "error": message
-                        }),
+        } else {
+            let connect_timeout = Duration::from_millis(CONNECT_COMMAND_BUDGET_MS);
+            match timeout(
+                connect_timeout,
+                net_runtime.connect_websocket_via_route(
+                    &relay_url,
+                    &route,
+                    &headers,
+                    tls_sni.as_deref(),
+                ),
+            )
+            .await
+            {
+                Ok(Ok(stream)) => stream,
+                Ok(Err(e)) => {
+                    let message = format_ws_connect_error(&e);
+                    emit_relay_status(
+                        &app,
+                        &window_label,
+                        &url,
+                        RelayStatus::Error {
+                            error: message.clone(),
+                            attempt: None,
+                            reason: None,
+                        },
                     );
+                    return Err(message);
                 }
-                return Err(message);
-            }
-            Err(_) => {
-                let message = format!("Connect timed out after {}ms", CONNECT_COMMAND_BUDGET_MS);
-                if let Some(window) = app.get_webview_window(&window_label) {
-                    let _ = window.emit(
-                        "relay-status",
-                        serde_json::json!({
-                            "url": url,
-                            "status": "error",
-                            "error": message
-                        }),
+                Err(_) => {
+                    let message =
+                        format!("Connect timed out after {}ms", CONNECT_COMMAND_BUDGET_MS);
+                    emit_relay_status(
+                        &app,
+                        &window_label,
+                        &url,
+                        RelayStatus::Error {
+                            error: message.clone(),
+                            attempt: None,
+                            reason: None,
+                        },
                     );
+                    return Err(message);
                 }
-                return Err(message);
             }
-        }
-    };
+        };
+
+    // Paused mid-handshake: drop the fresh socket rather than registering it
+    if state.holds_back(trigger) {
+        return Err(RECONNECTS_PAUSED_ERROR.to_string());
+    }
 
     let (mut write, read) = ws_stream.split();
     let (tx, mut rx) = mpsc::channel::<Message>(32);
+    let metrics = Arc::new(RelayConnectionMetrics::new());
+    let auth = Arc::new(ConnectionAuth::default());
+    let last_publish: Arc<Mutex<Option<RecentPublish>>> = Arc::new(Mutex::new(None));
 
     // Spawn write task (Messages from app -> Relay)
+    let write_metrics = metrics.clone();
+    let write_last_publish = last_publish.clone();
+    let write_auth = auth.clone();
     tokio::spawn(async move {
         while let Some(msg) = rx.recv().await {
+            let is_data = matches!(msg, Message::Text(_) | Message::Binary(_));
+            let event_id = match &msg {
+                Message::Text(text) => published_event_id(text.as_str()),
+                _ => None,
+            };
+            if let Message::Text(text) = &msg {
+                if let Some(auth_id) = auth_event_id(text.as_str()) {
+                    *write_auth.event_id.lock().unwrap() = Some(auth_id);
+                }
+            }
             match timeout(
                 Duration::from_millis(RELAY_WRITE_SEND_TIMEOUT_MS),
                 write.send(msg),
             )
             .await
             {
-                Ok(Ok(())) => {}
+                Ok(Ok(())) => {
+                    if is_data {
+                        write_metrics.record_sent();
+                    }
+                    if let Some(event_id) = event_id {
+                        *write_last_publish.lock().unwrap() = Some(RecentPublish {
+                            event_id,
+                            sent_at_ms: now_unix_ms(),
+                        });
+                    }
+                }
                 Ok(Err(_)) => break,
                 Err(_) => {
-                    println!(
+                    log::warn!(
                         "[NativeRelay] write loop send timed out after {}ms",
                         RELAY_WRITE_SEND_TIMEOUT_MS
                     );
@@ -533,17 +2440,44 @@ async fn connect_relay_internal(
     let app_handle = app.clone();
     let connections_clone = state.connections.clone();
     let pending_acks_clone = state.pending_acks.clone();
+    let sub_collectors_clone = state.sub_collectors.clone();
+    let states_clone = state.states.clone();
     let win_label_loop = window_label.clone();
     let read_url = url.clone();
     let control_tx = tx.clone();
+    let read_metrics = metrics.clone();
+    let read_last_publish = last_publish;
+    let read_auth = auth.clone();
 
     tokio::spawn(async move {
         let mut read_stream = read;
         while let Some(msg) = read_stream.next().await {
+            match &msg {
+                Ok(Message::Text(text)) => read_metrics.record_received(text.len()),
+                Ok(Message::Binary(data)) => read_metrics.record_received(data.len()),
+                _ => {}
+            }
+            // Some relays send their JSON in binary frames; fragmented messages arrive here
+            // already reassembled by tungstenite, bounded by the frame/message size cap.
+            let msg = match msg {
+                Ok(Message::Binary(data)) => match String::from_utf8(data.to_vec()) {
+                    Ok(text) => Ok(Message::Text(text.into())),
+                    Err(_) => {
+                        log::warn!(
+                            "[NativeRelay] Dropping non-UTF-8 binary message ({} bytes) from {}",
+                            data.len(),
+                            read_url
+                        );
+                        continue;
+                    }
+                },
+                other => other,
+            };
             match msg {
                 Ok(Message::Text(text)) => {
                     if let Ok(json) = serde_json::from_str::<Value>(&text) {
                         if let Some((event_id, ok, message)) = parse_ok_payload(&json) {
+                            read_auth.record_ok(&event_id, ok);
                             resolve_pending_ack(
                                 &pending_acks_clone,
                                 &win_label_loop,
@@ -553,15 +2487,76 @@ async fn connect_relay_internal(
                                 message,
                             );
                         }
-                        if let Some(window) = app_handle.get_webview_window(&win_label_loop) {
-                            let _ = window.emit(
-                                "relay-event",
-                                RelayMessage {
-                                    relay_url: read_url.clone(),
-                                    payload: json,
-                                },
+                        if requests_auth(&json) {
+                            let key = (win_label_loop.clone(), read_url.clone());
+                            if let Some(relay_state) = states_clone.lock().unwrap().get_mut(&key) {
+                                relay_state.auth_required = true;
+                            }
+                        }
+                        if let Some(message) = parse_notice_payload(&json) {
+                            let related_event_id = read_last_publish
+                                .lock()
+                                .unwrap()
+                                .as_ref()
+                                .filter(|recent| {
+                                    now_unix_ms().saturating_sub(recent.sent_at_ms)
+                                        <= NOTICE_CORRELATION_WINDOW_MS
+                                })
+                                .map(|recent| recent.event_id.clone());
+                            log::info!("[NativeRelay] NOTICE from {}: {}", read_url, message);
+                            if let Some(window) = app_handle.get_webview_window(&win_label_loop) {
+                                let _ = window.emit(
+                                    "relay-notice",
+                                    RelayNotice {
+                                        relay_url: read_url.clone(),
+                                        reason: relay_message_reason(&message),
+                                        message,
+                                        related_event_id,
+                                    },
+                                );
+                            }
+                        }
+                        if let Some(collector) = collector_for_frame(
+                            &sub_collectors_clone,
+                            &win_label_loop,
+                            &read_url,
+                            &json,
+                        ) {
+                            let _ = collector.send(json);
+                        } else if let Some(window) = app_handle.get_webview_window(&win_label_loop)
+                        {
+                            if let Some((sub_id, created_at)) = event_frame_cursor(&json) {
+                                let key = (win_label_loop.clone(), read_url.clone());
+                                if let Some(relay_state) =
+                                    states_clone.lock().unwrap().get_mut(&key)
+                                {
+                                    let oldest = relay_state
+                                        .oldest_seen
+                                        .entry(sub_id.to_string())
+                                        .or_insert(created_at);
+                                    *oldest = (*oldest).min(created_at);
+                                    let stats = relay_state
+                                        .subscription_stats
+                                        .entry(sub_id.to_string())
+                                        .or_default();
+                                    stats.events += 1;
+                                    stats.last_event_at = Some(now_unix_ms());
+                                }
+                            }
+                            app_handle.state::<RelayPool>().emit_relay_frame(
+                                &app_handle,
+                                &window,
+                                &win_label_loop,
+                                &read_url,
+                                json,
                             );
                         }
+                    } else {
+                        log::warn!(
+                            "[NativeRelay] Dropping non-JSON message ({} bytes) from {}",
+                            text.len(),
+                            read_url
+                        );
                     }
                 }
                 Ok(Message::Ping(payload)) => {
@@ -572,9 +2567,35 @@ async fn connect_relay_internal(
                     }
                 }
                 Ok(Message::Pong(_)) => {
-                    // Control-frame heartbeat acknowledgement, no routing needed.
+                    // Control-frame heartbeat acknowledgement; only the keepalive time is kept.
+                    read_metrics.record_pong();
                 }
                 Ok(Message::Close(_)) => break,
+                Err(tungstenite::Error::Capacity(CapacityError::MessageTooLong {
+                    size,
+                    max_size,
+                })) => {
+                    log::warn!(
+                        "[NativeRelay] {} sent a {} byte message (cap {}); dropping connection",
+                        read_url,
+                        size,
+                        max_size
+                    );
+                    emit_relay_status(
+                        &app_handle,
+                        &win_label_loop,
+                        &read_url,
+                        RelayStatus::Error {
+                            error: format!(
+                                "Relay sent a {} byte message, above the {} byte limit",
+                                size, max_size
+                            ),
+                            attempt: None,
+                            reason: Some(RELAY_OVERSIZE_FRAME_REASON),
+                        },
+                    );
+                    break;
+                }
                 Err(_) => break,
                 _ => {}
             }
@@ -588,247 +2609,903 @@ async fn connect_relay_internal(
             "Relay disconnected before OK response",
         );
 
-        if let Some(window) = app_handle.get_webview_window(&win_label_loop) {
-            let _ = window.emit(
-                "relay-status",
-                serde_json::json!({
-                    "url": read_url,
-                    "status": "disconnected"
-                }),
-            );
-        }
+        emit_relay_status(
+            &app_handle,
+            &win_label_loop,
+            &read_url,
+            RelayStatus::Disconnected,
+        );
 
-        // Remove from pool
-        let mut connections = connections_clone.lock().unwrap();
-        connections.remove(&(win_label_loop.clone(), read_url.clone()));
+        // Remove from pool. Local disconnects and route changes unregister the connection
+        // first, so one still registered was dropped by the relay or the network
+        let dropped = connections_clone
+            .lock()
+            .unwrap()
+            .remove(&(win_label_loop.clone(), read_url.clone()))
+            .is_some();
+        let pool = app_handle.state::<RelayPool>();
+        if dropped {
+            pool.record_drop(&app_handle);
+        }
+        pool.connect_queued_relays(&app_handle, pool.free_connection_slots());
     });
 
     // Add to pool
     {
+        let reconnect_count = {
+            let mut states = state.states.lock().unwrap();
+            let relay_state = states.entry(key.clone()).or_default();
+            relay_state.connect_count += 1;
+            relay_state.connect_count - 1
+        };
         let mut connections = state.connections.lock().unwrap();
         connections.insert(
             (window_label.clone(), url.clone()),
-            RelayConnection { tx: tx.clone() },
+            RelayConnection {
+                tx: tx.clone(),
+                metrics,
+                auth: auth.clone(),
+                reconnect_count,
+            },
         );
     }
 
-    // Auto-resubscribe from persistent state
-    let subs_to_re = {
-        let states = state.states.lock().unwrap();
+    // Flush publishes queued while the relay was down, before resubscribing
+    let queued_publishes: Vec<String> = {
+        let mut states = state.states.lock().unwrap();
         states
-            .get(&key)
-            .map(|s| s.subscriptions.clone())
+            .get_mut(&key)
+            .map(|s| s.pending_publishes.drain(..).collect())
             .unwrap_or_default()
     };
-
-    for (sub_id, filter) in subs_to_re {
-        let msg_json = serde_json::json!(["REQ", sub_id, filter]);
-        let _ = enqueue_relay_message(&tx, Message::Text(msg_json.to_string().into()));
-        println!("Auto-resubscribed to {} on {}", sub_id, url);
+    if !queued_publishes.is_empty() {
+        log::info!(
+            "[NativeRelay] Flushing {} queued publish(es) to {}",
+            queued_publishes.len(),
+            url
+        );
+    }
+    for msg_str in queued_publishes {
+        if tx.send(Message::Text(msg_str.into())).await.is_err() {
+            break;
+        }
     }
 
-    if let Some(window) = app.get_webview_window(&window_label) {
-        let _ = window.emit(
-            "relay-status",
-            serde_json::json!({
-                "url": url,
-                "status": "connected"
-            }),
-        );
+    // Auto-resubscribe from persistent state. A relay that asked for AUTH before would CLOSE
+    // the REQs with auth-required, so they wait until this connection authenticates (or
+    // give up waiting and go out anyway)
+    let auth_required = state
+        .states
+        .lock()
+        .unwrap()
+        .get(&key)
+        .is_some_and(|relay_state| relay_state.auth_required);
+    if auth_required {
+        let (app, key, tx) = (app.clone(), key.clone(), tx.clone());
+        tokio::spawn(async move {
+            let _ = timeout(
+                Duration::from_millis(AUTH_RESUBSCRIBE_WAIT_MS),
+                auth.authenticated_notify.notified(),
+            )
+            .await;
+            app.state::<RelayPool>().replay_subscriptions(&key, &tx);
+        });
+    } else {
+        state.replay_subscriptions(&key, &tx);
     }
 
+    emit_relay_status(&app, &window_label, &url, RelayStatus::Connected);
+
     Ok("Connected".to_string())
 }
 
-#[tauri::command]
-pub async fn connect_relay(
+/// Clears a relay's in-flight marker however the connect attempt ends, handing the
+/// slot of a failed attempt to the next queued connect
+struct InflightConnect {
     app: AppHandle,
-    window: WebviewWindow,
+    inflight: Arc<Mutex<HashSet<(String, RelayUrl)>>>,
+    connections: Arc<Mutex<HashMap<(String, RelayUrl), RelayConnection>>>,
+    key: (String, RelayUrl),
+}
+
+impl Drop for InflightConnect {
+    fn drop(&mut self) {
+        if let Ok(mut inflight) = self.inflight.lock() {
+            inflight.remove(&self.key);
+        }
+        let connected = self
+            .connections
+            .lock()
+            .is_ok_and(|connections| connections.contains_key(&self.key));
+        if !connected {
+            let pool = self.app.state::<RelayPool>();
+            pool.connect_queued_relays(&self.app, pool.free_connection_slots());
+        }
+    }
+}
+
+/// Concurrent handshakes allowed across bulk connects (`connect_all`, `connect_relays`,
+/// route-change and network-recovery reconnects) unless `set_connect_concurrency` says otherwise
+const DEFAULT_CONNECT_CONCURRENCY: usize = 5;
+const MAX_CONNECT_CONCURRENCY: usize = 64;
+
+/// `connect_relay_internal` for bulk paths: waits for a permit from the bulk connect gate so
+/// relays connect in waves instead of all at once. The permit is held for the handshake only.
+async fn connect_relay_gated(
+    app: AppHandle,
+    window_label: String,
+    url: RelayUrl,
     state: State<'_, RelayPool>,
     net_runtime: State<'_, NativeNetworkRuntime>,
-    url: String,
+    trigger: ConnectTrigger,
 ) -> Result<String, String> {
-    connect_relay_internal(app, window.label().to_string(), url, state, net_runtime).await
+    let gate = state.bulk_connect_gate.lock().unwrap().clone();
+    let _permit = gate
+        .acquire_owned()
+        .await
+        .map_err(|_| "Connect gate closed".to_string())?;
+    connect_relay_internal(app, window_label, url, state, net_runtime, trigger).await
 }
 
-// Command: Disconnect from a relay
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayConnectResult {
+    pub url: String,
+    pub ok: bool,
+    pub message: String,
+}
+
+// Command: Reconnect every saved relay and every relay this window connected (and has not
+// disconnected since) that is neither connected nor already connecting, a few at a time
 #[tauri::command]
-pub async fn disconnect_relay(
+pub async fn connect_all(
     app: AppHandle,
     window: WebviewWindow,
     state: State<'_, RelayPool>,
-    url: String,
-) -> Result<String, String> {
-    let window_label = window.label().to_string();
-    let key = (window_label.clone(), url.clone());
+    net_runtime: State<'_, NativeNetworkRuntime>,
+) -> Result<Vec<RelayConnectResult>, String> {
+    Ok(connect_pending_relays(app, window.label().to_string(), state, net_runtime).await)
+}
 
-    let tx = {
+async fn connect_pending_relays(
+    app: AppHandle,
+    window_label: String,
+    state: State<'_, RelayPool>,
+    net_runtime: State<'_, NativeNetworkRuntime>,
+) -> Vec<RelayConnectResult> {
+    let pending = state.pending_relays(&window_label, true);
+    connect_relay_urls(app, window_label, pending, state, net_runtime).await
+}
+
+/// Connect `urls` for a window, a few handshakes at a time, seeding each from the saved list
+async fn connect_relay_urls(
+    app: AppHandle,
+    window_label: String,
+    urls: Vec<RelayUrl>,
+    state: State<'_, RelayPool>,
+    net_runtime: State<'_, NativeNetworkRuntime>,
+) -> Vec<RelayConnectResult> {
+    let connects = urls.into_iter().map(|url| {
+        let (app, window_label, state, net_runtime) = (
+            app.clone(),
+            window_label.clone(),
+            state.clone(),
+            net_runtime.clone(),
+        );
+        async move {
+            state.seed_state_from_saved(&window_label, &url);
+            state.mark_wanted(&window_label, &url);
+            match connect_relay_gated(
+                app,
+                window_label,
+                url.clone(),
+                state,
+                net_runtime,
+                ConnectTrigger::Explicit,
+            )
+            .await
+            {
+                Ok(message) => RelayConnectResult {
+                    url,
+                    ok: true,
+                    message,
+                },
+                Err(message) => RelayConnectResult {
+                    url,
+                    ok: false,
+                    message,
+                },
+            }
+        }
+    });
+    futures_util::future::join_all(connects).await
+}
+
+/// Reconnect every live relay that follows the global route, after the Tor settings
+/// changed it. Relays with a proxy override keep their connection.
+pub async fn reconnect_for_route_change(app: AppHandle) {
+    let state = app.state::<RelayPool>();
+    let net_runtime = app.state::<NativeNetworkRuntime>();
+    let closing: Vec<((String, RelayUrl), Sender<Message>)> = {
+        let overrides = state.proxy_overrides.lock().unwrap();
         let mut connections = state.connections.lock().unwrap();
-        connections.remove(&key).map(|c| c.tx)
+        let keys: Vec<(String, RelayUrl)> = connections
+            .keys()
+            .filter(|(_, url)| !overrides.contains_key(url))
+            .cloned()
+            .collect();
+        keys.into_iter()
+            .filter_map(|key| connections.remove(&key).map(|conn| (key, conn.tx)))
+            .collect()
     };
+    if closing.is_empty() {
+        return;
+    }
+    log::info!(
+        "[NativeRelay] Network route changed; reconnecting {} relay connection(s)",
+        closing.len()
+    );
 
-    if let Some(tx) = tx {
+    let mut reconnect = Vec::with_capacity(closing.len());
+    for ((window_label, url), tx) in closing {
         fail_pending_acks_for_scope_relay(
             &state.pending_acks,
             &window_label,
             &url,
-            "Relay disconnected before OK response",
+            "Relay reconfiguring before OK response",
         );
-        // Sending Close message will terminate the read loop eventually
+        emit_relay_status(&app, &window_label, &url, RelayStatus::Reconfiguring);
         let _ = tx.send(Message::Close(None)).await;
-        if let Some(window) = app.get_webview_window(&window_label) {
-            let _ = window.emit(
-                "relay-status",
-                serde_json::json!({
-                    "url": url,
-                    "status": "disconnected"
-                }),
-            );
-        }
-        Ok("Disconnected".to_string())
-    } else {
-        Err("Not connected".to_string())
+        reconnect.push((window_label, url));
     }
+
+    let connects = reconnect.into_iter().map(|(window_label, url)| {
+        let (app, state, net_runtime) = (app.clone(), state.clone(), net_runtime.clone());
+        async move {
+            if let Err(e) = connect_relay_gated(
+                app,
+                window_label,
+                url.clone(),
+                state,
+                net_runtime,
+                ConnectTrigger::Automatic,
+            )
+            .await
+            {
+                log::warn!(
+                    "[NativeRelay] Reconnect after route change failed for {}: {}",
+                    url,
+                    e
+                );
+            }
+        }
+    });
+    futures_util::future::join_all(connects).await;
 }
 
+// Command: Connect several relays in one call and add them to the saved list. Handshakes
+// run concurrently; every URL gets a result, including ones that fail to parse
 #[tauri::command]
-pub async fn recycle_relays(
+pub async fn connect_relays(
     app: AppHandle,
     window: WebviewWindow,
     state: State<'_, RelayPool>,
     net_runtime: State<'_, NativeNetworkRuntime>,
-) -> Result<String, String> {
+    urls: Vec<String>,
+) -> Result<Vec<RelayConnectResult>, String> {
     let window_label = window.label().to_string();
-
-    let mut reconnect_urls: HashSet<String> = {
-        let states = state.states.lock().unwrap();
-        states
-            .iter()
-            .filter_map(|((w, u), _)| {
-                if w == &window_label {
-                    Some(u.clone())
-                } else {
-                    None
+    let mut results = Vec::new();
+    let mut valid = Vec::new();
+    let mut seen = HashSet::new();
+    for raw in urls {
+        match normalize_relay_url(&raw) {
+            Ok(url) => {
+                if seen.insert(url.clone()) {
+                    valid.push(url);
                 }
-            })
-            .collect()
-    };
-
-    {
-        let connections = state.connections.lock().unwrap();
-        connections
-            .iter()
-            .filter(|((w, _), _)| w == &window_label)
-            .for_each(|((_, url), _)| {
-                reconnect_urls.insert(url.clone());
-            });
-    };
-
-    let active_connections: Vec<(String, Sender<Message>)> = {
-        let mut connections = state.connections.lock().unwrap();
-        let mut to_remove = Vec::new();
-        for ((w, u), _) in connections.iter() {
-            if w == &window_label {
-                to_remove.push((w.clone(), u.clone()));
             }
+            Err(message) => results.push(RelayConnectResult {
+                url: raw,
+                ok: false,
+                message,
+            }),
         }
+    }
 
-        let mut results = Vec::new();
-        for key in to_remove {
-            if let Some(conn) = connections.remove(&key) {
-                results.push((key.1, conn.tx));
+    // One write to relays.json for the whole batch
+    let added: Vec<SavedRelay> = valid
+        .iter()
+        .map(|url| SavedRelay {
+            url: url.clone(),
+            marker: state.marker_for(&window_label, url),
+            proxy: None,
+            tls_sni: None,
+        })
+        .collect();
+    state.update_saved_relays(&app, |relays| {
+        let before = relays.len();
+        for relay in added {
+            if !relays.iter().any(|saved| saved.url == relay.url) {
+                relays.push(relay);
             }
         }
-        results
-    };
-
-    for (url, tx) in active_connections {
-        fail_pending_acks_for_scope_relay(
-            &state.pending_acks,
-            &window_label,
-            &url,
-            "Relay recycled before OK response",
-        );
-        let _ = tx.send(Message::Close(None)).await;
-        if let Some(window) = app.get_webview_window(&window_label) {
-            let _ = window.emit(
-                "relay-status",
-                serde_json::json!({
-                    "url": url,
-                    "status": "disconnected"
-                }),
-            );
-        }
-    }
-
-    for url in reconnect_urls {
-        let _ = connect_relay_internal(
-            app.clone(),
-            window_label.clone(),
-            url.clone(),
-            state.clone(),
-            net_runtime.clone(),
-        )
-        .await;
-    }
+        relays.len() != before
+    });
 
-    Ok("Recycled profile relay connections".to_string())
+    results.extend(connect_relay_urls(app, window_label, valid, state, net_runtime).await);
+    Ok(results)
 }
 
-// Command: Publish Event
+// Command: Turn the debounced `relay-pool-summary` event on or off for this window.
+// Enabling schedules a first summary
 #[tauri::command]
-pub async fn publish_event(
+pub async fn set_relay_summary_enabled(
+    app: AppHandle,
     window: WebviewWindow,
     state: State<'_, RelayPool>,
-    url: String,
-    event_json: Value,
-) -> Result<String, String> {
-    // Wrap event in ["EVENT", event_json] as per NIP-01
-    let msg_json = serde_json::json!(["EVENT", event_json]);
-    let msg_str = msg_json.to_string();
-    let key = (window.label().to_string(), url);
-
-    let tx = {
-        let connections = state.connections.lock().unwrap();
-        connections.get(&key).map(|c| c.tx.clone())
-    };
-
-    if let Some(tx) = tx {
-        enqueue_relay_message(&tx, Message::Text(msg_str.into()))?;
-        Ok("Published".to_string())
+    enabled: bool,
+) -> Result<(), String> {
+    let window_label = window.label().to_string();
+    if enabled {
+        state
+            .summary_windows
+            .lock()
+            .unwrap()
+            .insert(window_label.clone());
+        state.schedule_summary(&app, &window_label);
     } else {
-        Err("Not connected".to_string())
+        state.summary_windows.lock().unwrap().remove(&window_label);
     }
+    Ok(())
 }
 
-#[tauri::command]
-pub async fn subscribe_relay(
-    window: WebviewWindow,
-    state: State<'_, RelayPool>,
-    url: String,
-    sub_id: String,
-    filter: Value,
-) -> Result<String, String> {
-    let key = (window.label().to_string(), url.clone());
+/// Coordinated reconnect after a mass disconnect or a resume from sleep: every window's
+/// dropped relays reconnect once, each starting a little after the previous one.
+async fn reconnect_after_network_recovery(app: AppHandle) {
+    let state = app.state::<RelayPool>();
+    let net_runtime = app.state::<NativeNetworkRuntime>();
+    state.recovery_scheduled.store(false, Ordering::SeqCst);
+    state.recent_drops.lock().unwrap().clear();
+    if state.reconnects_paused.load(Ordering::SeqCst) {
+        return;
+    }
 
-    // 1. Update persistent state
+    let mut windows: Vec<String> = state
+        .wanted
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(window_label, _)| window_label.clone())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    windows.sort();
+    let targets: Vec<(String, RelayUrl)> = windows
+        .into_iter()
+        .flat_map(|window_label| {
+            let mut pending = state.pending_relays(&window_label, false);
+            pending.sort();
+            pending
+                .into_iter()
+                .map(move |url| (window_label.clone(), url))
+        })
+        .collect();
+    if targets.is_empty() {
+        return;
+    }
+    log::info!(
+        "[NativeRelay] Network recovery: reconnecting {} relay(s), {}ms apart",
+        targets.len(),
+        NETWORK_RECOVERY_STAGGER_MS
+    );
+
+    for (window_label, url) in &targets {
+        emit_relay_status(&app, window_label, url, RelayStatus::Reconfiguring);
+    }
+    let connects = targets
+        .into_iter()
+        .enumerate()
+        .map(|(index, (window_label, url))| {
+            let (app, state, net_runtime) = (app.clone(), state.clone(), net_runtime.clone());
+            async move {
+                sleep(Duration::from_millis(
+                    NETWORK_RECOVERY_STAGGER_MS * index as u64,
+                ))
+                .await;
+                if let Err(e) = connect_relay_gated(
+                    app,
+                    window_label,
+                    url.clone(),
+                    state,
+                    net_runtime,
+                    ConnectTrigger::Automatic,
+                )
+                .await
+                {
+                    log::warn!(
+                        "[NativeRelay] Reconnect after network recovery failed for {}: {}",
+                        url,
+                        e
+                    );
+                }
+            }
+        });
+    futures_util::future::join_all(connects).await;
+}
+
+/// Watch for the machine waking from sleep, which desktop platforms do not report to the
+/// app: a wall-clock jump well past the tick interval means time passed while suspended.
+pub fn spawn_resume_watchdog(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_tick = std::time::SystemTime::now();
+        loop {
+            sleep(Duration::from_secs(RESUME_WATCHDOG_TICK_SECS)).await;
+            let now = std::time::SystemTime::now();
+            let gap = now.duration_since(last_tick).unwrap_or_default();
+            last_tick = now;
+            if gap >= Duration::from_secs(RESUME_GAP_THRESHOLD_SECS) {
+                app.state::<RelayPool>()
+                    .schedule_network_recovery(&app, "resumed from sleep");
+            }
+        }
+    });
+}
+
+// Command: Stop automatic reconnects (e.g. while changing Tor settings). Network recovery,
+// route-change and queued connects still handshaking abort and new ones fail until
+// `resume_reconnects`; explicit connects (`connect_relay`, `fetch_events`, ...) go through
+// and live connections stay up.
+#[tauri::command]
+pub async fn pause_reconnects(state: State<'_, RelayPool>) -> Result<(), String> {
+    state.reconnects_paused.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+// Command: Lift `pause_reconnects` and reconnect this window's relays that are down
+#[tauri::command]
+pub async fn resume_reconnects(
+    app: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, RelayPool>,
+    net_runtime: State<'_, NativeNetworkRuntime>,
+) -> Result<Vec<RelayConnectResult>, String> {
+    state.reconnects_paused.store(false, Ordering::SeqCst);
+    Ok(connect_pending_relays(app, window.label().to_string(), state, net_runtime).await)
+}
+
+// Command: Dial a dropped relay now instead of waiting for the next scheduled reconnect.
+// Emits `starting` first so the UI can show the attempt; a relay that is already
+// connecting is left to finish rather than dialed twice.
+#[tauri::command]
+pub async fn force_reconnect(
+    app: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, RelayPool>,
+    net_runtime: State<'_, NativeNetworkRuntime>,
+    url: String,
+) -> Result<String, String> {
+    let url = normalize_relay_url(&url)?;
+    let window_label = window.label().to_string();
+    let key = (window_label.clone(), url.clone());
+    if state.connects_inflight.lock().unwrap().contains(&key) {
+        return Ok("Already connecting".to_string());
+    }
+    log::info!("[NativeRelay] Manual reconnect requested for {}", url);
+    state.seed_state_from_saved(&window_label, &url);
+    state.mark_wanted(&window_label, &url);
+    emit_relay_status(&app, &window_label, &url, RelayStatus::Starting);
+    connect_relay_internal(
+        app,
+        window_label,
+        url,
+        state,
+        net_runtime,
+        ConnectTrigger::Explicit,
+    )
+    .await
+}
+
+// Command: `force_reconnect` for every relay this window wants that is down
+#[tauri::command]
+pub async fn force_reconnect_all(
+    app: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, RelayPool>,
+    net_runtime: State<'_, NativeNetworkRuntime>,
+) -> Result<Vec<RelayConnectResult>, String> {
+    let window_label = window.label().to_string();
+    let pending = state.pending_relays(&window_label, true);
+    log::info!(
+        "[NativeRelay] Manual reconnect requested for {} relay(s)",
+        pending.len()
+    );
+    for url in &pending {
+        emit_relay_status(&app, &window_label, url, RelayStatus::Starting);
+    }
+    Ok(connect_relay_urls(app, window_label, pending, state, net_runtime).await)
+}
+
+#[tauri::command]
+pub async fn connect_relay(
+    app: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, RelayPool>,
+    net_runtime: State<'_, NativeNetworkRuntime>,
+    url: String,
+    marker: Option<RelayMarker>,
+    headers: Option<HashMap<String, String>>,
+) -> Result<String, String> {
+    let url = normalize_relay_url(&url)?;
+    if let Some(headers) = headers {
+        let headers = parse_upgrade_headers(&headers)?;
+        let mut upgrade_headers = state.upgrade_headers.lock().unwrap();
+        if headers.is_empty() {
+            upgrade_headers.remove(&url);
+        } else {
+            upgrade_headers.insert(url.clone(), headers);
+        }
+    }
+    let window_label = window.label().to_string();
+    let is_saved = state.seed_state_from_saved(&window_label, &url);
+    state.mark_wanted(&window_label, &url);
+    if let Some(marker) = marker {
+        state
+            .states
+            .lock()
+            .unwrap()
+            .entry((window_label.clone(), url.clone()))
+            .or_default()
+            .marker = marker;
+        if is_saved {
+            state.update_saved_relays(&app, |relays| set_saved_relay_marker(relays, &url, marker));
+        }
+    }
+    let result = connect_relay_internal(
+        app.clone(),
+        window_label.clone(),
+        url.clone(),
+        state.clone(),
+        net_runtime,
+        ConnectTrigger::Explicit,
+    )
+    .await?;
+    if !is_saved {
+        let marker = state.marker_for(&window_label, &url);
+        state.update_saved_relays(&app, |relays| {
+            if relays.iter().any(|relay| relay.url == url) {
+                return false;
+            }
+            relays.push(SavedRelay {
+                url: url.clone(),
+                marker,
+                proxy: None,
+                tls_sni: None,
+            });
+            true
+        });
+    }
+    Ok(result)
+}
+
+/// Wait until the connection's write queue is empty and no publish on it awaits an OK,
+/// or until `limit` passes. Returns whether it drained in time.
+async fn drain_relay_connection(
+    pending_acks: &Arc<Mutex<HashMap<PendingAckKey, PendingRelayAck>>>,
+    tx: &Sender<Message>,
+    window_label: &str,
+    relay_url: &str,
+    limit: Duration,
+) -> bool {
+    let deadline = Instant::now() + limit;
+    loop {
+        let queue_empty = tx.capacity() == tx.max_capacity();
+        let awaiting_ok = pending_acks
+            .lock()
+            .unwrap()
+            .keys()
+            .any(|(scope, url, _)| scope == window_label && url == relay_url);
+        if queue_empty && !awaiting_ok {
+            return true;
+        }
+        if tx.is_closed() || Instant::now() >= deadline {
+            return false;
+        }
+        sleep(Duration::from_millis(DISCONNECT_DRAIN_POLL_MS)).await;
+    }
+}
+
+/// Drain a released connection if asked, fail whatever still awaits an OK, then close
+/// the socket. Returns whether the drain finished before its deadline.
+async fn close_released_relay(
+    pending_acks: &Arc<Mutex<HashMap<PendingAckKey, PendingRelayAck>>>,
+    tx: &Sender<Message>,
+    window_label: &str,
+    relay_url: &str,
+    drain: Option<Duration>,
+) -> bool {
+    let drained = match drain {
+        Some(limit) => {
+            drain_relay_connection(pending_acks, tx, window_label, relay_url, limit).await
+        }
+        None => true,
+    };
+    fail_pending_acks_for_scope_relay(
+        pending_acks,
+        window_label,
+        relay_url,
+        "Relay disconnected before OK response",
+    );
+    // Sending Close message will terminate the read loop eventually
+    let _ = tx.send(Message::Close(None)).await;
+    drained
+}
+
+// Command: Disconnect from a relay
+#[tauri::command]
+pub async fn disconnect_relay(
+    app: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, RelayPool>,
+    url: String,
+) -> Result<String, String> {
+    disconnect_relay_internal(&app, &state, window.label(), &url, None).await
+}
+
+// Command: Disconnect after letting queued EVENTs/REQs go out and in-flight publishes get
+// their OK, waiting at most `timeout_ms`. The relay takes no new messages meanwhile.
+#[tauri::command]
+pub async fn disconnect_relay_drain(
+    app: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, RelayPool>,
+    url: String,
+    timeout_ms: u64,
+) -> Result<String, String> {
+    let limit = Duration::from_millis(timeout_ms.min(DISCONNECT_DRAIN_MAX_TIMEOUT_MS));
+    disconnect_relay_internal(&app, &state, window.label(), &url, Some(limit)).await
+}
+
+async fn disconnect_relay_internal(
+    app: &AppHandle,
+    state: &RelayPool,
+    window_label: &str,
+    url: &str,
+    drain: Option<Duration>,
+) -> Result<String, String> {
+    let url = normalize_relay_url(url)?;
+    let window_label = window_label.to_string();
+    let key = (window_label.clone(), url.clone());
+    state.update_saved_relays(app, |relays| {
+        let before = relays.len();
+        relays.retain(|relay| relay.url != url);
+        relays.len() != before
+    });
+
+    if let Some(tx) = state.release_relay(&key) {
+        let drained =
+            close_released_relay(&state.pending_acks, &tx, &window_label, &url, drain).await;
+        emit_relay_status(app, &window_label, &url, RelayStatus::Disconnected);
+        if drained {
+            Ok("Disconnected".to_string())
+        } else {
+            Ok("Disconnected (drain timed out)".to_string())
+        }
+    } else {
+        Err("Not connected".to_string())
+    }
+}
+
+#[tauri::command]
+pub async fn recycle_relays(
+    app: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, RelayPool>,
+    net_runtime: State<'_, NativeNetworkRuntime>,
+) -> Result<String, String> {
+    let window_label = window.label().to_string();
+
+    let mut reconnect_urls: HashSet<String> =
+        window_relays(state.wanted.lock().unwrap().iter(), &window_label);
+
+    {
+        let connections = state.connections.lock().unwrap();
+        connections
+            .iter()
+            .filter(|((w, _), _)| w == &window_label)
+            .for_each(|((_, url), _)| {
+                reconnect_urls.insert(url.clone());
+            });
+    };
+
+    let active_connections: Vec<(String, Sender<Message>)> = {
+        let mut connections = state.connections.lock().unwrap();
+        let mut to_remove = Vec::new();
+        for ((w, u), _) in connections.iter() {
+            if w == &window_label {
+                to_remove.push((w.clone(), u.clone()));
+            }
+        }
+
+        let mut results = Vec::new();
+        for key in to_remove {
+            if let Some(conn) = connections.remove(&key) {
+                results.push((key.1, conn.tx));
+            }
+        }
+        results
+    };
+
+    for (url, tx) in active_connections {
+        fail_pending_acks_for_scope_relay(
+            &state.pending_acks,
+            &window_label,
+            &url,
+            "Relay recycled before OK response",
+        );
+        let _ = tx.send(Message::Close(None)).await;
+        emit_relay_status(&app, &window_label, &url, RelayStatus::Disconnected);
+    }
+
+    for url in reconnect_urls {
+        let _ = connect_relay_internal(
+            app.clone(),
+            window_label.clone(),
+            url.clone(),
+            state.clone(),
+            net_runtime.clone(),
+            ConnectTrigger::Explicit,
+        )
+        .await;
+    }
+
+    Ok("Recycled profile relay connections".to_string())
+}
+
+// Command: Publish Event
+#[tauri::command]
+pub async fn publish_event(
+    window: WebviewWindow,
+    state: State<'_, RelayPool>,
+    url: String,
+    event_json: Value,
+    use_markers: Option<bool>,
+) -> Result<String, String> {
+    let url = normalize_relay_url(&url)?;
+    // Markers are honoured unless the caller opts out with `use_markers: false`
+    if use_markers.unwrap_or(true) && !state.marker_for(window.label(), &url).allows_write() {
+        return Err(format!("Relay {} is read-only; not publishing", url));
+    }
+    // Wrap event in ["EVENT", event_json] as per NIP-01
+    let msg_json = serde_json::json!(["EVENT", event_json]);
+    state.send_or_buffer_publish(window.label(), &url, msg_json.to_string())
+}
+
+// Command: Cap open plus handshaking relay connections across all windows (0 = no cap).
+// Connects over the cap are queued and start as connections close; raising the cap
+// starts queued connects right away. Open connections are never dropped to fit a lower cap
+#[tauri::command]
+pub async fn set_max_connections(
+    app: AppHandle,
+    state: State<'_, RelayPool>,
+    max: usize,
+) -> Result<usize, String> {
+    state.max_connections.store(max, Ordering::SeqCst);
+    state.connect_queued_relays(&app, state.free_connection_slots());
+    Ok(max)
+}
+
+// Command: Limit how many handshakes bulk connects run at once (1 to 64, default 5).
+// Single-relay connects are not gated. Returns the limit applied
+#[tauri::command]
+pub async fn set_connect_concurrency(
+    state: State<'_, RelayPool>,
+    n: usize,
+) -> Result<usize, String> {
+    if n == 0 {
+        return Err("Connect concurrency must be at least 1".to_string());
+    }
+    let limit = n.min(MAX_CONNECT_CONCURRENCY);
+    *state.bulk_connect_gate.lock().unwrap() = Arc::new(Semaphore::new(limit));
+    Ok(limit)
+}
+
+// Command: Deliver this window's stored events as `relay-events-batch` (one array per
+// subscription every 100ms) instead of one `relay-event` each. Live events after EOSE,
+// EOSE and CLOSED still arrive as `relay-event`
+#[tauri::command]
+pub async fn set_relay_event_batching(
+    app: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, RelayPool>,
+    enabled: bool,
+) -> Result<(), String> {
+    let window_label = window.label().to_string();
+    if enabled {
+        state
+            .event_batch_windows
+            .lock()
+            .unwrap()
+            .insert(window_label);
+        return Ok(());
+    }
+    state
+        .event_batch_windows
+        .lock()
+        .unwrap()
+        .remove(&window_label);
+    let pending: Vec<EventBatchKey> = state
+        .event_batches
+        .lock()
+        .unwrap()
+        .keys()
+        .filter(|key| key.0 == window_label)
+        .cloned()
+        .collect();
+    for key in pending {
+        state.flush_event_batch(&app, &key);
+    }
+    Ok(())
+}
+
+// Command: Queue up to `capacity` publishes for this relay while it is reconnecting (0 disables)
+#[tauri::command]
+pub async fn set_publish_buffer(
+    window: WebviewWindow,
+    state: State<'_, RelayPool>,
+    url: String,
+    capacity: usize,
+) -> Result<usize, String> {
+    state.set_publish_buffer(window.label(), &url, capacity)
+}
+
+// Command: Open (or replace) a subscription with one or more filters, sent in a single REQ
+// and replayed whole on reconnect. `filter` is the older single-filter form.
+#[tauri::command]
+pub async fn subscribe_relay(
+    window: WebviewWindow,
+    state: State<'_, RelayPool>,
+    url: String,
+    sub_id: String,
+    filter: Option<Value>,
+    filters: Option<Vec<Value>>,
+    use_markers: Option<bool>,
+) -> Result<String, String> {
+    let filters = subscription_filters(filter, filters)?;
+    let url = normalize_relay_url(&url)?;
+    if use_markers.unwrap_or(true) && !state.marker_for(window.label(), &url).allows_read() {
+        return Ok("Skipped (write-only relay)".to_string());
+    }
+    let key = (window.label().to_string(), url.clone());
+
+    // 1. Update persistent state
     {
         let mut states = state.states.lock().unwrap();
         let relay_state = states.entry(key.clone()).or_default();
         relay_state
             .subscriptions
-            .insert(sub_id.clone(), filter.clone());
+            .insert(sub_id.clone(), filters.clone());
+        // A replaced subscription pages from its own results
+        relay_state.oldest_seen.remove(&sub_id);
+        relay_state.eose_received.remove(&sub_id);
+        relay_state.subscription_stats.remove(&sub_id);
     }
 
-    // 2. Send REQ if connected
+    // 2. Send REQ if connected; a paused window sends it on resume
+    if state
+        .subscriptions_paused
+        .lock()
+        .unwrap()
+        .contains_key(window.label())
+    {
+        return Ok("Subscribed (paused)".to_string());
+    }
     let tx = {
         let connections = state.connections.lock().unwrap();
         connections.get(&key).map(|c| c.tx.clone())
     };
 
     if let Some(tx) = tx {
-        let msg_json = serde_json::json!(["REQ", sub_id, filter]);
-        enqueue_relay_message(&tx, Message::Text(msg_json.to_string().into()))?;
+        enqueue_relay_message(&tx, Message::Text(req_frame(&sub_id, &filters).into()))?;
         Ok("Subscribed (active)".to_string())
     } else {
         Ok("Subscribed (persistent, offline)".to_string())
@@ -842,6 +3519,7 @@ pub async fn unsubscribe_relay(
     url: String,
     sub_id: String,
 ) -> Result<String, String> {
+    let url = normalize_relay_url(&url)?;
     let key = (window.label().to_string(), url);
 
     // 1. Remove from persistent state
@@ -849,6 +3527,9 @@ pub async fn unsubscribe_relay(
         let mut states = state.states.lock().unwrap();
         if let Some(relay_state) = states.get_mut(&key) {
             relay_state.subscriptions.remove(&sub_id);
+            relay_state.oldest_seen.remove(&sub_id);
+            relay_state.eose_received.remove(&sub_id);
+            relay_state.subscription_stats.remove(&sub_id);
         }
     }
 
@@ -867,24 +3548,1673 @@ pub async fn unsubscribe_relay(
     }
 }
 
-// Command: Send Raw Message
+// Command: Stop receiving events in this window without dropping connections: CLOSE every
+// subscription and hold them until `resume_subscriptions`. Returns the CLOSEs sent
 #[tauri::command]
-pub async fn send_relay_message(
+pub async fn pause_subscriptions(
     window: WebviewWindow,
     state: State<'_, RelayPool>,
-    url: String,
-    message: String,
-) -> Result<String, String> {
-    let key = (window.label().to_string(), url);
-    let tx = {
-        let connections = state.connections.lock().unwrap();
-        connections.get(&key).map(|c| c.tx.clone())
-    };
+) -> Result<usize, String> {
+    Ok(state.pause_window_subscriptions(window.label(), SubscriptionPause::Manual))
+}
 
-    if let Some(tx) = tx {
-        enqueue_relay_message(&tx, Message::Text(message.into()))?;
-        Ok("Sent".to_string())
-    } else {
-        Err("Not connected".to_string())
+// Command: Re-issue this window's paused subscriptions. Returns the REQs sent
+#[tauri::command]
+pub async fn resume_subscriptions(
+    window: WebviewWindow,
+    state: State<'_, RelayPool>,
+) -> Result<usize, String> {
+    Ok(state.resume_window_subscriptions(window.label(), false))
+}
+
+// Command: Pause a window's subscriptions while it is hidden to the tray (persisted)
+#[tauri::command]
+pub async fn set_pause_subscriptions_when_hidden(
+    app: AppHandle,
+    state: State<'_, RelayPool>,
+    enabled: bool,
+) -> Result<(), String> {
+    write_subscription_settings(
+        &app,
+        &SubscriptionSettings {
+            pause_when_hidden: enabled,
+        },
+    )?;
+    state.set_pause_subscriptions_when_hidden(enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_pause_subscriptions_when_hidden(
+    state: State<'_, RelayPool>,
+) -> Result<bool, String> {
+    Ok(state.pause_subscriptions_when_hidden.load(Ordering::SeqCst))
+}
+
+/// Window hide/show hook: pauses the window's subscriptions when it is hidden (if enabled)
+/// and resumes a hide-triggered pause once it is shown again.
+pub fn on_window_visibility_changed(app: &AppHandle, window_label: &str, visible: bool) {
+    let Some(pool) = app.try_state::<RelayPool>() else {
+        return;
+    };
+    if visible {
+        pool.resume_window_subscriptions(window_label, true);
+    } else if pool.pause_subscriptions_when_hidden.load(Ordering::SeqCst) {
+        pool.pause_window_subscriptions(window_label, SubscriptionPause::Hidden);
+    }
+}
+
+/// Window destroyed hook: drops the window's relay state so no reconnect path dials its
+/// relays again, and closes its connections.
+pub fn on_window_destroyed(app: &AppHandle, window_label: &str) {
+    let Some(pool) = app.try_state::<RelayPool>() else {
+        return;
+    };
+    let closing = pool.forget_window(window_label);
+    if closing.is_empty() {
+        return;
+    }
+    log::info!(
+        "[NativeRelay] Window {} destroyed; closing {} relay connection(s)",
+        window_label,
+        closing.len()
+    );
+    for (url, _) in &closing {
+        fail_pending_acks_for_scope_relay(
+            &pool.pending_acks,
+            window_label,
+            url,
+            "Window closed before OK response",
+        );
+    }
+    tauri::async_runtime::spawn(async move {
+        for (_, tx) in closing {
+            let _ = tx.send(Message::Close(None)).await;
+        }
+    });
+}
+
+/// Per-relay outcome of `page_subscription`
+#[derive(Debug, Serialize, Clone)]
+pub struct SubscriptionPage {
+    pub url: String,
+    /// `until` sent; None when the relay has delivered nothing for this subscription yet
+    pub until: Option<u64>,
+    pub sent: bool,
+    /// Events of the page, as the relay sent them up to its EOSE
+    pub events: Vec<Value>,
+    pub message: Option<String>,
+}
+
+const MAX_PAGE_LIMIT: usize = 500;
+
+// Command: "Load older" for a subscription. On every relay of this window holding it, runs
+// its filters once under a fresh sub id, with `until` just before the oldest event seen
+// there and `limit`, collects up to EOSE and CLOSEs. The live REQ is left untouched.
+#[tauri::command]
+pub async fn page_subscription(
+    window: WebviewWindow,
+    state: State<'_, RelayPool>,
+    sub_id: String,
+    limit: usize,
+    timeout_ms: Option<u64>,
+) -> Result<Vec<SubscriptionPage>, String> {
+    let limit = limit.clamp(1, MAX_PAGE_LIMIT);
+    let window_label = window.label().to_string();
+    let targets: Vec<(RelayUrl, Vec<Value>, Option<u64>)> = {
+        let states = state.states.lock().unwrap();
+        states
+            .iter()
+            .filter(|((label, _), _)| *label == window_label)
+            .filter_map(|((_, url), relay_state)| {
+                let filters = relay_state.subscriptions.get(&sub_id)?;
+                Some((
+                    url.clone(),
+                    filters.clone(),
+                    relay_state.oldest_seen.get(&sub_id).copied(),
+                ))
+            })
+            .collect()
+    };
+    if targets.is_empty() {
+        return Err(format!("No subscription {} in this window", sub_id));
+    }
+    let budget = timeout_ms
+        .unwrap_or(FETCH_EVENTS_DEFAULT_TIMEOUT_MS)
+        .min(FETCH_EVENTS_MAX_TIMEOUT_MS);
+    let deadline = Instant::now() + Duration::from_millis(budget);
+
+    let pages = targets.into_iter().map(|(url, filters, oldest)| {
+        let (state, window_label, sub_id) = (state.clone(), window_label.clone(), &sub_id);
+        async move {
+            let Some(oldest) = oldest else {
+                return SubscriptionPage {
+                    url,
+                    until: None,
+                    sent: false,
+                    events: Vec::new(),
+                    message: Some("No events seen yet".to_string()),
+                };
+            };
+            let filters = paged_filters(&filters, oldest, limit);
+            let result =
+                collect_relay_events(&state, &window_label, &url, &filters, deadline).await;
+            if let Ok(events) = &result {
+                state.note_page_cursor(&window_label, &url, sub_id, events);
+            }
+            let (events, message) = match result {
+                Ok(events) => (events, None),
+                Err(e) => (Vec::new(), Some(e)),
+            };
+            SubscriptionPage {
+                url,
+                until: Some(oldest.saturating_sub(1)),
+                sent: message.is_none(),
+                events,
+                message,
+            }
+        }
+    });
+    let mut pages = futures_util::future::join_all(pages).await;
+    pages.sort_by(|a, b| a.url.cmp(&b.url));
+    Ok(pages)
+}
+
+// Command: Send Raw Message
+#[tauri::command]
+pub async fn send_relay_message(
+    window: WebviewWindow,
+    state: State<'_, RelayPool>,
+    url: String,
+    message: String,
+) -> Result<String, String> {
+    let url = normalize_relay_url(&url)?;
+    let key = (window.label().to_string(), url);
+    let tx = {
+        let connections = state.connections.lock().unwrap();
+        connections.get(&key).map(|c| c.tx.clone())
+    };
+
+    if let Some(tx) = tx {
+        enqueue_relay_message(&tx, Message::Text(message.into()))?;
+        Ok("Sent".to_string())
+    } else {
+        Err("Not connected".to_string())
+    }
+}
+
+/// Upper bound for `send_relay_message_await` timeouts
+const SEND_AWAIT_MAX_TIMEOUT_MS: u64 = 60_000;
+
+/// Relay's OK verdict for a frame sent through `send_relay_message_await`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayOkOutcome {
+    pub event_id: String,
+    pub accepted: bool,
+    pub message: Option<String>,
+}
+
+// Command: Send a raw EVENT or AUTH frame and wait for the relay's correlated OK
+#[tauri::command]
+pub async fn send_relay_message_await(
+    window: WebviewWindow,
+    state: State<'_, RelayPool>,
+    url: String,
+    message: String,
+    timeout_ms: Option<u64>,
+) -> Result<RelayOkOutcome, String> {
+    let url = normalize_relay_url(&url)?;
+    let frame: Value =
+        serde_json::from_str(&message).map_err(|e| format!("Message is not JSON: {}", e))?;
+    let event = match frame.as_array().map(Vec::as_slice) {
+        Some([Value::String(verb), event]) if verb == "EVENT" || verb == "AUTH" => event,
+        _ => {
+            return Err(
+                "Only [\"EVENT\", <event>] and [\"AUTH\", <event>] frames get an OK".to_string(),
+            )
+        }
+    };
+    let event_id = extract_event_id(event)?;
+    let ack_timeout = Duration::from_millis(
+        timeout_ms
+            .unwrap_or(WRITE_RELAY_ACK_TIMEOUT_MS)
+            .min(SEND_AWAIT_MAX_TIMEOUT_MS),
+    );
+    let ack = state
+        .send_awaiting_ok(window.label(), &url, event_id.clone(), message, ack_timeout)
+        .await?;
+    Ok(RelayOkOutcome {
+        event_id,
+        accepted: ack.ok,
+        message: ack.message,
+    })
+}
+
+// Command: Override the proxy used for one relay (takes effect on the next connect)
+#[tauri::command]
+pub async fn set_relay_proxy(
+    app: AppHandle,
+    state: State<'_, RelayPool>,
+    url: String,
+    proxy: String,
+) -> Result<(), String> {
+    let url = normalize_relay_url(&url)?;
+    let route = parse_relay_proxy(&proxy)?;
+    state
+        .proxy_overrides
+        .lock()
+        .unwrap()
+        .insert(url.clone(), route);
+    state.update_saved_relays(&app, |relays| {
+        set_saved_relay_proxy(relays, &url, Some(proxy.trim().to_string()))
+    });
+    Ok(())
+}
+
+// Command: Drop a relay's proxy override so it follows the global setting again
+#[tauri::command]
+pub async fn clear_relay_proxy(
+    app: AppHandle,
+    state: State<'_, RelayPool>,
+    url: String,
+) -> Result<bool, String> {
+    let url = normalize_relay_url(&url)?;
+    let removed = state.proxy_overrides.lock().unwrap().remove(&url).is_some();
+    state.update_saved_relays(&app, |relays| set_saved_relay_proxy(relays, &url, None));
+    Ok(removed)
+}
+
+fn set_saved_relay_proxy(relays: &mut [SavedRelay], url: &str, proxy: Option<String>) -> bool {
+    match relays.iter_mut().find(|relay| relay.url == url) {
+        Some(relay) if relay.proxy != proxy => {
+            relay.proxy = proxy;
+            true
+        }
+        _ => false,
+    }
+}
+
+// Command: Send `tls_sni` instead of the relay host as the TLS server name for one wss relay,
+// or clear the override with None (takes effect on the next connect)
+#[tauri::command]
+pub async fn set_relay_tls_sni(
+    app: AppHandle,
+    state: State<'_, RelayPool>,
+    url: String,
+    tls_sni: Option<String>,
+) -> Result<(), String> {
+    let url = normalize_relay_url(&url)?;
+    let tls_sni = tls_sni
+        .filter(|tls_sni| !tls_sni.trim().is_empty())
+        .map(|tls_sni| parse_tls_sni(&tls_sni))
+        .transpose()?;
+    {
+        let mut overrides = state.tls_sni_overrides.lock().unwrap();
+        match &tls_sni {
+            Some(tls_sni) => overrides.insert(url.clone(), tls_sni.clone()),
+            None => overrides.remove(&url),
+        };
+    }
+    state.update_saved_relays(&app, |relays| {
+        match relays.iter_mut().find(|relay| relay.url == url) {
+            Some(relay) if relay.tls_sni != tls_sni => {
+                relay.tls_sni = tls_sni;
+                true
+            }
+            _ => false,
+        }
+    });
+    Ok(())
+}
+
+// Command: The persisted relay list restored at startup
+#[tauri::command]
+pub async fn get_saved_relays(state: State<'_, RelayPool>) -> Result<Vec<SavedRelay>, String> {
+    Ok(state.saved.lock().unwrap().clone())
+}
+
+// Command: Replace the persisted relay list; markers apply to this window and proxy
+// overrides take effect on each relay's next connect
+#[tauri::command]
+pub async fn save_relays(
+    app: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, RelayPool>,
+    relays: Vec<SavedRelay>,
+) -> Result<Vec<SavedRelay>, String> {
+    let mut seen = HashSet::new();
+    let mut normalized = Vec::with_capacity(relays.len());
+    let mut routes = Vec::with_capacity(relays.len());
+    for relay in relays {
+        let url = normalize_relay_url(&relay.url)?;
+        if !seen.insert(url.clone()) {
+            continue;
+        }
+        let proxy = relay
+            .proxy
+            .map(|proxy| proxy.trim().to_string())
+            .filter(|proxy| !proxy.is_empty());
+        routes.push(proxy.as_deref().map(parse_relay_proxy).transpose()?);
+        let tls_sni = relay
+            .tls_sni
+            .filter(|tls_sni| !tls_sni.trim().is_empty())
+            .map(|tls_sni| parse_tls_sni(&tls_sni))
+            .transpose()?;
+        normalized.push(SavedRelay {
+            url,
+            marker: relay.marker,
+            proxy,
+            tls_sni,
+        });
+    }
+
+    {
+        let mut overrides = state.proxy_overrides.lock().unwrap();
+        for (relay, route) in normalized.iter().zip(routes) {
+            match route {
+                Some(route) => overrides.insert(relay.url.clone(), route),
+                None => overrides.remove(&relay.url),
+            };
+        }
+    }
+    {
+        let mut tls_sni_overrides = state.tls_sni_overrides.lock().unwrap();
+        for relay in &normalized {
+            match &relay.tls_sni {
+                Some(tls_sni) => tls_sni_overrides.insert(relay.url.clone(), tls_sni.clone()),
+                None => tls_sni_overrides.remove(&relay.url),
+            };
+        }
+    }
+    {
+        let mut states = state.states.lock().unwrap();
+        for relay in &normalized {
+            let key = (window.label().to_string(), relay.url.clone());
+            states.entry(key).or_default().marker = relay.marker;
+        }
+    }
+    let saved = normalized.clone();
+    state.update_saved_relays(&app, move |relays| {
+        *relays = saved;
+        true
+    });
+    Ok(normalized)
+}
+
+/// Run one REQ on an already-connected relay and collect its events until EOSE, CLOSED or `deadline`.
+async fn collect_relay_events(
+    state: &RelayPool,
+    window_label: &str,
+    url: &str,
+    filters: &[Value],
+    deadline: Instant,
+) -> Result<Vec<Value>, String> {
+    let key = (window_label.to_string(), url.to_string());
+    let tx = {
+        let connections = state.connections.lock().unwrap();
+        connections.get(&key).map(|c| c.tx.clone())
+    };
+    let Some(tx) = tx else {
+        return Err("Not connected".to_string());
+    };
+
+    let sub_id = format!("fetch-{}", uuid::Uuid::new_v4().simple());
+    let collector_key = (window_label.to_string(), url.to_string(), sub_id.clone());
+    let (frame_tx, mut frame_rx) = mpsc::unbounded_channel::<Value>();
+    state
+        .sub_collectors
+        .lock()
+        .unwrap()
+        .insert(collector_key.clone(), frame_tx);
+    let _subscription = FetchSubscription {
+        sub_collectors: state.sub_collectors.clone(),
+        key: collector_key,
+        tx: tx.clone(),
+    };
+
+    let req = req_frame(&sub_id, filters);
+    let mut result = enqueue_relay_message(&tx, Message::Text(req.into()));
+    let mut events = Vec::new();
+    while result.is_ok() {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let Ok(Some(frame)) = timeout(remaining, frame_rx.recv()).await else {
+            break;
+        };
+        match frame.get(0).and_then(Value::as_str) {
+            Some("EVENT") => {
+                if let Some(event) = frame.get(2) {
+                    events.push(event.clone());
+                }
+            }
+            Some("CLOSED") => {
+                let reason = frame
+                    .get(2)
+                    .and_then(Value::as_str)
+                    .unwrap_or("closed by relay");
+                result = Err(format!("Subscription closed: {}", reason));
+            }
+            _ => break, // EOSE
+        }
+    }
+
+    result.map(|()| events)
+}
+
+/// Unregisters a one-shot fetch and CLOSEs it on the relay when dropped, so a fetch
+/// abandoned mid-flight (e.g. another relay answered first) does not leak the REQ.
+struct FetchSubscription {
+    sub_collectors: Arc<Mutex<HashMap<SubCollectorKey, UnboundedSender<Value>>>>,
+    key: SubCollectorKey,
+    tx: Sender<Message>,
+}
+
+impl Drop for FetchSubscription {
+    fn drop(&mut self) {
+        if let Ok(mut collectors) = self.sub_collectors.lock() {
+            collectors.remove(&self.key);
+        }
+        let close = serde_json::json!(["CLOSE", self.key.2]);
+        let _ = enqueue_relay_message(&self.tx, Message::Text(close.to_string().into()));
+    }
+}
+
+/// `event` parsed, with a valid id and signature, and carrying `id`
+fn verified_event_with_id(event: &Value, id: &nostr::EventId) -> bool {
+    serde_json::from_value::<nostr::Event>(event.clone())
+        .is_ok_and(|parsed| parsed.id == *id && parsed.verify().is_ok())
+}
+
+// Command: Look up one event (hex id, note or nevent) across relays. Returns the first copy
+// whose id and signature verify, closing the other relays' REQs; None if none arrives in time
+#[tauri::command]
+pub async fn fetch_event_by_id(
+    app: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, RelayPool>,
+    net_runtime: State<'_, NativeNetworkRuntime>,
+    urls: Vec<String>,
+    event_id: String,
+    timeout_ms: Option<u64>,
+) -> Result<Option<Value>, String> {
+    let id = parse_event_reference(&event_id)?;
+    let urls = normalize_relay_urls(&urls)?;
+    let filter = serde_json::json!({ "ids": [id.to_hex()], "limit": 1 });
+    let budget = timeout_ms
+        .unwrap_or(FETCH_EVENTS_DEFAULT_TIMEOUT_MS)
+        .min(FETCH_EVENTS_MAX_TIMEOUT_MS);
+    let deadline = Instant::now() + Duration::from_millis(budget);
+    let window_label = window.label().to_string();
+
+    let mut lookups = urls
+        .into_iter()
+        .map(|url| {
+            let (app, window_label, state, net_runtime, filter, id) = (
+                app.clone(),
+                window_label.clone(),
+                state.clone(),
+                net_runtime.clone(),
+                &filter,
+                &id,
+            );
+            async move {
+                connect_relay_internal(
+                    app,
+                    window_label.clone(),
+                    url.clone(),
+                    state.clone(),
+                    net_runtime,
+                    ConnectTrigger::Explicit,
+                )
+                .await
+                .ok()?;
+                let events = collect_relay_events(
+                    &state,
+                    &window_label,
+                    &url,
+                    std::slice::from_ref(filter),
+                    deadline,
+                )
+                .await
+                .ok()?;
+                events
+                    .into_iter()
+                    .find(|event| verified_event_with_id(event, id))
+            }
+        })
+        .collect::<futures_util::stream::FuturesUnordered<_>>();
+
+    let first_verified = async {
+        while let Some(found) = lookups.next().await {
+            if found.is_some() {
+                return found;
+            }
+        }
+        None
+    };
+    // Dropping the remaining lookups closes their subscriptions
+    Ok(timeout(
+        deadline.saturating_duration_since(Instant::now()),
+        first_verified,
+    )
+    .await
+    .ok()
+    .flatten())
+}
+
+/// Event id from hex, `note1…` or `nevent1…`, optionally `nostr:`-prefixed
+fn parse_event_reference(reference: &str) -> Result<nostr::EventId, String> {
+    use nostr::nips::nip19::{FromBech32, Nip19Event};
+
+    let trimmed = reference.trim();
+    let bech32 = trimmed.strip_prefix("nostr:").unwrap_or(trimmed);
+    if bech32.starts_with("nevent1") {
+        return Nip19Event::from_bech32(bech32)
+            .map(|event| event.event_id)
+            .map_err(|e| format!("Invalid event id: {e}"));
+    }
+    nostr::EventId::parse(bech32).map_err(|e| format!("Invalid event id: {e}"))
+}
+
+/// Dedupe by event id and return newest first, truncated to `limit`.
+fn merge_fetched_events(batches: Vec<Vec<Value>>, limit: Option<usize>) -> Vec<Value> {
+    let mut seen = HashSet::new();
+    let mut merged: Vec<Value> = batches
+        .into_iter()
+        .flatten()
+        .filter(|event| match event.get("id").and_then(Value::as_str) {
+            Some(id) => seen.insert(id.to_string()),
+            None => false,
+        })
+        .collect();
+    merged.sort_by_key(|event| {
+        std::cmp::Reverse(event.get("created_at").and_then(Value::as_u64).unwrap_or(0))
+    });
+    if let Some(limit) = limit {
+        merged.truncate(limit);
+    }
+    merged
+}
+
+// Command: One-shot query across relays; returns collected events instead of streaming them
+#[tauri::command]
+pub async fn fetch_events(
+    app: AppHandle,
+    window: WebviewWindow,
+    urls: Vec<String>,
+    filter: Value,
+    timeout_ms: Option<u64>,
+    limit: Option<usize>,
+) -> Result<Vec<Value>, String> {
+    fetch_events_internal(
+        app,
+        window.label().to_string(),
+        &urls,
+        filter,
+        timeout_ms,
+        limit,
+    )
+    .await
+}
+
+// Connect to each relay, run the filter once, and merge what comes back before the deadline
+async fn fetch_events_internal(
+    app: AppHandle,
+    window_label: String,
+    urls: &[String],
+    filter: Value,
+    timeout_ms: Option<u64>,
+    limit: Option<usize>,
+) -> Result<Vec<Value>, String> {
+    let state = app.state::<RelayPool>();
+    let net_runtime = app.state::<NativeNetworkRuntime>();
+    if !filter.is_object() {
+        return Err("Filter must be a JSON object".to_string());
+    }
+    let urls = normalize_relay_urls(urls)?;
+    let mut filter = filter;
+    if let (Some(limit), Some(object)) = (limit, filter.as_object_mut()) {
+        object
+            .entry("limit")
+            .or_insert_with(|| serde_json::json!(limit));
+    }
+    let budget = timeout_ms
+        .unwrap_or(FETCH_EVENTS_DEFAULT_TIMEOUT_MS)
+        .min(FETCH_EVENTS_MAX_TIMEOUT_MS);
+    let deadline = Instant::now() + Duration::from_millis(budget);
+
+    let queries = urls.iter().map(|url| {
+        let (app, window_label, state, net_runtime, filter) = (
+            app.clone(),
+            window_label.clone(),
+            state.clone(),
+            net_runtime.clone(),
+            &filter,
+        );
+        async move {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match timeout(
+                remaining,
+                connect_relay_internal(
+                    app,
+                    window_label.clone(),
+                    url.clone(),
+                    state.clone(),
+                    net_runtime,
+                    ConnectTrigger::Explicit,
+                ),
+            )
+            .await
+            {
+                Ok(Ok(_)) => {}
+                Ok(Err(e)) => return Err(format!("{}: {}", url, e)),
+                Err(_) => return Err(format!("{}: connect timed out", url)),
+            }
+            collect_relay_events(
+                &state,
+                &window_label,
+                url,
+                std::slice::from_ref(filter),
+                deadline,
+            )
+            .await
+            .map_err(|e| format!("{}: {}", url, e))
+        }
+    });
+    let results = futures_util::future::join_all(queries).await;
+
+    let mut batches = Vec::new();
+    let mut errors = Vec::new();
+    for result in results {
+        match result {
+            Ok(events) => batches.push(events),
+            Err(e) => errors.push(e),
+        }
+    }
+    if batches.is_empty() && !errors.is_empty() {
+        return Err(format!("All relays failed: {}", errors.join("; ")));
+    }
+    Ok(merge_fetched_events(batches, limit))
+}
+
+// Rounds `fetch_events_paged` runs to fill a page relays returned short (dedupe, per-relay caps)
+const FETCH_PAGE_MAX_ROUNDS: usize = 4;
+
+/// One page of `fetch_events_paged`, newest first
+#[derive(Debug, Serialize, Clone)]
+pub struct FetchedEventPage {
+    pub events: Vec<Value>,
+    /// `until` for the next page (oldest `created_at` minus one); None once relays have
+    /// nothing older
+    pub next_until: Option<u64>,
+}
+
+/// `filter` with `limit` and, when given, `until` replaced
+fn windowed_filter(filter: &Value, until: Option<u64>, limit: usize) -> Value {
+    let mut filter = filter.clone();
+    if let Some(object) = filter.as_object_mut() {
+        if let Some(until) = until {
+            object.insert("until".to_string(), Value::from(until));
+        }
+        object.insert("limit".to_string(), Value::from(limit));
+    }
+    filter
+}
+
+/// Oldest `created_at` in `events` minus one: the `until` that continues below them
+fn page_cursor(events: &[Value]) -> Option<u64> {
+    events
+        .iter()
+        .filter_map(|event| event.get("created_at").and_then(Value::as_u64))
+        .min()
+        .map(|oldest| oldest.saturating_sub(1))
+}
+
+// Command: Fetch one page of history older than `until` (newest first when omitted),
+// deduped across relays. Short pages are topped up with further REQs below the oldest
+// event so far; pass `next_until` back in for the following page
+#[tauri::command]
+pub async fn fetch_events_paged(
+    app: AppHandle,
+    window: WebviewWindow,
+    urls: Vec<String>,
+    base_filter: Value,
+    page_size: usize,
+    until: Option<u64>,
+) -> Result<FetchedEventPage, String> {
+    let page_size = page_size.clamp(1, MAX_PAGE_LIMIT);
+    // One fetch's budget covers the whole page, top-up rounds included
+    let deadline = Instant::now() + Duration::from_millis(FETCH_EVENTS_DEFAULT_TIMEOUT_MS);
+    let mut seen = HashSet::new();
+    let mut events: Vec<Value> = Vec::new();
+    let mut cursor = until;
+    let mut exhausted = false;
+
+    for round in 0..FETCH_PAGE_MAX_ROUNDS {
+        let wanted = page_size - events.len();
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if wanted == 0 || remaining.is_zero() {
+            break;
+        }
+        let batch = match fetch_events_internal(
+            app.clone(),
+            window.label().to_string(),
+            &urls,
+            windowed_filter(&base_filter, cursor, wanted),
+            Some(remaining.as_millis() as u64),
+            Some(wanted),
+        )
+        .await
+        {
+            Ok(batch) => batch,
+            Err(e) if round == 0 => return Err(e),
+            Err(_) => break,
+        };
+        let fresh: Vec<Value> = batch
+            .into_iter()
+            .filter(|event| match event.get("id").and_then(Value::as_str) {
+                Some(id) => seen.insert(id.to_string()),
+                None => false,
+            })
+            .collect();
+        if fresh.is_empty() {
+            exhausted = true;
+            break;
+        }
+        let short = fresh.len() < wanted;
+        events.extend(fresh);
+        cursor = page_cursor(&events);
+        if short && round > 0 {
+            // A second short round: the relays are running out of history
+            break;
+        }
+    }
+
+    let events = merge_fetched_events(vec![events], Some(page_size));
+    Ok(FetchedEventPage {
+        next_until: if exhausted && events.is_empty() {
+            None
+        } else {
+            page_cursor(&events).or(cursor)
+        },
+        events,
+    })
+}
+
+// Command: Throughput counters for one connected relay (None when not connected)
+#[tauri::command]
+pub async fn get_relay_metrics(
+    window: WebviewWindow,
+    state: State<'_, RelayPool>,
+    url: String,
+) -> Result<Option<RelayMetrics>, String> {
+    let url = normalize_relay_url(&url)?;
+    let key = (window.label().to_string(), url.clone());
+    let connections = state.connections.lock().unwrap();
+    Ok(connections
+        .get(&key)
+        .map(|connection| connection.metrics_snapshot(&url)))
+}
+
+// Command: Events each active subscription on `url` has delivered since it was placed;
+// subscriptions that delivered nothing are listed with a zero count
+#[tauri::command]
+pub async fn get_subscription_stats(
+    window: WebviewWindow,
+    state: State<'_, RelayPool>,
+    url: String,
+) -> Result<HashMap<String, SubscriptionStats>, String> {
+    let url = normalize_relay_url(&url)?;
+    let key = (window.label().to_string(), url);
+    let states = state.states.lock().unwrap();
+    let Some(relay_state) = states.get(&key) else {
+        return Ok(HashMap::new());
+    };
+    Ok(relay_state
+        .subscriptions
+        .keys()
+        .map(|sub_id| {
+            let stats = relay_state
+                .subscription_stats
+                .get(sub_id)
+                .cloned()
+                .unwrap_or_default();
+            (sub_id.clone(), stats)
+        })
+        .collect())
+}
+
+// Command: Throughput counters for every relay connected in this window
+#[tauri::command]
+pub async fn get_all_relay_metrics(
+    window: WebviewWindow,
+    state: State<'_, RelayPool>,
+) -> Result<Vec<RelayMetrics>, String> {
+    let window_label = window.label().to_string();
+    let connections = state.connections.lock().unwrap();
+    let mut metrics: Vec<RelayMetrics> = connections
+        .iter()
+        .filter(|((label, _), _)| *label == window_label)
+        .map(|((_, url), connection)| connection.metrics_snapshot(url))
+        .collect();
+    metrics.sort_by(|a, b| a.url.cmp(&b.url));
+    Ok(metrics)
+}
+
+// Command: Set a relay's NIP-65 marker for this window (defaults to both)
+#[tauri::command]
+pub async fn set_relay_marker(
+    app: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, RelayPool>,
+    url: String,
+    marker: RelayMarker,
+) -> Result<(), String> {
+    let url = normalize_relay_url(&url)?;
+    let key = (window.label().to_string(), url.clone());
+    state.states.lock().unwrap().entry(key).or_default().marker = marker;
+    state.update_saved_relays(&app, |relays| set_saved_relay_marker(relays, &url, marker));
+    Ok(())
+}
+
+fn set_saved_relay_marker(relays: &mut [SavedRelay], url: &str, marker: RelayMarker) -> bool {
+    match relays.iter_mut().find(|relay| relay.url == url) {
+        Some(relay) if relay.marker != marker => {
+            relay.marker = marker;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Relays taken from one NIP-65 list; the rest of a longer list is ignored
+const MAX_NIP65_RELAYS: usize = 20;
+
+/// Newest kind 10002 event by `author` whose id and signature verify. Relays that ignore
+/// the filter or forge a list must not smuggle in relays.
+fn latest_nip65_event(events: Vec<Value>, author: &str) -> Option<Value> {
+    events
+        .into_iter()
+        .filter_map(|event| {
+            let parsed = serde_json::from_value::<nostr::Event>(event.clone()).ok()?;
+            let genuine = parsed.kind.as_u16() == 10002
+                && parsed.pubkey.to_hex() == author
+                && parsed.verify().is_ok();
+            genuine.then_some((parsed.created_at.as_u64(), event))
+        })
+        .max_by_key(|(created_at, _)| *created_at)
+        .map(|(_, event)| event)
+}
+
+/// `r` tags of a kind 10002 event. Unknown markers read as both; non-websocket, invalid and
+/// duplicate URLs are dropped before the `MAX_NIP65_RELAYS` cap
+fn parse_nip65_relays(event: &Value) -> Vec<Nip65Relay> {
+    let mut seen = HashSet::new();
+    event
+        .get("tags")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|tag| {
+            let tag = tag.as_array()?;
+            if tag.first()?.as_str()? != "r" {
+                return None;
+            }
+            let url = normalize_relay_url(tag.get(1)?.as_str()?).ok()?;
+            if !seen.insert(url.clone()) {
+                return None;
+            }
+            let marker = match tag.get(2).and_then(Value::as_str) {
+                Some("read") => RelayMarker::Read,
+                Some("write") => RelayMarker::Write,
+                _ => RelayMarker::Both,
+            };
+            Some(Nip65Relay {
+                url: url.to_string(),
+                marker,
+                connected: None,
+            })
+        })
+        .take(MAX_NIP65_RELAYS)
+        .collect()
+}
+
+// Command: Fetch a pubkey's latest kind 10002 relay list from bootstrap relays,
+// optionally connecting to each listed relay with its marker. None when no list is published.
+#[tauri::command]
+pub async fn import_relays_from_nip65(
+    app: AppHandle,
+    window: WebviewWindow,
+    state: State<'_, RelayPool>,
+    net_runtime: State<'_, NativeNetworkRuntime>,
+    urls: Vec<String>,
+    pubkey: String,
+    auto_connect: Option<bool>,
+) -> Result<Option<Nip65RelayList>, String> {
+    let author = nostr::PublicKey::parse(pubkey.trim())
+        .map_err(|e| format!("Invalid pubkey: {}", e))?
+        .to_hex();
+    let window_label = window.label().to_string();
+    let filter = serde_json::json!({ "kinds": [10002], "authors": [author] });
+    let events =
+        fetch_events_internal(app.clone(), window_label.clone(), &urls, filter, None, None).await?;
+    let Some(event) = latest_nip65_event(events, &author) else {
+        return Ok(None);
+    };
+
+    let mut relays = parse_nip65_relays(&event);
+    if auto_connect.unwrap_or(false) {
+        {
+            let mut states = state.states.lock().unwrap();
+            for relay in &relays {
+                states
+                    .entry((window_label.clone(), relay.url.clone()))
+                    .or_default()
+                    .marker = relay.marker;
+            }
+        }
+        let connects = relays.iter().map(|relay| {
+            connect_relay_gated(
+                app.clone(),
+                window_label.clone(),
+                relay.url.clone(),
+                state.clone(),
+                net_runtime.clone(),
+                ConnectTrigger::Explicit,
+            )
+        });
+        let results = futures_util::future::join_all(connects).await;
+        for (relay, result) in relays.iter_mut().zip(results) {
+            if let Err(e) = &result {
+                log::warn!(
+                    "[NativeRelay] NIP-65 connect to {} failed: {}",
+                    relay.url,
+                    e
+                );
+            }
+            relay.connected = Some(result.is_ok());
+        }
+    }
+
+    Ok(Some(Nip65RelayList {
+        event_id: event
+            .get("id")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+        created_at: event.get("created_at").and_then(Value::as_u64).unwrap_or(0),
+        relays,
+    }))
+}
+
+// Command: Publish to every connected write relay and wait for each OK
+#[tauri::command]
+pub async fn publish_to_write_relays(
+    window: WebviewWindow,
+    state: State<'_, RelayPool>,
+    event_json: Value,
+) -> Result<Vec<RelayPublishResult>, String> {
+    let window_label = window.label().to_string();
+    let mut write_urls: Vec<String> = {
+        let connections = state.connections.lock().unwrap();
+        connections
+            .keys()
+            .filter(|(label, _)| *label == window_label)
+            .map(|(_, url)| url.clone())
+            .collect()
+    };
+    write_urls.retain(|url| state.marker_for(&window_label, url).allows_write());
+    write_urls.sort();
+
+    let deliveries =
+        deliver_to_relays(&state, &window_label, write_urls, event_json, None, true).await?;
+    Ok(deliveries
+        .into_iter()
+        .map(|(url, delivery)| RelayPublishResult {
+            url,
+            ok: delivery.status == RelayDeliveryStatus::Accepted,
+            message: delivery.message,
+        })
+        .collect())
+}
+
+// Command: Publish to the given relays concurrently and wait for each OK. Every URL gets
+// an outcome in input order; relays that are not connected or are read-only are reported
+// rather than skipped
+#[tauri::command]
+pub async fn publish_to_relays(
+    window: WebviewWindow,
+    state: State<'_, RelayPool>,
+    urls: Vec<String>,
+    event_json: Value,
+    timeout_ms: Option<u64>,
+    use_markers: Option<bool>,
+) -> Result<Vec<RelayPublishOutcome>, String> {
+    let deliveries = deliver_to_relays(
+        &state,
+        window.label(),
+        urls,
+        event_json,
+        timeout_ms,
+        use_markers.unwrap_or(true),
+    )
+    .await?;
+    Ok(deliveries
+        .into_iter()
+        .map(|(url, delivery)| RelayPublishOutcome {
+            url,
+            accepted: delivery.status == RelayDeliveryStatus::Accepted,
+            message: delivery.message,
+        })
+        .collect())
+}
+
+// Command: Publish one event to `urls` in a single call and report, per normalized URL,
+// whether each relay accepted, rejected or never answered it before the deadline
+#[tauri::command]
+pub async fn publish_event_multi(
+    window: WebviewWindow,
+    state: State<'_, RelayPool>,
+    urls: Vec<String>,
+    event_json: Value,
+    timeout_ms: Option<u64>,
+) -> Result<HashMap<String, RelayDelivery>, String> {
+    let deliveries =
+        deliver_to_relays(&state, window.label(), urls, event_json, timeout_ms, true).await?;
+    Ok(deliveries.into_iter().collect())
+}
+
+/// Send an EVENT to each of `urls` concurrently and classify every relay's OK, in input
+/// order. Relays that cannot be written to are reported as `Failed` without sending.
+/// The one fan-out behind `publish_to_write_relays`, `publish_to_relays` and
+/// `publish_event_multi`.
+async fn deliver_to_relays(
+    state: &RelayPool,
+    window_label: &str,
+    urls: Vec<String>,
+    event_json: Value,
+    timeout_ms: Option<u64>,
+    use_markers: bool,
+) -> Result<Vec<(String, RelayDelivery)>, String> {
+    let event_id = extract_event_id(&event_json)?;
+    let frame = serde_json::json!(["EVENT", event_json]).to_string();
+    let ack_timeout = Duration::from_millis(
+        timeout_ms
+            .unwrap_or(WRITE_RELAY_ACK_TIMEOUT_MS)
+            .min(SEND_AWAIT_MAX_TIMEOUT_MS),
+    );
+    let window_label = window_label.to_string();
+
+    // (url, reason it is not attempted)
+    let mut targets: Vec<(String, Option<String>)> = Vec::new();
+    let mut seen = HashSet::new();
+    for raw in urls {
+        let url = match normalize_relay_url(&raw) {
+            Ok(url) => url,
+            Err(error) => {
+                targets.push((raw, Some(error)));
+                continue;
+            }
+        };
+        if !seen.insert(url.clone()) {
+            continue;
+        }
+        let connected = state
+            .connections
+            .lock()
+            .unwrap()
+            .contains_key(&(window_label.clone(), url.clone()));
+        let skip = if !connected {
+            Some("Not connected".to_string())
+        } else if use_markers && !state.marker_for(&window_label, &url).allows_write() {
+            Some(format!("Relay {} is read-only; not publishing", url))
+        } else {
+            None
+        };
+        targets.push((url, skip));
+    }
+
+    let publishes = targets.into_iter().map(|(url, skip)| {
+        let window_label = &window_label;
+        let (event_id, frame) = (event_id.clone(), frame.clone());
+        async move {
+            if let Some(message) = skip {
+                let delivery = RelayDelivery {
+                    status: RelayDeliveryStatus::Failed,
+                    message: Some(message),
+                };
+                return (url, delivery);
+            }
+            let delivery = match state
+                .send_awaiting_ok(window_label, &url, event_id, frame, ack_timeout)
+                .await
+            {
+                Ok(ack) => RelayDelivery {
+                    status: if ack.ok {
+                        RelayDeliveryStatus::Accepted
+                    } else {
+                        RelayDeliveryStatus::Rejected
+                    },
+                    message: ack.message,
+                },
+                Err(error) => RelayDelivery {
+                    status: match error {
+                        AwaitOkError::Timeout => RelayDeliveryStatus::Timeout,
+                        AwaitOkError::Failed(_) => RelayDeliveryStatus::Failed,
+                    },
+                    message: Some(error.to_string()),
+                },
+            };
+            (url, delivery)
+        }
+    });
+    Ok(futures_util::future::join_all(publishes).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subscription_frames_name_their_subscription() {
+        let event = serde_json::json!(["EVENT", "feed", { "id": "a" }]);
+        assert_eq!(subscription_frame(&event), Some(("EVENT", "feed")));
+        let eose = serde_json::json!(["EOSE", "feed"]);
+        assert_eq!(subscription_frame(&eose), Some(("EOSE", "feed")));
+        let closed = serde_json::json!(["CLOSED", "feed", "auth-required: sign in"]);
+        assert_eq!(subscription_frame(&closed), Some(("CLOSED", "feed")));
+        assert_eq!(
+            subscription_frame(&serde_json::json!(["NOTICE", "slow down"])),
+            None
+        );
+        assert_eq!(
+            subscription_frame(&serde_json::json!(["OK", "id", true, ""])),
+            None
+        );
+    }
+
+    #[test]
+    fn nip65_lists_must_verify_and_cap_after_filtering() {
+        let keys = nostr::Keys::generate();
+        let author = keys.public_key().to_hex();
+        let mut tags = vec![
+            nostr::Tag::parse(["r", "not a url"]).unwrap(),
+            nostr::Tag::parse(["r", "wss://dup.example"]).unwrap(),
+            nostr::Tag::parse(["r", "wss://dup.example/"]).unwrap(),
+        ];
+        tags.extend(
+            (0..MAX_NIP65_RELAYS).map(|i| {
+                nostr::Tag::parse(["r".to_string(), format!("wss://r{i}.example")]).unwrap()
+            }),
+        );
+        let event = nostr::EventBuilder::new(nostr::Kind::RelayList, "")
+            .tags(tags)
+            .sign_with_keys(&keys)
+            .expect("sign");
+        let json = serde_json::to_value(&event).expect("json");
+
+        let mut forged = json.clone();
+        forged["tags"] = serde_json::json!([["r", "wss://evil.example"]]);
+        assert_eq!(latest_nip65_event(vec![forged.clone()], &author), None);
+        let other = nostr::Keys::generate().public_key().to_hex();
+        assert_eq!(latest_nip65_event(vec![json.clone()], &other), None);
+        let picked = latest_nip65_event(vec![forged, json.clone()], &author).unwrap();
+        assert_eq!(picked, json);
+
+        let relays = parse_nip65_relays(&picked);
+        assert_eq!(relays.len(), MAX_NIP65_RELAYS);
+        assert_eq!(relays[0].url, "wss://dup.example");
+        assert_eq!(relays[1].url, "wss://r0.example");
+    }
+
+    #[test]
+    fn fetched_events_must_verify_against_the_requested_id() {
+        let keys = nostr::Keys::generate();
+        let event = nostr::EventBuilder::text_note("quoted")
+            .sign_with_keys(&keys)
+            .expect("sign");
+        let json = serde_json::to_value(&event).expect("json");
+        assert!(verified_event_with_id(&json, &event.id));
+        assert!(!verified_event_with_id(&json, &nostr::EventId::all_zeros()));
+
+        let mut forged = json.clone();
+        forged["content"] = Value::from("edited");
+        assert!(!verified_event_with_id(&forged, &event.id));
+
+        let note = nostr::ToBech32::to_bech32(&event.id).expect("note");
+        assert_eq!(
+            parse_event_reference(&format!("nostr:{note}")),
+            Ok(event.id)
+        );
+        assert_eq!(parse_event_reference(&event.id.to_hex()), Ok(event.id));
+        assert!(parse_event_reference("not-an-id").is_err());
+    }
+
+    #[test]
+    fn probe_adds_no_separate_lookup_while_tor_is_on() {
+        assert_eq!(probe_dns_mode(true, true, false), "proxy");
+        assert_eq!(probe_dns_mode(false, true, true), "skipped");
+        assert_eq!(probe_dns_mode(false, false, true), "doh");
+        assert_eq!(probe_dns_mode(false, false, false), "system");
+    }
+
+    #[test]
+    fn fetched_pages_continue_below_their_oldest_event() {
+        let filter = serde_json::json!({ "kinds": [1], "until": 9_999, "limit": 5 });
+        assert_eq!(
+            windowed_filter(&filter, Some(1_700), 20),
+            serde_json::json!({ "kinds": [1], "until": 1_700, "limit": 20 })
+        );
+        assert_eq!(
+            windowed_filter(&filter, None, 20),
+            serde_json::json!({ "kinds": [1], "until": 9_999, "limit": 20 })
+        );
+
+        let events = vec![
+            serde_json::json!({ "id": "a", "created_at": 1_800 }),
+            serde_json::json!({ "id": "b", "created_at": 1_750 }),
+        ];
+        assert_eq!(page_cursor(&events), Some(1_749));
+        assert_eq!(page_cursor(&[]), None);
+    }
+
+    #[test]
+    fn paging_bounds_filters_before_the_oldest_event() {
+        let frame = serde_json::json!(["EVENT", "feed", { "id": "a", "created_at": 1_700 }]);
+        assert_eq!(event_frame_cursor(&frame), Some(("feed", 1_700)));
+        assert_eq!(
+            event_frame_cursor(&serde_json::json!(["EOSE", "feed"])),
+            None
+        );
+
+        let filters = vec![
+            serde_json::json!({ "kinds": [1], "since": 10 }),
+            serde_json::json!({ "kinds": [7] }),
+        ];
+        let paged = paged_filters(&filters, 1_700, 50);
+        assert_eq!(
+            paged[0],
+            serde_json::json!({ "kinds": [1], "since": 10, "until": 1_699, "limit": 50 })
+        );
+        assert_eq!(paged[1]["until"], 1_699);
+    }
+
+    #[test]
+    fn subscriptions_send_every_filter_in_one_req() {
+        let mine = serde_json::json!({ "authors": ["a"] });
+        let mentions = serde_json::json!({ "#p": ["a"] });
+        let filters = subscription_filters(None, Some(vec![mine.clone(), mentions.clone()]))
+            .expect("filters");
+        assert_eq!(
+            req_frame("feed", &filters),
+            r##"["REQ","feed",{"authors":["a"]},{"#p":["a"]}]"##
+        );
+        // Legacy single filter, and an array passed through it
+        assert_eq!(
+            subscription_filters(Some(mine.clone()), None).unwrap(),
+            vec![mine.clone()]
+        );
+        assert_eq!(
+            subscription_filters(Some(serde_json::json!([mine, mentions])), None)
+                .unwrap()
+                .len(),
+            2
+        );
+        assert!(subscription_filters(None, None).is_err());
+        assert!(subscription_filters(None, Some(vec![serde_json::json!("kinds")])).is_err());
+    }
+
+    #[test]
+    fn normalize_relay_url_collapses_cosmetic_variants() {
+        for raw in [
+            "wss://relay.example",
+            "wss://relay.example/",
+            " WSS://Relay.Example:443/ ",
+            "relay.example",
+            "wss://relay.example/#frag",
+        ] {
+            assert_eq!(normalize_relay_url(raw).unwrap(), "wss://relay.example");
+        }
+        assert_eq!(
+            normalize_relay_url("ws://relay.example:80/nostr/").unwrap(),
+            "ws://relay.example/nostr"
+        );
+        assert_eq!(
+            normalize_relay_url("wss://relay.example:7777").unwrap(),
+            "wss://relay.example:7777"
+        );
+        assert_eq!(
+            normalize_relay_url("wss://relay.example/?token=a").unwrap(),
+            "wss://relay.example/?token=a"
+        );
+        assert!(normalize_relay_url("https://relay.example").is_err());
+        assert!(normalize_relay_url("   ").is_err());
+    }
+
+    #[test]
+    fn publish_buffers_are_keyed_on_the_normalized_url() {
+        let pool = RelayPool::new();
+        for (capacity, raw) in [
+            "wss://relay.example",
+            "wss://relay.example/",
+            " WSS://Relay.Example:443/ ",
+            "relay.example",
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            assert_eq!(pool.set_publish_buffer("main", raw, capacity), Ok(capacity));
+        }
+        let states = pool.states.lock().unwrap();
+        let keys: Vec<_> = states.keys().collect();
+        assert_eq!(
+            keys,
+            [&("main".to_string(), "wss://relay.example".to_string())]
+        );
+        assert_eq!(states[keys[0]].publish_buffer_capacity, 3);
+        drop(states);
+        assert!(pool.set_publish_buffer("main", "   ", 1).is_err());
+    }
+
+    #[test]
+    fn normalize_relay_urls_dedupes_in_order() {
+        let urls = vec![
+            "wss://b.example/".to_string(),
+            "wss://a.example".to_string(),
+            "wss://B.example".to_string(),
+        ];
+        assert_eq!(
+            normalize_relay_urls(&urls).unwrap(),
+            vec!["wss://b.example".to_string(), "wss://a.example".to_string()]
+        );
+    }
+
+    #[test]
+    fn summary_counts_each_desired_relay_once() {
+        let set = |urls: &[&str]| {
+            urls.iter()
+                .map(|url| url.to_string())
+                .collect::<HashSet<_>>()
+        };
+        let desired = set(&["wss://a", "wss://b", "wss://c", "wss://d"]);
+        let connected = set(&["wss://a"]);
+        let connecting = set(&["wss://a", "wss://b"]);
+        let errors = HashMap::from([
+            ("wss://a".to_string(), "stale".to_string()),
+            ("wss://c".to_string(), "refused".to_string()),
+        ]);
+        let summary = summarize_relays(&desired, &connected, &connecting, &errors);
+        assert_eq!((summary.connected, summary.connecting), (1, 1));
+        assert_eq!((summary.disconnected, summary.desired), (2, 4));
+        assert_eq!(
+            summary.problems,
+            vec![RelayProblem {
+                url: "wss://c".to_string(),
+                error: "refused".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn auth_frames_are_recognized() {
+        let event = serde_json::json!({ "id": "abc", "kind": 22242 });
+        let frame = serde_json::json!(["AUTH", event]).to_string();
+        assert_eq!(auth_event_id(&frame).as_deref(), Some("abc"));
+        assert_eq!(published_event_id(&frame), None);
+
+        assert!(requests_auth(&serde_json::json!(["AUTH", "challenge"])));
+        assert!(requests_auth(&serde_json::json!([
+            "CLOSED",
+            "feed",
+            "auth-required: sign in first"
+        ])));
+        assert!(!requests_auth(&serde_json::json!([
+            "CLOSED",
+            "feed",
+            "error: bad filter"
+        ])));
+        assert!(!requests_auth(
+            &serde_json::json!(["AUTH", { "id": "abc" }])
+        ));
+    }
+
+    #[test]
+    fn released_relays_are_not_redialed() {
+        let pool = RelayPool::new();
+        pool.mark_wanted("main", "wss://a.example");
+        pool.mark_wanted("main", "wss://b.example");
+        let key = ("main".to_string(), "wss://a.example".to_string());
+        let (tx, _rx) = mpsc::channel(8);
+        pool.connections.lock().unwrap().insert(
+            key.clone(),
+            RelayConnection {
+                tx,
+                metrics: Arc::new(RelayConnectionMetrics::new()),
+                auth: Arc::default(),
+                reconnect_count: 0,
+            },
+        );
+        pool.states.lock().unwrap().entry(key.clone()).or_default();
+
+        assert!(pool.release_relay(&key).is_some());
+        // Its subscriptions state survives, but nothing brings the relay back
+        assert!(pool.states.lock().unwrap().contains_key(&key));
+        assert_eq!(
+            pool.pending_relays("main", true),
+            vec!["wss://b.example".to_string()]
+        );
+        assert_eq!(pool.summary("main").desired, 1);
+    }
+
+    #[test]
+    fn publish_buffer_set_on_a_url_variant_holds_publishes() {
+        let pool = RelayPool::new();
+        assert_eq!(
+            pool.set_publish_buffer("main", " WSS://Relay.Example/ ", 1),
+            Ok(1)
+        );
+        // `publish_event` looks the relay up by its normalized URL
+        let url = normalize_relay_url("wss://relay.example/").unwrap();
+        let frame = serde_json::json!(["EVENT", { "id": "a" }]).to_string();
+        assert_eq!(
+            pool.send_or_buffer_publish("main", &url, frame.clone()),
+            Ok("Queued".to_string())
+        );
+        assert_eq!(
+            pool.send_or_buffer_publish("main", &url, frame),
+            Err("Not connected (publish buffer full)".to_string())
+        );
+        assert!(pool
+            .set_publish_buffer("main", "https://relay.example", 1)
+            .is_err());
+    }
+
+    #[test]
+    fn paused_reconnects_hold_back_only_automatic_connects() {
+        let pool = RelayPool::new();
+        assert!(!pool.holds_back(ConnectTrigger::Automatic));
+        pool.reconnects_paused.store(true, Ordering::SeqCst);
+        assert!(pool.holds_back(ConnectTrigger::Automatic));
+        assert!(!pool.holds_back(ConnectTrigger::Explicit));
+    }
+
+    #[test]
+    fn destroyed_windows_are_forgotten() {
+        let pool = RelayPool::new();
+        pool.mark_wanted("profile-1", "wss://a.example");
+        pool.mark_wanted("main", "wss://a.example");
+        pool.states
+            .lock()
+            .unwrap()
+            .entry(("profile-1".to_string(), "wss://a.example".to_string()))
+            .or_default();
+
+        assert!(pool.forget_window("profile-1").is_empty());
+        assert!(pool.pending_relays("profile-1", false).is_empty());
+        assert!(pool.states.lock().unwrap().is_empty());
+        assert_eq!(
+            pool.pending_relays("main", false),
+            vec!["wss://a.example".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn pages_run_under_their_own_subscription() {
+        let pool = RelayPool::new();
+        let url = "wss://relay.example";
+        let (tx, mut rx) = mpsc::channel(8);
+        pool.connections.lock().unwrap().insert(
+            ("main".to_string(), url.to_string()),
+            RelayConnection {
+                tx,
+                metrics: Arc::new(RelayConnectionMetrics::new()),
+                auth: Arc::default(),
+                reconnect_count: 0,
+            },
+        );
+        let filters = paged_filters(&[serde_json::json!({ "kinds": [1] })], 100, 10);
+        let deadline = Instant::now() + Duration::from_secs(5);
+
+        let relay = async {
+            let Some(Message::Text(req)) = rx.recv().await else {
+                panic!("expected a REQ");
+            };
+            let req: Value = serde_json::from_str(req.as_str()).unwrap();
+            let sub_id = req[1].as_str().unwrap().to_string();
+            assert_ne!(sub_id, "feed");
+            assert_eq!(req[2]["until"], 99);
+            for frame in [
+                serde_json::json!(["EVENT", sub_id, { "id": "a", "created_at": 42 }]),
+                serde_json::json!(["EOSE", sub_id]),
+            ] {
+                collector_for_frame(&pool.sub_collectors, "main", url, &frame)
+                    .unwrap()
+                    .send(frame)
+                    .unwrap();
+            }
+            let Some(Message::Text(close)) = rx.recv().await else {
+                panic!("expected a CLOSE");
+            };
+            assert_eq!(
+                close.as_str(),
+                serde_json::json!(["CLOSE", sub_id]).to_string()
+            );
+        };
+        let (events, ()) = tokio::join!(
+            collect_relay_events(&pool, "main", url, &filters, deadline),
+            relay
+        );
+        assert_eq!(events.unwrap().len(), 1);
+        assert!(pool.sub_collectors.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn drain_flushes_queue_and_oks_before_closing() {
+        let url = "wss://relay.example";
+        let key = ("main".to_string(), url.to_string(), "e1".to_string());
+        let pending_acks = Arc::new(Mutex::new(HashMap::new()));
+        let (ack_tx, ack_rx) = oneshot::channel();
+        pending_acks
+            .lock()
+            .unwrap()
+            .insert(key.clone(), PendingRelayAck { sender: ack_tx });
+        let (tx, mut rx) = mpsc::channel(8);
+        tx.send(Message::Text(r#"["EVENT",{"id":"e1"}]"#.into()))
+            .await
+            .unwrap();
+
+        let relay = async {
+            assert!(matches!(rx.recv().await, Some(Message::Text(_))));
+            sleep(Duration::from_millis(DISCONNECT_DRAIN_POLL_MS * 2)).await;
+            let entry = pending_acks.lock().unwrap().remove(&key).unwrap();
+            let _ = entry.sender.send(RelayPublishAck {
+                ok: true,
+                message: None,
+            });
+            rx.recv().await
+        };
+        let (drained, last) = tokio::join!(
+            close_released_relay(
+                &pending_acks,
+                &tx,
+                "main",
+                url,
+                Some(Duration::from_secs(5))
+            ),
+            relay
+        );
+        assert!(drained);
+        assert!(matches!(last, Some(Message::Close(None))));
+        assert!(ack_rx.await.unwrap().ok);
+    }
+
+    #[tokio::test]
+    async fn drain_deadline_fails_pending_oks_and_closes() {
+        let url = "wss://relay.example";
+        let pending_acks = Arc::new(Mutex::new(HashMap::new()));
+        let (ack_tx, ack_rx) = oneshot::channel();
+        pending_acks.lock().unwrap().insert(
+            ("main".to_string(), url.to_string(), "e1".to_string()),
+            PendingRelayAck { sender: ack_tx },
+        );
+        let (tx, mut rx) = mpsc::channel(8);
+        let limit = Duration::from_millis(100);
+
+        let started = Instant::now();
+        let drained = close_released_relay(&pending_acks, &tx, "main", url, Some(limit)).await;
+        assert!(!drained);
+        assert!(started.elapsed() >= limit);
+        let ack = ack_rx.await.unwrap();
+        assert!(!ack.ok);
+        assert_eq!(
+            ack.message.as_deref(),
+            Some("Relay disconnected before OK response")
+        );
+        assert!(pending_acks.lock().unwrap().is_empty());
+        assert!(matches!(rx.recv().await, Some(Message::Close(None))));
+    }
+
+    #[test]
+    fn paused_subscriptions_close_and_replay() {
+        let pool = RelayPool::new();
+        let key = ("main".to_string(), "wss://relay.example".to_string());
+        let (tx, mut rx) = mpsc::channel(8);
+        pool.connections.lock().unwrap().insert(
+            key.clone(),
+            RelayConnection {
+                tx,
+                metrics: Arc::new(RelayConnectionMetrics::new()),
+                auth: Arc::default(),
+                reconnect_count: 0,
+            },
+        );
+        pool.states
+            .lock()
+            .unwrap()
+            .entry(key)
+            .or_default()
+            .subscriptions
+            .insert(
+                "feed".to_string(),
+                vec![serde_json::json!({ "kinds": [1] })],
+            );
+        let mut next_frame = || match rx.try_recv() {
+            Ok(Message::Text(text)) => text.as_str().to_string(),
+            other => panic!("expected a text frame, got {:?}", other),
+        };
+
+        assert_eq!(
+            pool.pause_window_subscriptions("main", SubscriptionPause::Hidden),
+            1
+        );
+        assert_eq!(next_frame(), r#"["CLOSE","feed"]"#);
+        // Made manual while hidden: showing the window must not resume it
+        assert_eq!(
+            pool.pause_window_subscriptions("main", SubscriptionPause::Manual),
+            0
+        );
+        assert_eq!(pool.resume_window_subscriptions("main", true), 0);
+        assert_eq!(pool.resume_window_subscriptions("main", false), 1);
+        assert_eq!(next_frame(), r#"["REQ","feed",{"kinds":[1]}]"#);
     }
 }
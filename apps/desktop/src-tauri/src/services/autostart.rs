@@ -0,0 +1,98 @@
+//! Launch-at-login helper functions
+
+#[cfg(desktop)]
+use crate::models::autostart::*;
+#[cfg(desktop)]
+use crate::services::settings::{load_json_setting, write_json_setting};
+#[cfg(desktop)]
+use tauri::AppHandle;
+#[cfg(desktop)]
+use tauri_plugin_autostart::ManagerExt;
+
+/// Load the launch-at-login preference (disabled by default)
+#[cfg(desktop)]
+pub fn load_autostart_settings(app: &AppHandle) -> AutostartSettings {
+    load_json_setting(app, AUTOSTART_SETTINGS_FILE).unwrap_or_default()
+}
+
+/// Persist the launch-at-login preference next to the Tor settings
+#[cfg(desktop)]
+pub fn write_autostart_settings(
+    app: &AppHandle,
+    settings: &AutostartSettings,
+) -> Result<(), String> {
+    write_json_setting(app, AUTOSTART_SETTINGS_FILE, settings)
+}
+
+/// Whether this process was started by the login item
+#[cfg(desktop)]
+pub fn launched_by_autostart() -> bool {
+    std::env::args().any(|arg| arg == AUTOSTART_LAUNCH_ARG)
+}
+
+/// `Exec=` line for Linux packages whose executable path is not stable:
+/// Flatpak must go through `flatpak run`, and an AppImage mounts at a new path on every launch.
+#[cfg(target_os = "linux")]
+fn sandboxed_exec_line() -> Option<String> {
+    if let Ok(flatpak_id) = std::env::var("FLATPAK_ID") {
+        return Some(format!(
+            "flatpak run {} {}",
+            flatpak_id, AUTOSTART_LAUNCH_ARG
+        ));
+    }
+    if let Ok(appimage) = std::env::var("APPIMAGE") {
+        return Some(format!("\"{}\" {}", appimage, AUTOSTART_LAUNCH_ARG));
+    }
+    None
+}
+
+/// XDG autostart entry on the host. Under Flatpak `XDG_CONFIG_HOME` points into the sandbox,
+/// so the host path is built from `HOME`; the manifest needs `--filesystem=xdg-config/autostart:create`.
+#[cfg(target_os = "linux")]
+fn xdg_autostart_entry_path(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+    let home = std::env::var("HOME").map_err(|_| "HOME is not set".to_string())?;
+    Ok(std::path::PathBuf::from(home)
+        .join(".config")
+        .join("autostart")
+        .join(format!("{}.desktop", app.config().identifier)))
+}
+
+#[cfg(desktop)]
+pub fn set_autostart_enabled(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    if let Some(exec_line) = sandboxed_exec_line() {
+        let path = xdg_autostart_entry_path(app)?;
+        if !enabled {
+            return match std::fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+                _ => Ok(()),
+            };
+        }
+        let name = app.package_info().name.clone();
+        let entry = format!(
+            "[Desktop Entry]\nType=Application\nName={}\nExec={}\nX-GNOME-Autostart-enabled=true\n",
+            name, exec_line
+        );
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        return std::fs::write(&path, entry).map_err(|e| e.to_string());
+    }
+
+    let autolaunch = app.autolaunch();
+    if enabled {
+        autolaunch.enable().map_err(|e| e.to_string())
+    } else {
+        autolaunch.disable().map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(desktop)]
+pub fn is_autostart_enabled(app: &AppHandle) -> Result<bool, String> {
+    #[cfg(target_os = "linux")]
+    if sandboxed_exec_line().is_some() {
+        return Ok(xdg_autostart_entry_path(app)?.exists());
+    }
+
+    app.autolaunch().is_enabled().map_err(|e| e.to_string())
+}
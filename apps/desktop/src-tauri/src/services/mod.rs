@@ -1,3 +1,10 @@
 //! Service modules for desktop functionality
 
+pub mod autostart;
+pub mod relay_list;
+pub mod settings;
+pub mod shortcut;
+pub mod sign_auth;
+pub mod theme;
 pub mod tray;
+pub mod window;
@@ -0,0 +1,28 @@
+//! Relay list persistence helpers
+
+use crate::models::relay_list::*;
+use crate::services::settings::{load_json_setting, write_json_setting};
+use tauri::AppHandle;
+
+/// Load the saved relay list (empty when missing or unreadable)
+pub fn load_saved_relays(app: &AppHandle) -> Vec<SavedRelay> {
+    load_json_setting(app, RELAYS_FILE).unwrap_or_default()
+}
+
+/// Persist the relay list
+pub fn write_saved_relays(app: &AppHandle, relays: &[SavedRelay]) -> Result<(), String> {
+    write_json_setting(app, RELAYS_FILE, relays)
+}
+
+/// Load the subscription preferences (defaults when missing or unreadable)
+pub fn load_subscription_settings(app: &AppHandle) -> SubscriptionSettings {
+    load_json_setting(app, SUBSCRIPTION_SETTINGS_FILE).unwrap_or_default()
+}
+
+/// Persist the subscription preferences
+pub fn write_subscription_settings(
+    app: &AppHandle,
+    settings: &SubscriptionSettings,
+) -> Result<(), String> {
+    write_json_setting(app, SUBSCRIPTION_SETTINGS_FILE, settings)
+}
@@ -0,0 +1,24 @@
+//! JSON settings files in the app data directory
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+/// Read `file_name` from the app data directory; `None` when it is missing or unreadable
+pub fn load_json_setting<T: DeserializeOwned>(app: &AppHandle, file_name: &str) -> Option<T> {
+    let app_dir = app.path().app_data_dir().ok()?;
+    let json = std::fs::read_to_string(app_dir.join(file_name)).ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+/// Write `value` to `file_name` in the app data directory, next to the Tor settings
+pub fn write_json_setting<T: Serialize + ?Sized>(
+    app: &AppHandle,
+    file_name: &str,
+    value: &T,
+) -> Result<(), String> {
+    let app_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&app_dir).map_err(|e| e.to_string())?;
+    let json = serde_json::to_string(value).map_err(|e| e.to_string())?;
+    std::fs::write(app_dir.join(file_name), json).map_err(|e| e.to_string())
+}
@@ -0,0 +1,91 @@
+//! Global shortcut helper functions
+
+#[cfg(desktop)]
+use crate::models::shortcut::*;
+#[cfg(desktop)]
+use crate::models::window::MAIN_WINDOW_LABEL;
+#[cfg(desktop)]
+use crate::services::settings::{load_json_setting, write_json_setting};
+#[cfg(desktop)]
+use tauri::{AppHandle, Manager};
+#[cfg(desktop)]
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+/// Load shortcut settings, falling back to the default toggle shortcut
+#[cfg(desktop)]
+pub fn load_shortcut_settings(app: &AppHandle) -> ShortcutSettings {
+    let default = ShortcutSettings {
+        toggle_window: DEFAULT_TOGGLE_WINDOW_SHORTCUT.to_string(),
+    };
+    load_json_setting(app, SHORTCUT_SETTINGS_FILE).unwrap_or(default)
+}
+
+/// Persist shortcut settings next to the Tor settings
+#[cfg(desktop)]
+pub fn write_shortcut_settings(app: &AppHandle, settings: &ShortcutSettings) -> Result<(), String> {
+    write_json_setting(app, SHORTCUT_SETTINGS_FILE, settings)
+}
+
+/// Hide the main window if it is visible and focused, otherwise bring it to the front
+#[cfg(desktop)]
+pub fn toggle_main_window(app: &AppHandle) {
+    let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) else {
+        return;
+    };
+    let visible = window.is_visible().unwrap_or(false);
+    let minimized = window.is_minimized().unwrap_or(false);
+    let focused = window.is_focused().unwrap_or(false);
+    if visible && !minimized && focused {
+        let _ = window.hide();
+        crate::relay::on_window_visibility_changed(app, window.label(), false);
+    } else {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// Register `accelerator` as the show/hide shortcut, replacing the previous one.
+/// On failure the previous shortcut stays registered.
+#[cfg(desktop)]
+pub fn register_toggle_window_shortcut(app: &AppHandle, accelerator: &str) -> Result<(), String> {
+    let shortcut: Shortcut = accelerator
+        .parse()
+        .map_err(|e| format!("Invalid shortcut '{}': {}", accelerator, e))?;
+    let state = app.state::<GlobalShortcutState>();
+    let mut current = state.toggle_window.lock().unwrap();
+    let previous = current.as_deref().and_then(|s| s.parse::<Shortcut>().ok());
+    if previous == Some(shortcut) {
+        *current = Some(accelerator.to_string());
+        return Ok(());
+    }
+
+    let global_shortcut = app.global_shortcut();
+    if let Some(previous) = previous {
+        let _ = global_shortcut.unregister(previous);
+    }
+    let result = global_shortcut.on_shortcut(shortcut, |app, _shortcut, event| {
+        if event.state() == ShortcutState::Pressed {
+            toggle_main_window(app);
+        }
+    });
+    match result {
+        Ok(()) => {
+            *current = Some(accelerator.to_string());
+            Ok(())
+        }
+        Err(e) => {
+            if let Some(previous) = previous {
+                let _ = global_shortcut.on_shortcut(previous, |app, _shortcut, event| {
+                    if event.state() == ShortcutState::Pressed {
+                        toggle_main_window(app);
+                    }
+                });
+            }
+            Err(format!(
+                "Failed to register shortcut '{}': {}",
+                accelerator, e
+            ))
+        }
+    }
+}
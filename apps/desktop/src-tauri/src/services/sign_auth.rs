@@ -0,0 +1,110 @@
+//! Sign-authorization helper functions
+
+use crate::models::sign_auth::*;
+use crate::services::settings::{load_json_setting, write_json_setting};
+use std::time::Instant;
+use tauri::AppHandle;
+
+/// Load the sign-authorization preference (disabled by default)
+pub fn load_sign_auth_settings(app: &AppHandle) -> SignAuthSettings {
+    load_json_setting(app, SIGN_AUTH_SETTINGS_FILE).unwrap_or_default()
+}
+
+/// Persist the sign-authorization preference
+pub fn write_sign_auth_settings(
+    app: &AppHandle,
+    settings: &SignAuthSettings,
+) -> Result<(), String> {
+    write_json_setting(app, SIGN_AUTH_SETTINGS_FILE, settings)
+}
+
+/// Load the timestamp privacy mode (exact timestamps by default)
+pub fn load_timestamp_privacy(app: &AppHandle) -> TimestampPrivacy {
+    load_json_setting(app, TIMESTAMP_PRIVACY_FILE).unwrap_or_default()
+}
+
+/// Persist the timestamp privacy mode
+pub fn write_timestamp_privacy(app: &AppHandle, mode: TimestampPrivacy) -> Result<(), String> {
+    write_json_setting(app, TIMESTAMP_PRIVACY_FILE, &mode)
+}
+
+/// The `created_at` to sign a `kind` event with under the configured timestamp privacy
+/// mode; kinds exempt per `timestamp_privacy_applies` keep the requested time
+pub async fn private_created_at(
+    state: &SignAuthState,
+    kind: u16,
+    created_at: u64,
+) -> Result<u64, String> {
+    if !timestamp_privacy_applies(kind) {
+        return Ok(created_at);
+    }
+    let mode = *state.timestamp_privacy.lock().await;
+    let mut random = [0u8; 8];
+    if matches!(mode, TimestampPrivacy::Jitter { .. }) {
+        getrandom::getrandom(&mut random).map_err(|e| e.to_string())?;
+    }
+    Ok(mode.apply(created_at, u64::from_le_bytes(random)))
+}
+
+/// Prompt for OS authentication before signing when the setting is on.
+/// A success is cached for the grace period so a burst of signs prompts once.
+pub async fn authorize_signing(state: &SignAuthState) -> Result<(), String> {
+    let settings = state.settings.lock().await.clone();
+    if !settings.require_auth_to_sign {
+        return Ok(());
+    }
+    prompt_unless_recently_authorized(
+        state,
+        settings.grace_period_secs,
+        "Verify your identity to sign with Obscur.",
+    )
+    .await
+}
+
+/// Whether reading the session secret from the keychain needs an OS prompt first
+pub async fn keychain_unlock_requires_auth(state: &SignAuthState) -> bool {
+    state.settings.lock().await.require_biometric
+}
+
+/// Prompt for OS authentication before the session secret is read from the keychain.
+/// Fails closed: a declined or unavailable prompt leaves the keychain untouched.
+pub async fn authorize_keychain_unlock(state: &SignAuthState) -> Result<(), String> {
+    let settings = state.settings.lock().await.clone();
+    if !settings.require_biometric {
+        return Ok(());
+    }
+    prompt_unless_recently_authorized(
+        state,
+        settings.grace_period_secs,
+        crate::platform_biometric::DEFAULT_BIOMETRIC_REASON,
+    )
+    .await
+}
+
+async fn prompt_unless_recently_authorized(
+    state: &SignAuthState,
+    grace_period_secs: u64,
+    reason: &'static str,
+) -> Result<(), String> {
+    // Held across the prompt so concurrent callers wait for one prompt instead of stacking several
+    let mut last_authorized = state.last_authorized.lock().await;
+    if within_grace_period(*last_authorized, grace_period_secs, Instant::now()) {
+        return Ok(());
+    }
+
+    let verified = tauri::async_runtime::spawn_blocking(move || {
+        crate::platform_biometric::request_biometric_verification(reason)
+    })
+    .await
+    .map_err(|error| format!("{AUTH_REQUIRED_CODE}: authentication task failed: {error}"))?
+    .map_err(|error| format!("{AUTH_REQUIRED_CODE}: {error}"))?;
+
+    if !verified {
+        *last_authorized = None;
+        return Err(format!(
+            "{AUTH_REQUIRED_CODE}: authentication was cancelled"
+        ));
+    }
+    *last_authorized = Some(Instant::now());
+    Ok(())
+}
@@ -0,0 +1,54 @@
+//! System theme helper functions
+
+#[cfg(desktop)]
+use crate::models::theme::*;
+#[cfg(desktop)]
+use tauri::{AppHandle, Emitter, Manager, Theme, WebviewWindow};
+
+/// String contract shared with `get_system_theme`
+#[cfg(desktop)]
+pub fn theme_label(theme: Theme) -> &'static str {
+    match theme {
+        Theme::Dark => "dark",
+        _ => "light",
+    }
+}
+
+/// Emit `system-theme-changed` unless the frontend already has this theme
+#[cfg(desktop)]
+pub fn emit_system_theme_if_changed(app: &AppHandle, theme: Theme) {
+    let label = theme_label(theme);
+    let state = app.state::<SystemThemeState>();
+    let mut last = state.last.lock().unwrap();
+    if *last == Some(label) {
+        return;
+    }
+    let first_report = last.is_none();
+    *last = Some(label);
+    drop(last);
+    if !first_report {
+        let _ = app.emit(SYSTEM_THEME_CHANGED_EVENT, label);
+    }
+}
+
+/// Seed the current theme and poll it as a fallback for desktops (e.g. some Linux
+/// environments) that never deliver `WindowEvent::ThemeChanged`.
+#[cfg(desktop)]
+pub fn watch_system_theme(app: &AppHandle, window: &WebviewWindow) {
+    if let Ok(theme) = window.theme() {
+        emit_system_theme_if_changed(app, theme);
+    }
+    let app = app.clone();
+    let window = window.clone();
+    tauri::async_runtime::spawn(async move {
+        let interval = std::time::Duration::from_secs(SYSTEM_THEME_POLL_INTERVAL_SECS);
+        loop {
+            tokio::time::sleep(interval).await;
+            match window.theme() {
+                Ok(theme) => emit_system_theme_if_changed(&app, theme),
+                // The window is gone; nothing left to follow.
+                Err(_) => break,
+            }
+        }
+    });
+}
@@ -320,6 +320,18 @@ pub fn draw_glyph(
     }
 }
 
+/// Render a standalone badge bubble for the Windows taskbar overlay slot
+#[cfg(target_os = "windows")]
+pub fn render_badge_overlay_icon(badge_label: &str) -> tauri::image::Image<'static> {
+    const OVERLAY_ICON_SIZE: u32 = 32;
+    let transparent = tauri::image::Image::new_owned(
+        vec![0; (OVERLAY_ICON_SIZE * OVERLAY_ICON_SIZE * 4) as usize],
+        OVERLAY_ICON_SIZE,
+        OVERLAY_ICON_SIZE,
+    );
+    render_badged_tray_icon(&transparent, badge_label)
+}
+
 /// Render a badged tray icon with unread count
 #[cfg(desktop)]
 pub fn render_badged_tray_icon(
@@ -0,0 +1,71 @@
+//! Window close behavior and always-on-top helper functions
+
+#[cfg(desktop)]
+use crate::commands::tor::stop_tor_child;
+#[cfg(desktop)]
+use crate::models::tor::TorState;
+#[cfg(desktop)]
+use crate::models::window::*;
+#[cfg(desktop)]
+use crate::services::settings::{load_json_setting, write_json_setting};
+#[cfg(desktop)]
+use tauri::{AppHandle, Manager};
+
+/// Load the close behavior preference (hide to tray by default)
+#[cfg(desktop)]
+pub fn load_close_behavior_settings(app: &AppHandle) -> CloseBehaviorSettings {
+    load_json_setting(app, CLOSE_BEHAVIOR_SETTINGS_FILE).unwrap_or_default()
+}
+
+/// Persist the close behavior preference next to the Tor settings
+#[cfg(desktop)]
+pub fn write_close_behavior_settings(
+    app: &AppHandle,
+    settings: &CloseBehaviorSettings,
+) -> Result<(), String> {
+    write_json_setting(app, CLOSE_BEHAVIOR_SETTINGS_FILE, settings)
+}
+
+/// Load the always-on-top preference (off by default)
+#[cfg(desktop)]
+pub fn load_always_on_top_settings(app: &AppHandle) -> AlwaysOnTopSettings {
+    load_json_setting(app, ALWAYS_ON_TOP_SETTINGS_FILE).unwrap_or_default()
+}
+
+/// Persist the always-on-top preference
+#[cfg(desktop)]
+pub fn write_always_on_top_settings(
+    app: &AppHandle,
+    settings: &AlwaysOnTopSettings,
+) -> Result<(), String> {
+    write_json_setting(app, ALWAYS_ON_TOP_SETTINGS_FILE, settings)
+}
+
+/// The stored always-on-top preference, false before setup has loaded it
+#[cfg(desktop)]
+pub fn always_on_top_preference(app: &AppHandle) -> bool {
+    app.try_state::<AlwaysOnTopState>()
+        .map(|state| *state.enabled.lock().unwrap())
+        .unwrap_or(false)
+}
+
+/// What closing `label` does, or `None` for secondary windows, which are always destroyed
+#[cfg(desktop)]
+pub fn close_behavior_for_window(app: &AppHandle, label: &str) -> Option<CloseBehavior> {
+    if label != MAIN_WINDOW_LABEL {
+        return None;
+    }
+    let behavior = app
+        .try_state::<CloseBehaviorState>()
+        .map(|state| *state.behavior.lock().unwrap())
+        .unwrap_or_default();
+    Some(behavior)
+}
+
+/// Stop the Tor sidecar and exit, as the tray "Quit" item does
+#[cfg(desktop)]
+pub fn quit_app(app: &AppHandle) {
+    let state = app.state::<TorState>();
+    let _ = stop_tor_child(&state);
+    app.exit(0);
+}
@@ -1,5 +1,7 @@
 use nostr::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 use std::{collections::HashMap, sync::Arc};
 use tokio::sync::Mutex;
 
@@ -7,15 +9,35 @@ use tokio::sync::Mutex;
 /// This replaces the OS keychain dependency for active operations.
 pub struct SessionState {
     pub keys: Arc<Mutex<HashMap<String, Keys>>>,
+    /// Last time each profile's keys were set, used or touched
+    last_active: Arc<Mutex<HashMap<String, Instant>>>,
+    /// Idle time after which hydrated keys are dropped; 0 keeps them until cleared
+    idle_ttl_secs: AtomicU64,
+}
+
+/// Whether keys last active at `last_active` have outlived an idle TTL of `ttl_secs` at `now`
+pub fn session_idle_expired(last_active: Option<Instant>, ttl_secs: u64, now: Instant) -> bool {
+    match last_active {
+        Some(at) if ttl_secs > 0 => {
+            now.saturating_duration_since(at) >= Duration::from_secs(ttl_secs)
+        }
+        _ => false,
+    }
 }
 
 impl SessionState {
     pub fn new() -> Self {
         Self {
             keys: Arc::new(Mutex::new(HashMap::new())),
+            last_active: Arc::new(Mutex::new(HashMap::new())),
+            idle_ttl_secs: AtomicU64::new(0),
         }
     }
 
+    pub fn set_idle_ttl_secs(&self, ttl_secs: u64) {
+        self.idle_ttl_secs.store(ttl_secs, Ordering::Relaxed);
+    }
+
     /// Set the active session keys from an nsec or hex string
     pub async fn set_keys(&self, profile_id: &str, key_str: &str) -> Result<PublicKey, String> {
         let keys = if key_str.starts_with("nsec") {
@@ -30,23 +52,51 @@ impl SessionState {
         let pubkey = keys.public_key();
         let mut session_keys = self.keys.lock().await;
         session_keys.insert(profile_id.to_string(), keys);
+        self.last_active
+            .lock()
+            .await
+            .insert(profile_id.to_string(), Instant::now());
         Ok(pubkey)
     }
 
     /// Clear the active session
     pub async fn clear(&self, profile_id: Option<&str>) {
         let mut session_keys = self.keys.lock().await;
+        let mut last_active = self.last_active.lock().await;
         if let Some(profile_id) = profile_id {
             session_keys.remove(profile_id);
+            last_active.remove(profile_id);
         } else {
             session_keys.clear();
+            last_active.clear();
         }
     }
 
-    /// Get a clone of the keys if available
+    /// Get a clone of the keys if available and not idle past the TTL.
+    /// Use counts as activity.
     pub async fn get_keys(&self, profile_id: &str) -> Option<Keys> {
-        let session_keys = self.keys.lock().await;
-        session_keys.get(profile_id).cloned()
+        let mut session_keys = self.keys.lock().await;
+        let mut last_active = self.last_active.lock().await;
+        let now = Instant::now();
+        let ttl_secs = self.idle_ttl_secs.load(Ordering::Relaxed);
+        if session_idle_expired(last_active.get(profile_id).copied(), ttl_secs, now) {
+            session_keys.remove(profile_id);
+            last_active.remove(profile_id);
+            log::info!(
+                "[SESSION] Native session for profile {} expired after {}s idle",
+                profile_id,
+                ttl_secs
+            );
+            return None;
+        }
+        let keys = session_keys.get(profile_id).cloned()?;
+        last_active.insert(profile_id.to_string(), now);
+        Some(keys)
+    }
+
+    /// Keep the profile's hydrated keys alive; false when there is no live session
+    pub async fn touch(&self, profile_id: &str) -> bool {
+        self.get_keys(profile_id).await.is_some()
     }
 }
 
@@ -65,3 +115,29 @@ pub struct SessionResponse {
     pub npub: Option<String>,
     pub message: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn idle_ttl_expires_only_when_set() {
+        let now = Instant::now();
+        assert!(!session_idle_expired(
+            Some(now),
+            0,
+            now + Duration::from_secs(86_400)
+        ));
+        assert!(!session_idle_expired(None, 60, now));
+        assert!(!session_idle_expired(
+            Some(now),
+            60,
+            now + Duration::from_secs(59)
+        ));
+        assert!(session_idle_expired(
+            Some(now),
+            60,
+            now + Duration::from_secs(60)
+        ));
+    }
+}
@@ -1,15 +1,54 @@
 //! Repo-hosted desktop update channel (stable feed on `main`).
 
+use nostr::secp256k1::schnorr::Signature;
+use nostr::secp256k1::Message;
+use sha2::{Digest, Sha256};
 use tauri::{AppHandle, Url};
-use tauri_plugin_updater::UpdaterExt;
+use tauri_plugin_updater::{Update, UpdaterExt};
 
 pub const DEFAULT_STABLE_FEED_URL: &str =
     "https://raw.githubusercontent.com/Dendro-X0/Obscur/main/apps/desktop/release/channel/stable/latest.json";
 
+/// Field next to each platform's `signature` in the feed holding our own artifact signature
+const PINNED_SIGNATURE_FIELD: &str = "obscurSignature";
+
 pub fn resolve_stable_feed_url() -> &'static str {
     option_env!("OBSCUR_STABLE_UPDATE_FEED_URL").unwrap_or(DEFAULT_STABLE_FEED_URL)
 }
 
+/// Release key pinned at build time (hex or npub). When set, an update is only installed if
+/// its artifact carries a valid signature from this key, on top of the updater's minisign check.
+pub fn pinned_update_pubkey() -> Option<&'static str> {
+    option_env!("OBSCUR_UPDATE_PINNED_PUBKEY")
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+}
+
+/// The pinned-key signature the feed announces for this update's target, if any
+pub fn pinned_artifact_signature(update: &Update) -> Option<String> {
+    update.raw_json["platforms"][&update.target][PINNED_SIGNATURE_FIELD]
+        .as_str()
+        .map(|signature| signature.trim().to_string())
+}
+
+/// Check a BIP-340 Schnorr `signature` (hex) by `pubkey` over the SHA-256 of `artifact`.
+pub fn verify_artifact_signature(
+    artifact: &[u8],
+    signature: &str,
+    pubkey: &str,
+) -> Result<(), String> {
+    let pubkey = nostr::PublicKey::parse(pubkey.trim())
+        .map_err(|error| format!("Invalid pinned update key: {error}"))?;
+    let signature = signature
+        .trim()
+        .parse::<Signature>()
+        .map_err(|error| format!("Invalid update signature: {error}"))?;
+    let digest: [u8; 32] = Sha256::digest(artifact).into();
+    nostr::SECP256K1
+        .verify_schnorr(&signature, &Message::from_digest(digest), &pubkey)
+        .map_err(|_| "Update signature does not match the pinned release key".to_string())
+}
+
 pub fn build_updater(app: &AppHandle) -> Result<tauri_plugin_updater::Updater, String> {
     let feed_url = resolve_stable_feed_url()
         .parse::<Url>()
@@ -20,3 +59,23 @@ pub fn build_updater(app: &AppHandle) -> Result<tauri_plugin_updater::Updater, S
         .build()
         .map_err(|error| format!("Failed to build updater: {error}"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn artifact_signature_is_checked_against_the_pinned_key() {
+        let keys = nostr::Keys::generate();
+        let artifact = b"obscur-update-artifact";
+        let digest: [u8; 32] = Sha256::digest(artifact).into();
+        let signature = keys.sign_schnorr(&Message::from_digest(digest)).to_string();
+        let pubkey = keys.public_key().to_hex();
+
+        assert!(verify_artifact_signature(artifact, &signature, &pubkey).is_ok());
+        assert!(verify_artifact_signature(b"tampered", &signature, &pubkey).is_err());
+        let other = nostr::Keys::generate().public_key().to_hex();
+        assert!(verify_artifact_signature(artifact, &signature, &other).is_err());
+        assert!(verify_artifact_signature(artifact, "zz", &pubkey).is_err());
+    }
+}
@@ -4,24 +4,55 @@
 //! direct reqwest multipart requests, bypassing all WebView/JavaScript
 //! complexity.
 
-use crate::net::NativeNetworkRuntime;
+use crate::image_meta::{image_metadata, is_supported_image_type, ImageMetadata};
+use crate::net::{
+    compute_backoff_delay, compute_backoff_delay_from, NativeNetworkRuntime, UPLOAD_MAX_RETRIES_CAP,
+};
 use crate::session::SessionState;
 use nostr::prelude::*;
 use serde::{Deserialize, Serialize};
-use tauri::{command, State, WebviewWindow};
+use tauri::{command, Emitter, Manager, State, WebviewWindow};
 // #[cfg(not(target_os = "android"))]
 // use keyring::Entry;
 // use zeroize::Zeroizing;
-use std::time::Duration;
 #[cfg(not(target_os = "android"))]
 use base64::Engine;
-#[cfg(not(target_os = "android"))]
-use nostr::hashes::{sha256, Hash};
+use nostr::hashes::{sha256, Hash, HashEngine};
 #[cfg(not(target_os = "android"))]
 use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::io::Read;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tokio::sync::watch;
 
 const BUILD_VERSION: &str = "2026-03-30-OPTION-C-V4-UPLOAD-TIMEOUT-HARDEN";
 const REQUEST_TIMEOUT_SECS: u64 = 120;
+const STREAMING_REQUEST_TIMEOUT_SECS: u64 = 1_800;
+const HASH_CHUNK_BYTES: usize = 64 * 1024;
+const UPLOAD_FIELD_NAMES: [&str; 3] = ["file", "files[]", "files"];
+const NIP98_DEFAULT_EXPIRATION_SECS: u64 = 120;
+const NIP98_MAX_EXPIRATION_SECS: u64 = 3_600;
+const HTTP_METHODS: [&str; 9] = [
+    "GET", "HEAD", "POST", "PUT", "DELETE", "PATCH", "OPTIONS", "CONNECT", "TRACE",
+];
+
+/// Multipart field name each NIP-96 endpoint last accepted, so streamed uploads
+/// (whose body cannot be replayed cheaply) start with the known-good shape.
+static UPLOAD_FIELD_NAME_CACHE: LazyLock<Mutex<HashMap<String, &'static str>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Cancel switches for in-flight uploads, keyed by the caller-supplied upload id.
+static UPLOAD_CANCEL_SENDERS: LazyLock<Mutex<HashMap<String, watch::Sender<bool>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+const UPLOAD_CANCELLED_CODE: &str = "UPLOAD_CANCELLED";
+const UNSUPPORTED_CONTENT_TYPE_CODE: &str = "UNSUPPORTED_CONTENT_TYPE";
+
+/// Failed uploads kept for the diagnostics report, oldest first
+static RECENT_UPLOAD_ERRORS: LazyLock<Mutex<VecDeque<UploadErrorRecord>>> =
+    LazyLock::new(|| Mutex::new(VecDeque::new()));
+const RECENT_UPLOAD_ERROR_CAPACITY: usize = 20;
 // const APP_SERVICE: &str = "app.obscur.desktop";
 // const KEY_NAME: &str = "nsec";
 
@@ -32,6 +63,27 @@ pub struct UploadResponse {
     pub url: Option<String>,                    // Uploaded file URL
     pub message: Option<String>,                // Error message if any
     pub nip94_event: Option<serde_json::Value>, // Raw NIP-94 event
+    /// `<width>x<height>` for images, for the NIP-94 `dim` tag
+    pub dim: Option<String>,
+    /// Blurhash for images we can decode (PNG)
+    pub blurhash: Option<String>,
+}
+
+impl UploadResponse {
+    fn with_image_metadata(mut self, metadata: Option<ImageMetadata>) -> Self {
+        if let Some(metadata) = metadata {
+            self.dim = Some(metadata.dim);
+            self.blurhash = metadata.blurhash;
+        }
+        self
+    }
+
+    fn with_description(mut self, description: &MediaDescription) -> Self {
+        if let Some(event) = self.nip94_event.as_mut() {
+            description.apply_to_nip94_event(event);
+        }
+        self
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -68,44 +120,50 @@ impl From<std::io::Error> for NativeError {
     }
 }
 
-/// Generate NIP-98 authorization header
+/// Generate NIP-98 authorization header for an arbitrary method.
+/// The `payload` tag is only attached when a request body hash is provided.
 #[cfg(not(target_os = "android"))]
-async fn generate_nip98_auth(api_url: &str, file_bytes: &[u8], keys: &Keys) -> Option<String> {
-    // // let entry = Entry::new(APP_SERVICE, KEY_NAME).ok()?;
-    // // let nsec = entry.get_password().ok()?;
-    // // let nsec_zero = Zeroizing::new(nsec);
-    // // let keys = Keys::parse(nsec_zero.as_str()).ok()?;
-
-    // Compute SHA-256 of file bytes
-    let hash = sha256::Hash::hash(file_bytes);
-    let payload_hash = hash.to_string();
-
+async fn generate_nip98_auth_for_request(
+    request_url: &str,
+    method: &str,
+    payload_hash: Option<&str>,
+    expiration_secs: u64,
+    keys: &Keys,
+) -> Option<String> {
     let now = Timestamp::now();
-    let expiration = now.as_u64() + 120; // 2 minute expiration
+    let expiration = now.as_u64() + expiration_secs;
+
+    log::debug!("[NIP96-V2] Building auth event:");
+    log::debug!("  URL: {}", request_url);
+    log::debug!("  Method: {}", method);
 
-    eprintln!("[NIP96-V2] Building auth event:");
-    eprintln!("  URL: {}", api_url);
-    eprintln!("  Payload hash: {}", &payload_hash[..16]);
+    let mut tags = vec![
+        Tag::custom(
+            TagKind::Custom(Cow::Borrowed("u")),
+            vec![request_url.to_string()],
+        ),
+        Tag::custom(
+            TagKind::Custom(Cow::Borrowed("method")),
+            vec![method.to_uppercase()],
+        ),
+    ];
+    if let Some(payload_hash) = payload_hash {
+        log::debug!(
+            "  Payload hash: {}",
+            &payload_hash[..16.min(payload_hash.len())]
+        );
+        tags.push(Tag::custom(
+            TagKind::Custom(Cow::Borrowed("payload")),
+            vec![payload_hash.to_string()],
+        ));
+    }
+    tags.push(Tag::custom(
+        TagKind::Custom(Cow::Borrowed("expiration")),
+        vec![expiration.to_string()],
+    ));
 
     let unsigned_event = EventBuilder::new(Kind::from(27235), "")
-        .tags(vec![
-            Tag::custom(
-                TagKind::Custom(Cow::Borrowed("u")),
-                vec![api_url.to_string()],
-            ),
-            Tag::custom(
-                TagKind::Custom(Cow::Borrowed("method")),
-                vec!["POST".to_string()],
-            ),
-            Tag::custom(
-                TagKind::Custom(Cow::Borrowed("payload")),
-                vec![payload_hash],
-            ),
-            Tag::custom(
-                TagKind::Custom(Cow::Borrowed("expiration")),
-                vec![expiration.to_string()],
-            ),
-        ])
+        .tags(tags)
         .custom_created_at(now)
         .build(keys.public_key());
 
@@ -117,10 +175,65 @@ async fn generate_nip98_auth(api_url: &str, file_bytes: &[u8], keys: &Keys) -> O
 }
 
 #[cfg(target_os = "android")]
-async fn generate_nip98_auth(_: &str, _: &[u8], _: &Keys) -> Option<String> {
+async fn generate_nip98_auth_for_request(
+    _: &str,
+    _: &str,
+    _: Option<&str>,
+    _: u64,
+    _: &Keys,
+) -> Option<String> {
     None // Android uses different auth mechanism (placeholder)
 }
 
+/// SHA-256 a file in fixed-size chunks so large media never sits in memory.
+async fn sha256_file_hex(path: std::path::PathBuf) -> Result<(String, u64), NativeError> {
+    tauri::async_runtime::spawn_blocking(move || -> Result<(String, u64), NativeError> {
+        let mut file = std::fs::File::open(&path)?;
+        let mut engine = sha256::Hash::engine();
+        let mut buffer = vec![0u8; HASH_CHUNK_BYTES];
+        let mut total: u64 = 0;
+        loop {
+            let read = file.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            engine.input(&buffer[..read]);
+            total += read as u64;
+        }
+        Ok((sha256::Hash::from_engine(engine).to_string(), total))
+    })
+    .await
+    .map_err(|e| NativeError {
+        code: "IO_ERROR".to_string(),
+        message: format!("Hash task failed: {}", e),
+    })?
+}
+
+fn remember_upload_field_name(api_url: &str, field_name: &'static str) {
+    if let Ok(mut cache) = UPLOAD_FIELD_NAME_CACHE.lock() {
+        cache.insert(api_url.to_string(), field_name);
+    }
+}
+
+/// Field names to try for `api_url`, the last accepted one first.
+fn upload_field_names_for(api_url: &str) -> Vec<&'static str> {
+    let known = UPLOAD_FIELD_NAME_CACHE
+        .lock()
+        .ok()
+        .and_then(|cache| cache.get(api_url).copied());
+    let mut names = Vec::with_capacity(UPLOAD_FIELD_NAMES.len());
+    if let Some(known) = known {
+        names.push(known);
+    }
+    names.extend(
+        UPLOAD_FIELD_NAMES
+            .iter()
+            .copied()
+            .filter(|name| Some(*name) != known),
+    );
+    names
+}
+
 /// Extract URL from NIP-96 response
 fn normalize_upload_url(url: &str) -> String {
     let trimmed = url.trim().trim_end_matches([')', ']', ',', '.', ';']);
@@ -184,78 +297,668 @@ fn extract_url_from_response(json: &serde_json::Value) -> Option<String> {
     None
 }
 
+/// Run the sign-authorization gate before a NIP-98 signature, as `AUTH_REQUIRED`
+async fn authorize_upload_signing(
+    sign_auth: &crate::models::sign_auth::SignAuthState,
+) -> Result<(), NativeError> {
+    crate::services::sign_auth::authorize_signing(sign_auth)
+        .await
+        .map_err(|message| NativeError {
+            code: crate::models::sign_auth::AUTH_REQUIRED_CODE.to_string(),
+            message,
+        })
+}
+
+/// Resolve the active session keys for the profile bound to `window`, behind the
+/// sign-authorization gate since every caller signs NIP-98 with them.
+async fn session_keys_for_window(
+    app: &tauri::AppHandle,
+    window: &WebviewWindow,
+) -> Result<Keys, NativeError> {
+    let profiles = app.state::<crate::profiles::DesktopProfileState>();
+    let profile_id = crate::profiles::resolve_profile_for_window(app, &profiles, window)
+        .await
+        .map_err(|message| NativeError {
+            code: "PROFILE_ERROR".to_string(),
+            message,
+        })?;
+    let keys = app
+        .state::<SessionState>()
+        .get_keys(&profile_id)
+        .await
+        .ok_or_else(|| NativeError {
+            code: "NO_SESSION".to_string(),
+            message: "Native session is not initialized. Please unlock the app.".to_string(),
+        })?;
+    authorize_upload_signing(&app.state::<crate::models::sign_auth::SignAuthState>()).await?;
+    Ok(keys)
+}
+
+/// Longest accessibility text accepted for an upload, in characters
+const MEDIA_ALT_MAX_CHARS: usize = 1_500;
+const MEDIA_CAPTION_MAX_CHARS: usize = 4_000;
+
+/// Optional NIP-96 `alt` and `caption` form fields sent with an upload
+#[derive(Debug, Clone, Default, PartialEq)]
+struct MediaDescription {
+    alt: Option<String>,
+    caption: Option<String>,
+}
+
+impl MediaDescription {
+    /// Trim both fields, dropping empty ones and refusing text over the length limits
+    fn new(alt: Option<String>, caption: Option<String>) -> Result<Self, NativeError> {
+        let field = |name: &str, value: Option<String>, max_chars: usize| {
+            let Some(value) = value
+                .map(|value| value.trim().to_string())
+                .filter(|v| !v.is_empty())
+            else {
+                return Ok(None);
+            };
+            if value.chars().count() > max_chars {
+                return Err(NativeError {
+                    code: "INVALID_DESCRIPTION".to_string(),
+                    message: format!("{name} is longer than {max_chars} characters"),
+                });
+            }
+            Ok(Some(value))
+        };
+        Ok(Self {
+            alt: field("alt", alt, MEDIA_ALT_MAX_CHARS)?,
+            caption: field("caption", caption, MEDIA_CAPTION_MAX_CHARS)?,
+        })
+    }
+
+    fn add_to_form(&self, mut form: reqwest::multipart::Form) -> reqwest::multipart::Form {
+        if let Some(caption) = &self.caption {
+            form = form.text("caption", caption.clone());
+        }
+        if let Some(alt) = &self.alt {
+            form = form.text("alt", alt.clone());
+        }
+        form
+    }
+
+    /// Mirror the description into a server's NIP-94 event where it was left out:
+    /// an `alt` tag, and the caption as the event content.
+    fn apply_to_nip94_event(&self, event: &mut serde_json::Value) {
+        let Some(event) = event.as_object_mut() else {
+            return;
+        };
+        if let Some(alt) = &self.alt {
+            let tags = event.entry("tags").or_insert_with(|| serde_json::json!([]));
+            if let Some(tags) = tags.as_array_mut() {
+                let has_alt = tags
+                    .iter()
+                    .any(|tag| tag.get(0).and_then(|name| name.as_str()) == Some("alt"));
+                if !has_alt {
+                    tags.push(serde_json::json!(["alt", alt]));
+                }
+            }
+        }
+        if let Some(caption) = &self.caption {
+            let content = event
+                .entry("content")
+                .or_insert_with(|| serde_json::json!(""));
+            if content.as_str().is_none_or(str::is_empty) {
+                *content = serde_json::json!(caption);
+            }
+        }
+    }
+}
+
+/// In-memory file sent as the multipart file part
+struct UploadFileBytes<'a> {
+    bytes: &'a [u8],
+    name: &'a str,
+    content_type: &'a str,
+}
+
+/// Redirects an upload follows. Method and body are resent unchanged on every one of
+/// them: a NIP-96 POST turned into a bodiless GET could only fail on the next hop.
+fn is_followed_redirect(status: reqwest::StatusCode) -> bool {
+    matches!(status.as_u16(), 301 | 302 | 303 | 307 | 308)
+}
+
+/// Resolve the `Location` of an upload redirect against `current`. The NIP-98 header is
+/// re-signed for each hop, so a hop may not leave the host of `origin` or drop https.
+fn upload_redirect_target(
+    origin: &url::Url,
+    current: &url::Url,
+    location: Option<&reqwest::header::HeaderValue>,
+) -> Result<url::Url, String> {
+    let location = location
+        .and_then(|value| value.to_str().ok())
+        .ok_or("redirect without a usable Location header")?;
+    let next = current
+        .join(location)
+        .map_err(|e| format!("invalid redirect Location '{location}': {e}"))?;
+    if next.host_str() != origin.host_str() {
+        return Err(format!(
+            "refusing redirect to another host ({})",
+            next.host_str().unwrap_or_default()
+        ));
+    }
+    match (current.scheme(), next.scheme()) {
+        ("https", "https") | ("http", "http" | "https") => Ok(next),
+        _ => Err("refusing redirect from https to a non-https URL".to_string()),
+    }
+}
+
+/// HTTP client for one NIP-96 server. Redirects are followed by hand, up to the runtime's
+/// upload limit: reqwest can neither re-sign NIP-98 for the new URL nor resend a body
+/// it has already streamed.
+struct UploadClient<'a> {
+    http: reqwest::Client,
+    keys: &'a Keys,
+    max_redirects: usize,
+}
+
+impl<'a> UploadClient<'a> {
+    fn new(net_runtime: &NativeNetworkRuntime, keys: &'a Keys) -> Result<Self, NativeError> {
+        Ok(Self {
+            http: net_runtime.build_reqwest_client()?,
+            keys,
+            max_redirects: net_runtime.get_upload_max_redirects(),
+        })
+    }
+
+    /// Send a NIP-98 signed `method` request to `url`, following redirects. `attach_body`
+    /// builds the body (and per-request options) afresh for every hop.
+    async fn send<F, Fut>(
+        &self,
+        method: reqwest::Method,
+        url: &str,
+        payload_hash: Option<&str>,
+        mut attach_body: F,
+    ) -> Result<(reqwest::StatusCode, String), NativeError>
+    where
+        F: FnMut(reqwest::RequestBuilder) -> Fut,
+        Fut: Future<Output = Result<reqwest::RequestBuilder, NativeError>>,
+    {
+        let origin =
+            url::Url::parse(url).map_err(|e| invalid_input(&format!("Invalid URL: {e}")))?;
+        let mut target = origin.clone();
+        let mut signed_url = url.to_string();
+        let mut redirects = 0;
+        loop {
+            let auth_header = generate_nip98_auth_for_request(
+                &signed_url,
+                method.as_str(),
+                payload_hash,
+                NIP98_DEFAULT_EXPIRATION_SECS,
+                self.keys,
+            )
+            .await
+            .ok_or_else(|| NativeError {
+                code: "AUTH_ERROR".to_string(),
+                message: "Failed to generate NIP-98 authorization header.".to_string(),
+            })?;
+            let request = self
+                .http
+                .request(method.clone(), target.clone())
+                .header("Authorization", auth_header);
+            let response = attach_body(request).await?.send().await?;
+            let status = response.status();
+            if !is_followed_redirect(status) {
+                let text = response.text().await?;
+                return Ok((status, text));
+            }
+
+            let redirect_error = |message: String| NativeError {
+                code: "REDIRECT_ERROR".to_string(),
+                message,
+            };
+            if redirects == self.max_redirects {
+                return Err(redirect_error(format!(
+                    "too many redirects (max {})",
+                    self.max_redirects
+                )));
+            }
+            redirects += 1;
+            target = upload_redirect_target(
+                &origin,
+                &target,
+                response.headers().get(reqwest::header::LOCATION),
+            )
+            .map_err(redirect_error)?;
+            signed_url = target.to_string();
+            log::debug!("[NIP96] Following {} to {}", status, target);
+        }
+    }
+}
+
 /// Helper to send a single multipart request
 async fn send_multipart_request(
-    client: &reqwest::Client,
+    client: &UploadClient<'_>,
     api_url: &str,
     field_name: &str,
-    file_bytes: Vec<u8>,
-    file_name: String,
-    content_type: String,
-    auth_header: Option<String>,
+    file: &UploadFileBytes<'_>,
+    description: &MediaDescription,
+    payload_hash: &str,
 ) -> Result<(reqwest::StatusCode, String), NativeError> {
-    let file_part = reqwest::multipart::Part::bytes(file_bytes)
-        .file_name(file_name)
-        .mime_str(&content_type)
-        .map_err(|e| NativeError {
-            code: "MIME_ERROR".to_string(),
-            message: e.to_string(),
-        })?;
+    client
+        .send(
+            reqwest::Method::POST,
+            api_url,
+            Some(payload_hash),
+            |request| async move {
+                let file_part = reqwest::multipart::Part::bytes(file.bytes.to_vec())
+                    .file_name(file.name.to_string())
+                    .mime_str(file.content_type)
+                    .map_err(|e| NativeError {
+                        code: "MIME_ERROR".to_string(),
+                        message: e.to_string(),
+                    })?;
+                let form = reqwest::multipart::Form::new().part(field_name.to_string(), file_part);
+                let form = description.add_to_form(form);
+                let timeout = Duration::from_secs(REQUEST_TIMEOUT_SECS);
+                Ok::<_, NativeError>(request.multipart(form).timeout(timeout))
+            },
+        )
+        .await
+}
 
-    let form = reqwest::multipart::Form::new().part(field_name.to_string(), file_part);
+/// Outcome of a single multipart attempt against a NIP-96 endpoint
+enum UploadAttemptOutcome {
+    Done(UploadResponse),
+    /// Server parsed the request but rejected the multipart shape; try the next field name.
+    RetryNextField(String),
+    /// Gateway hiccup (502/503/504); the same request may succeed after a backoff.
+    Transient(String),
+    Failed(String),
+}
+
+fn is_transient_status(status: reqwest::StatusCode) -> bool {
+    matches!(status.as_u16(), 502..=504)
+}
 
-    let mut request = client.post(api_url).multipart(form);
-    if let Some(auth) = auth_header {
-        request = request.header("Authorization", auth);
+/// Timeouts, refused/reset connections and broken transfers are worth retrying;
+/// local errors (I/O, MIME) are not.
+fn is_transient_error(err: &NativeError) -> bool {
+    err.code.starts_with("NETWORK_")
+}
+
+/// Cancellation handle for one upload; unregisters itself on drop.
+struct UploadCancellation {
+    upload_id: Option<String>,
+    receiver: Option<watch::Receiver<bool>>,
+}
+
+impl UploadCancellation {
+    fn register(upload_id: Option<String>) -> Self {
+        let receiver = upload_id.as_ref().map(|id| {
+            let (sender, receiver) = watch::channel(false);
+            UPLOAD_CANCEL_SENDERS
+                .lock()
+                .unwrap()
+                .insert(id.clone(), sender);
+            receiver
+        });
+        Self {
+            upload_id,
+            receiver,
+        }
+    }
+
+    /// Resolves once the upload is cancelled; never resolves for uploads without an id.
+    async fn cancelled(&mut self) {
+        match self.receiver.as_mut() {
+            Some(receiver) => {
+                if receiver.wait_for(|cancelled| *cancelled).await.is_err() {
+                    std::future::pending::<()>().await;
+                }
+            }
+            None => std::future::pending::<()>().await,
+        }
     }
-    request = request.timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS));
 
-    let response = request.send().await?;
-    let status = response.status();
-    let text = response.text().await?;
+    /// Handle sharing this upload's cancel switch without owning its registration.
+    fn subscriber(&self) -> Self {
+        Self {
+            upload_id: None,
+            receiver: self.receiver.clone(),
+        }
+    }
 
-    Ok((status, text))
+    fn is_cancelled(&self) -> bool {
+        self.receiver
+            .as_ref()
+            .is_some_and(|receiver| *receiver.borrow())
+    }
 }
 
-async fn send_upload_request(
-    client: &reqwest::Client,
-    api_url: &str,
-    field_name: &str,
-    file_bytes: &[u8],
+impl Drop for UploadCancellation {
+    fn drop(&mut self) {
+        if let Some(id) = &self.upload_id {
+            UPLOAD_CANCEL_SENDERS.lock().unwrap().remove(id);
+        }
+    }
+}
+
+fn upload_cancelled_error() -> NativeError {
+    NativeError {
+        code: UPLOAD_CANCELLED_CODE.to_string(),
+        message: "Upload cancelled".to_string(),
+    }
+}
+
+/// Bounds accepted for a per-upload retry base delay
+const MIN_RETRY_BASE_DELAY_MS: u64 = 100;
+const MAX_RETRY_BASE_DELAY_MS: u64 = 10_000;
+
+/// How many times a transient upload failure is retried, and the first backoff delay
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct UploadRetryPolicy {
+    max_retries: u32,
+    /// First backoff delay; None uses the shared network backoff
+    base_delay_ms: Option<u64>,
+}
+
+impl UploadRetryPolicy {
+    /// The runtime-wide retry limit with the default backoff
+    fn from_runtime(net_runtime: &NativeNetworkRuntime) -> Self {
+        Self {
+            max_retries: net_runtime.get_upload_max_retries(),
+            base_delay_ms: None,
+        }
+    }
+
+    /// Apply per-call overrides, clamped like the runtime settings
+    fn with_overrides(self, max_retries: Option<u32>, base_delay_ms: Option<u64>) -> Self {
+        Self {
+            max_retries: max_retries
+                .map(|retries| retries.min(UPLOAD_MAX_RETRIES_CAP))
+                .unwrap_or(self.max_retries),
+            base_delay_ms: base_delay_ms
+                .map(|ms| ms.clamp(MIN_RETRY_BASE_DELAY_MS, MAX_RETRY_BASE_DELAY_MS))
+                .or(self.base_delay_ms),
+        }
+    }
+}
+
+/// Run one multipart attempt, retrying transient failures with exponential backoff.
+/// Fatal responses (4xx auth, bad payload) are returned immediately so a bad key is not hammered.
+async fn send_with_transient_retries<F, Fut>(
+    retry_policy: UploadRetryPolicy,
+    cancellation: &mut UploadCancellation,
+    mut send_attempt: F,
+) -> Result<UploadAttemptOutcome, NativeError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<(reqwest::StatusCode, String), NativeError>>,
+{
+    let mut retry: u32 = 0;
+    loop {
+        if cancellation.is_cancelled() {
+            return Err(upload_cancelled_error());
+        }
+        let result = tokio::select! {
+            result = send_attempt() => result,
+            _ = cancellation.cancelled() => return Err(upload_cancelled_error()),
+        };
+        let transient_reason = match result {
+            Ok((status, body)) => match interpret_upload_response(status, &body) {
+                UploadAttemptOutcome::Transient(error) => error,
+                outcome => return Ok(outcome),
+            },
+            Err(e) if is_transient_error(&e) => format!("{}: {}", e.code, e.message),
+            Err(e) => return Err(e),
+        };
+        if retry >= retry_policy.max_retries {
+            return Ok(UploadAttemptOutcome::Transient(transient_reason));
+        }
+        let delay = match retry_policy.base_delay_ms {
+            Some(base_ms) => compute_backoff_delay_from(base_ms, retry),
+            None => compute_backoff_delay(retry),
+        };
+        retry += 1;
+        log::warn!(
+            "[NIP96] Transient failure ({}), retry {}/{} in {}ms",
+            transient_reason,
+            retry,
+            retry_policy.max_retries,
+            delay.as_millis()
+        );
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = cancellation.cancelled() => return Err(upload_cancelled_error()),
+        }
+    }
+}
+
+fn interpret_upload_response(status: reqwest::StatusCode, body: &str) -> UploadAttemptOutcome {
+    log::debug!("[NIP96-V2] Status: {}", status);
+    if !status.is_success() {
+        let error = format!("HTTP {}: {}", status, body);
+        log::warn!("[NIP96-V2] HTTP Error: {}", error);
+        if is_transient_status(status) {
+            return UploadAttemptOutcome::Transient(error);
+        }
+        // Specific check for 400 "No files" to trigger retry
+        if status.as_u16() == 400 && body.to_lowercase().contains("no files") {
+            log::info!("[NIP96-V2] 'No files' error detected, retrying with next field name...");
+            return UploadAttemptOutcome::RetryNextField(error);
+        }
+        return UploadAttemptOutcome::Failed(error);
+    }
+
+    // Parse response
+    let json = match serde_json::from_str::<serde_json::Value>(body) {
+        Ok(json) => json,
+        Err(e) => {
+            log::warn!("[NIP96-V2] Failed to parse JSON: {}", e);
+            return UploadAttemptOutcome::Failed(format!("JSON Parse Error: {}", e));
+        }
+    };
+    if json.get("status").and_then(|s| s.as_str()) == Some("error") {
+        let msg = json
+            .get("message")
+            .and_then(|m| m.as_str())
+            .unwrap_or("Unknown API error");
+        log::warn!("[NIP96-V2] API returned error: {}", msg);
+        let error = format!("API Error: {}", msg);
+        // If "no files provided" from API, we might continue loop, but usually API error is specific
+        if msg.to_lowercase().contains("no files") {
+            return UploadAttemptOutcome::RetryNextField(error);
+        }
+        return UploadAttemptOutcome::Failed(error);
+    }
+
+    let url = extract_url_from_response(&json);
+    let nip94 = json.get("nip94_event").cloned();
+    if let Some(u) = &url {
+        log::info!("[NIP96-V2] ✓ Upload successful: {}", u);
+        UploadAttemptOutcome::Done(UploadResponse {
+            status: "success".to_string(),
+            url: Some(u.clone()),
+            message: None,
+            nip94_event: nip94,
+            dim: None,
+            blurhash: None,
+        })
+    } else {
+        log::warn!("[NIP96-V2] ⚠ Upload completed but no URL in response");
+        UploadAttemptOutcome::Done(UploadResponse {
+            status: "success".to_string(), // Still success protocol-wise
+            url: None,
+            message: Some("No URL in response".to_string()),
+            nip94_event: nip94,
+            dim: None,
+            blurhash: None,
+        })
+    }
+}
+
+/// An upload that failed after every attempt, as shown in the diagnostics report
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadErrorRecord {
+    pub at: u64, // unix ms
+    /// Scheme and host of the media server; paths and query strings may carry tokens
+    pub server: String,
+    pub error: String,
+}
+
+fn record_upload_error(api_url: &str, error: &str) {
+    let server = url::Url::parse(api_url)
+        .map(|url| url.origin().ascii_serialization())
+        .unwrap_or_else(|_| "[invalid url]".to_string());
+    let record = UploadErrorRecord {
+        at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0),
+        server,
+        error: crate::logging::redact_secrets(error),
+    };
+    if let Ok(mut errors) = RECENT_UPLOAD_ERRORS.lock() {
+        if errors.len() >= RECENT_UPLOAD_ERROR_CAPACITY {
+            errors.pop_front();
+        }
+        errors.push_back(record);
+    }
+}
+
+/// Uploads that failed recently, oldest first
+pub fn recent_upload_errors() -> Vec<UploadErrorRecord> {
+    RECENT_UPLOAD_ERRORS
+        .lock()
+        .map(|errors| errors.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+fn all_attempts_failed(api_url: &str, last_error: String) -> UploadResponse {
+    record_upload_error(api_url, &last_error);
+    UploadResponse {
+        status: "error".to_string(),
+        url: None,
+        message: Some(format!("All attempts failed. Last error: {}", last_error)),
+        nip94_event: None,
+        dim: None,
+        blurhash: None,
+    }
+}
+
+/// MIME type from the file's magic bytes; `None` when the signature is not recognized.
+fn sniff_content_type(bytes: &[u8]) -> Option<&'static str> {
+    let riff_kind = bytes.get(8..12).filter(|_| bytes.starts_with(b"RIFF"));
+    let ftyp_brand = bytes
+        .get(8..12)
+        .filter(|_| bytes.get(4..8) == Some(b"ftyp"));
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if riff_kind == Some(b"WEBP") {
+        Some("image/webp")
+    } else if riff_kind == Some(b"WAVE") {
+        Some("audio/wav")
+    } else if let Some(brand) = ftyp_brand {
+        Some(match brand {
+            b"avif" | b"avis" => "image/avif",
+            b"heic" | b"heix" | b"heim" | b"heis" => "image/heic",
+            b"mif1" | b"msf1" => "image/heif",
+            b"qt  " => "video/quicktime",
+            b"M4A " => "audio/mp4",
+            _ => "video/mp4",
+        })
+    } else if bytes.starts_with(&[0x1A, 0x45, 0xDF, 0xA3]) {
+        Some("video/webm")
+    } else if bytes.starts_with(b"OggS") {
+        Some("audio/ogg")
+    } else if bytes.starts_with(b"ID3") || bytes.starts_with(&[0xFF, 0xFB]) {
+        Some("audio/mpeg")
+    } else if bytes.starts_with(b"%PDF-") {
+        Some("application/pdf")
+    } else {
+        None
+    }
+}
+
+/// Whether `mime` matches an allowlist entry: an exact type, `type/*`, or `*/*`.
+fn content_type_allowed(mime: &str, allowed: &[String]) -> bool {
+    let mime = mime
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    let top_level = mime.split('/').next().unwrap_or_default();
+    allowed.iter().any(|entry| {
+        let entry = entry.trim().to_ascii_lowercase();
+        match entry.strip_suffix("/*") {
+            Some("*") => true,
+            Some(prefix) => prefix == top_level,
+            None => entry == mime,
+        }
+    })
+}
+
+/// Reject a file whose sniffed (or, failing that, declared) type is outside `allowed_types`
+/// before anything is sent. An empty or missing allowlist accepts every type.
+fn check_allowed_content_type(
     file_name: &str,
+    file_bytes: &[u8],
     content_type: &str,
-    auth_header: Option<String>,
-) -> Result<(reqwest::StatusCode, String), NativeError> {
-    send_multipart_request(
-        client,
-        api_url,
-        field_name,
-        file_bytes.to_vec(),
-        file_name.to_string(),
-        content_type.to_string(),
-        auth_header,
-    )
-    .await
+    allowed_types: Option<&[String]>,
+) -> Result<(), NativeError> {
+    let Some(allowed) = allowed_types.filter(|allowed| !allowed.is_empty()) else {
+        return Ok(());
+    };
+    let mime = sniff_content_type(file_bytes).unwrap_or(content_type);
+    if content_type_allowed(mime, allowed) {
+        return Ok(());
+    }
+    Err(NativeError {
+        code: UNSUPPORTED_CONTENT_TYPE_CODE.to_string(),
+        message: format!(
+            "{file_name} is {mime}, which this server does not accept (allowed: {})",
+            allowed.join(", ")
+        ),
+    })
 }
 
-/// Main upload command - receives bytes directly from frontend
+/// Optional settings for one `nip96_upload_v2` call
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct UploadOptions {
+    /// Lets `nip96_cancel_upload` cancel this upload
+    pub upload_id: Option<String>,
+    pub max_retries: Option<u32>,
+    pub retry_base_delay_ms: Option<u64>,
+    /// e.g. the server's advertised `content_types`
+    pub allowed_types: Option<Vec<String>>,
+    pub alt: Option<String>,
+    pub caption: Option<String>,
+}
+
+/// Main upload command - receives bytes directly from frontend.
+/// `options.allowed_types` is checked against the sniffed MIME type before any network call.
 #[command]
 pub async fn nip96_upload_v2(
     app: tauri::AppHandle,
     window: WebviewWindow,
-    net_runtime: State<'_, NativeNetworkRuntime>,
-    session: State<'_, SessionState>,
-    profiles: State<'_, crate::profiles::DesktopProfileState>,
     api_url: String,
     file_bytes: Vec<u8>,
     file_name: String,
     content_type: String,
+    options: Option<UploadOptions>,
 ) -> Result<UploadResponse, NativeError> {
-    eprintln!("╔════════════════════════════════════════════════════════════╗");
-    eprintln!("║ NIP-96 UPLOAD V2 (Pure Rust) - {} ║", BUILD_VERSION);
-    eprintln!("╚════════════════════════════════════════════════════════════╝");
-    eprintln!("[NIP96-V2] URL: {}", api_url);
-    eprintln!(
+    let net_runtime = app.state::<NativeNetworkRuntime>();
+    let UploadOptions {
+        upload_id,
+        max_retries,
+        retry_base_delay_ms,
+        allowed_types,
+        alt,
+        caption,
+    } = options.unwrap_or_default();
+    log::debug!("[NIP96-V2] NIP-96 upload v2 ({})", BUILD_VERSION);
+    log::debug!("[NIP96-V2] URL: {}", api_url);
+    log::debug!(
         "[NIP96-V2] File: {} ({} bytes)",
         file_name,
         file_bytes.len()
@@ -267,137 +970,375 @@ pub async fn nip96_upload_v2(
             url: None,
             message: Some("Empty file bytes".to_string()),
             nip94_event: None,
+            dim: None,
+            blurhash: None,
         });
     }
+    check_allowed_content_type(
+        &file_name,
+        &file_bytes,
+        &content_type,
+        allowed_types.as_deref(),
+    )?;
+    let description = MediaDescription::new(alt, caption)?;
 
     // Get keys from session
-    let profile_id = crate::profiles::resolve_profile_for_window(&app, &profiles, &window)
-        .await
-        .map_err(|message| NativeError {
-            code: "PROFILE_ERROR".to_string(),
-            message,
-        })?;
-    let keys = session.get_keys(&profile_id).await.ok_or_else(|| NativeError {
-        code: "NO_SESSION".to_string(),
-        message: "Native session is not initialized. Please unlock the app.".to_string(),
-    })?;
-
-    // Generate NIP-98 authorization
-    let auth_header = generate_nip98_auth(&api_url, &file_bytes, &keys).await;
-    if auth_header.is_some() {
-        eprintln!("[NIP96-V2] NIP-98 auth generated successfully");
-    } else {
-        return Err(NativeError {
-            code: "AUTH_ERROR".to_string(),
-            message: "Failed to generate NIP-98 authorization header.".to_string(),
-        });
-    }
+    let keys = session_keys_for_window(&app, &window).await?;
+
+    // Payload hash for the NIP-98 authorization signed on each request
+    let payload_hash = sha256::Hash::hash(&file_bytes).to_string();
 
     // Build HTTP client
-    let client = net_runtime.build_reqwest_client()?;
+    let client = UploadClient::new(&net_runtime, &keys)?;
+    let retry_policy = UploadRetryPolicy::from_runtime(&net_runtime)
+        .with_overrides(max_retries, retry_base_delay_ms);
+    let mut cancellation = UploadCancellation::register(upload_id);
 
     // Retry logic for field names: file -> files[] -> files
-    let field_names = vec!["file", "files[]", "files"];
     let mut last_error = String::from("No attempts made");
 
-    for field_name in field_names {
-        eprintln!(
+    for field_name in upload_field_names_for(&api_url) {
+        log::debug!(
             "[NIP96-V2] Attempting upload with field name: '{}'",
             field_name
         );
 
-        match send_upload_request(
-            &client,
-            &api_url,
-            field_name,
-            &file_bytes,
-            &file_name,
-            &content_type,
-            auth_header.clone(),
-        )
-        .await
-        {
-            Ok((status, body)) => {
-                eprintln!("[NIP96-V2] Status: {}", status);
-
-                if status.is_success() {
-                    eprintln!("[NIP96-V2] Request successful with '{}'", field_name);
-
-                    // Parse response
-                    let json_res: Result<serde_json::Value, _> = serde_json::from_str(&body);
-                    match json_res {
-                        Ok(json) => {
-                            if json.get("status").and_then(|s| s.as_str()) == Some("error") {
-                                let msg = json
-                                    .get("message")
-                                    .and_then(|m| m.as_str())
-                                    .unwrap_or("Unknown API error");
-                                last_error = format!("API Error: {}", msg);
-                                eprintln!("[NIP96-V2] API returned error: {}", msg);
-                                // If "no files provided" from API, we might continue loop, but usually API error is specific
-                                if msg.to_lowercase().contains("no files") {
-                                    continue;
-                                }
-                            } else {
-                                let url = extract_url_from_response(&json);
-                                let nip94 = json.get("nip94_event").cloned();
-
-                                if let Some(u) = &url {
-                                    eprintln!("[NIP96-V2] ✓ Upload successful: {}", u);
-                                    return Ok(UploadResponse {
-                                        status: "success".to_string(),
-                                        url: Some(u.clone()),
-                                        message: None,
-                                        nip94_event: nip94,
-                                    });
-                                } else {
-                                    eprintln!(
-                                        "[NIP96-V2] ⚠ Upload completed but no URL in response"
-                                    );
-                                    return Ok(UploadResponse {
-                                        status: "success".to_string(), // Still success protocol-wise
-                                        url: None,
-                                        message: Some("No URL in response".to_string()),
-                                        nip94_event: nip94,
-                                    });
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            last_error = format!("JSON Parse Error: {}", e);
-                            eprintln!("[NIP96-V2] Failed to parse JSON: {}", e);
-                        }
-                    }
+        let file = UploadFileBytes {
+            bytes: &file_bytes,
+            name: &file_name,
+            content_type: &content_type,
+        };
+        let outcome = send_with_transient_retries(retry_policy, &mut cancellation, || {
+            send_multipart_request(
+                &client,
+                &api_url,
+                field_name,
+                &file,
+                &description,
+                &payload_hash,
+            )
+        })
+        .await;
+        match outcome {
+            Ok(UploadAttemptOutcome::Done(response)) => {
+                log::debug!("[NIP96-V2] Request successful with '{}'", field_name);
+                remember_upload_field_name(&api_url, field_name);
+                let metadata = if is_supported_image_type(&content_type) {
+                    let (content_type, file_bytes) = (content_type.clone(), file_bytes.clone());
+                    tauri::async_runtime::spawn_blocking(move || {
+                        image_metadata(&content_type, &file_bytes)
+                    })
+                    .await
+                    .ok()
+                    .flatten()
                 } else {
-                    last_error = format!("HTTP {}: {}", status, body);
-                    eprintln!("[NIP96-V2] HTTP Error: {}", last_error);
-
-                    // Specific check for 400 "No files" to trigger retry
-                    if status.as_u16() == 400 && body.to_lowercase().contains("no files") {
-                        eprintln!("[NIP96-V2] 'No files' error detected, retrying with next field name...");
-                        continue;
-                    }
-                }
+                    None
+                };
+                return Ok(response
+                    .with_image_metadata(metadata)
+                    .with_description(&description));
+            }
+            Ok(UploadAttemptOutcome::RetryNextField(error)) => {
+                last_error = error;
+                continue;
+            }
+            Ok(UploadAttemptOutcome::Failed(error)) => {
+                last_error = error;
+            }
+            Ok(UploadAttemptOutcome::Transient(error)) => {
+                // Backoff retries are exhausted; other field names would fail the same way.
+                last_error = error;
+                break;
             }
+            Err(e) if e.code == UPLOAD_CANCELLED_CODE => return Err(e),
             Err(e) => {
                 last_error = format!("{}: {}", e.code, e.message);
-                eprintln!("[NIP96-V2] Network Error: {}", last_error);
-                // Field-name retries only help when the server parses multipart but rejects form shape.
-                // For transport-level failures (timeout/connect/request), retrying other field names is wasted time.
-                if e.code.starts_with("NETWORK_") {
-                    break;
-                }
+                log::warn!("[NIP96-V2] Network Error: {}", last_error);
             }
         }
     }
 
     // All attempts failed
-    Ok(UploadResponse {
-        status: "error".to_string(),
-        url: None,
-        message: Some(format!("All attempts failed. Last error: {}", last_error)),
-        nip94_event: None,
+    Ok(all_attempts_failed(&api_url, last_error))
+}
+
+/// File on disk described for a streamed multipart part
+struct StreamedUploadFile {
+    path: std::path::PathBuf,
+    len: u64,
+    name: String,
+    content_type: String,
+}
+
+/// Images up to this size are read whole for a blurhash; larger ones only get `dim`
+/// from a header prefix.
+const IMAGE_METADATA_MAX_BYTES: u64 = 32 * 1024 * 1024;
+const IMAGE_HEADER_PREFIX_BYTES: u64 = 256 * 1024;
+
+/// `dim`/blurhash for a streamed image, read back from disk after the upload succeeded.
+async fn streamed_image_metadata(file: &StreamedUploadFile) -> Option<ImageMetadata> {
+    if !is_supported_image_type(&file.content_type) {
+        return None;
+    }
+    let limit = if file.len <= IMAGE_METADATA_MAX_BYTES {
+        file.len
+    } else {
+        IMAGE_HEADER_PREFIX_BYTES
+    };
+    let (path, content_type) = (file.path.clone(), file.content_type.clone());
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut bytes = Vec::new();
+        std::fs::File::open(path)
+            .ok()?
+            .take(limit)
+            .read_to_end(&mut bytes)
+            .ok()?;
+        image_metadata(&content_type, &bytes)
     })
+    .await
+    .ok()
+    .flatten()
+}
+
+/// Send one multipart request whose file part is streamed from disk.
+async fn send_streaming_multipart_request(
+    client: &UploadClient<'_>,
+    api_url: &str,
+    field_name: &str,
+    file: &StreamedUploadFile,
+    payload_hash: &str,
+) -> Result<(reqwest::StatusCode, String), NativeError> {
+    client
+        .send(
+            reqwest::Method::POST,
+            api_url,
+            Some(payload_hash),
+            |request| async move {
+                let handle = tokio::fs::File::open(&file.path).await?;
+                let file_part = reqwest::multipart::Part::stream_with_length(
+                    reqwest::Body::from(handle),
+                    file.len,
+                )
+                .file_name(file.name.clone())
+                .mime_str(&file.content_type)
+                .map_err(|e| NativeError {
+                    code: "MIME_ERROR".to_string(),
+                    message: e.to_string(),
+                })?;
+                let form = reqwest::multipart::Form::new().part(field_name.to_string(), file_part);
+                let timeout = Duration::from_secs(STREAMING_REQUEST_TIMEOUT_SECS);
+                Ok::<_, NativeError>(request.multipart(form).timeout(timeout))
+            },
+        )
+        .await
+}
+
+/// One file to stream from disk, as described by the frontend
+#[derive(Debug, Clone, Deserialize)]
+pub struct UploadFileRequest {
+    pub file_path: String,
+    pub file_name: Option<String>,
+    pub content_type: Option<String>,
+}
+
+/// Hash, sign and stream one file from disk to `api_url`, trying each multipart field name.
+async fn upload_streamed_file(
+    client: &UploadClient<'_>,
+    api_url: &str,
+    request: UploadFileRequest,
+    retry_policy: UploadRetryPolicy,
+    cancellation: &mut UploadCancellation,
+) -> Result<UploadResponse, NativeError> {
+    let path = std::path::PathBuf::from(&request.file_path);
+    let file_name = request.file_name.unwrap_or_else(|| {
+        path.file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_else(|| "file".to_string())
+    });
+    let content_type = request
+        .content_type
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+
+    let (payload_hash, file_len) = sha256_file_hex(path.clone()).await?;
+    log::debug!("[NIP96-STREAM] URL: {}", api_url);
+    log::debug!("[NIP96-STREAM] File: {} ({} bytes)", file_name, file_len);
+    if file_len == 0 {
+        return Ok(UploadResponse {
+            status: "error".to_string(),
+            url: None,
+            message: Some("Empty file".to_string()),
+            nip94_event: None,
+            dim: None,
+            blurhash: None,
+        });
+    }
+
+    let file = StreamedUploadFile {
+        path,
+        len: file_len,
+        name: file_name,
+        content_type,
+    };
+
+    let mut last_error = String::from("No attempts made");
+
+    for field_name in upload_field_names_for(api_url) {
+        log::debug!(
+            "[NIP96-STREAM] Attempting upload with field name: '{}'",
+            field_name
+        );
+        // Each attempt (and redirect hop) re-opens the file, so the stream restarts from the
+        // beginning.
+        let outcome = send_with_transient_retries(retry_policy, cancellation, || {
+            send_streaming_multipart_request(client, api_url, field_name, &file, &payload_hash)
+        })
+        .await;
+        match outcome {
+            Ok(UploadAttemptOutcome::Done(response)) => {
+                remember_upload_field_name(api_url, field_name);
+                return Ok(response.with_image_metadata(streamed_image_metadata(&file).await));
+            }
+            Ok(UploadAttemptOutcome::RetryNextField(error)) => {
+                last_error = error;
+                continue;
+            }
+            Ok(UploadAttemptOutcome::Failed(error))
+            | Ok(UploadAttemptOutcome::Transient(error)) => {
+                last_error = error;
+                break;
+            }
+            Err(e) if e.code == UPLOAD_CANCELLED_CODE => return Err(e),
+            Err(e) => {
+                last_error = format!("{}: {}", e.code, e.message);
+                log::warn!("[NIP96-STREAM] Error: {}", last_error);
+                break;
+            }
+        }
+    }
+
+    Ok(all_attempts_failed(api_url, last_error))
+}
+
+/// Upload a file from disk without buffering it in memory.
+/// The payload hash is computed incrementally and the multipart body streams from the file;
+/// the field name last accepted by `api_url` is tried first so large bodies are not resent.
+#[command]
+pub async fn nip96_upload_file(
+    app: tauri::AppHandle,
+    window: WebviewWindow,
+    net_runtime: State<'_, NativeNetworkRuntime>,
+    api_url: String,
+    file: UploadFileRequest,
+    upload_id: Option<String>,
+) -> Result<UploadResponse, NativeError> {
+    let keys = session_keys_for_window(&app, &window).await?;
+    let client = UploadClient::new(&net_runtime, &keys)?;
+    let mut cancellation = UploadCancellation::register(upload_id);
+    upload_streamed_file(
+        &client,
+        &api_url,
+        file,
+        UploadRetryPolicy::from_runtime(&net_runtime),
+        &mut cancellation,
+    )
+    .await
+}
+
+const DEFAULT_BATCH_CONCURRENCY: usize = 3;
+const MAX_BATCH_CONCURRENCY: usize = 8;
+const UPLOAD_PROGRESS_EVENT: &str = "nip96-upload-progress";
+
+/// Per-file progress emitted on `nip96-upload-progress` while a batch runs
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadProgressEvent {
+    pub batch_id: String,
+    pub index: usize,
+    pub total: usize,
+    pub file_name: String,
+    pub status: String, // "uploading", "success" or "error"
+    pub url: Option<String>,
+    pub message: Option<String>,
+}
+
+/// Upload several files to one server in parallel (at most `concurrency` at a time).
+/// Results keep the input order; a failed file yields an error `UploadResponse` instead of
+/// aborting the batch. `batch_id` tags progress events and can be passed to `nip96_cancel_upload`.
+#[command]
+pub async fn nip96_upload_batch(
+    app: tauri::AppHandle,
+    window: WebviewWindow,
+    net_runtime: State<'_, NativeNetworkRuntime>,
+    api_url: String,
+    files: Vec<UploadFileRequest>,
+    concurrency: Option<usize>,
+    batch_id: Option<String>,
+) -> Result<Vec<UploadResponse>, NativeError> {
+    let batch_id = batch_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let keys = session_keys_for_window(&app, &window).await?;
+    let client = UploadClient::new(&net_runtime, &keys)?;
+    let retry_policy = UploadRetryPolicy::from_runtime(&net_runtime);
+    let cancellation = UploadCancellation::register(Some(batch_id.clone()));
+    let limit = concurrency
+        .unwrap_or(DEFAULT_BATCH_CONCURRENCY)
+        .clamp(1, MAX_BATCH_CONCURRENCY);
+    let semaphore = tokio::sync::Semaphore::new(limit);
+    let total = files.len();
+    log::info!(
+        "[NIP96-BATCH] {} file(s) to {} (batch {}, concurrency {})",
+        total,
+        api_url,
+        batch_id,
+        limit
+    );
+
+    let uploads = files.into_iter().enumerate().map(|(index, request)| {
+        let mut file_cancellation = cancellation.subscriber();
+        let (client, api_url, semaphore, window, batch_id) =
+            (&client, &api_url, &semaphore, &window, &batch_id);
+        async move {
+            let file_name = request.file_name.clone().unwrap_or_else(|| {
+                std::path::Path::new(&request.file_path)
+                    .file_name()
+                    .map(|f| f.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "file".to_string())
+            });
+            let progress = |status: &str, response: Option<&UploadResponse>| UploadProgressEvent {
+                batch_id: batch_id.clone(),
+                index,
+                total,
+                file_name: file_name.clone(),
+                status: status.to_string(),
+                url: response.and_then(|r| r.url.clone()),
+                message: response.and_then(|r| r.message.clone()),
+            };
+
+            let _permit = semaphore.acquire().await.ok();
+            let _ = window.emit(UPLOAD_PROGRESS_EVENT, progress("uploading", None));
+            let response = upload_streamed_file(
+                client,
+                api_url,
+                request,
+                retry_policy,
+                &mut file_cancellation,
+            )
+            .await
+            .unwrap_or_else(|e| UploadResponse {
+                status: "error".to_string(),
+                url: None,
+                message: Some(format!("{}: {}", e.code, e.message)),
+                nip94_event: None,
+                dim: None,
+                blurhash: None,
+            });
+            let _ = window.emit(
+                UPLOAD_PROGRESS_EVENT,
+                progress(&response.status, Some(&response)),
+            );
+            response
+        }
+    });
+
+    Ok(futures_util::future::join_all(uploads).await)
 }
 
 // Keep legacy command for backwards compatibility during transition
@@ -406,33 +1347,439 @@ pub async fn nip96_upload(
     app: tauri::AppHandle,
     window: WebviewWindow,
     net_runtime: State<'_, NativeNetworkRuntime>,
-    session: State<'_, SessionState>,
-    profiles: State<'_, crate::profiles::DesktopProfileState>,
     api_url: String,
     file_path: String,
     content_type: Option<String>,
     _authorization: Option<String>, // Renamed to suppress warning
 ) -> Result<UploadResponse, NativeError> {
-    eprintln!("[NIP96-LEGACY] Redirecting to V2...");
-
-    // Read file from path (legacy behavior)
-    let file_bytes = std::fs::read(&file_path)?;
-    let file_name = std::path::Path::new(&file_path)
-        .file_name()
-        .map(|f| f.to_string_lossy().to_string())
-        .unwrap_or_else(|| "file".to_string());
-    let content = content_type.unwrap_or_else(|| "application/octet-stream".to_string());
-
-    nip96_upload_v2(
-        app,
-        window,
-        net_runtime,
-        session,
-        profiles,
-        api_url,
-        file_bytes,
-        file_name,
-        content,
+    log::debug!("[NIP96-LEGACY] Redirecting to streaming upload...");
+
+    let file = UploadFileRequest {
+        file_path,
+        file_name: None,
+        content_type,
+    };
+    nip96_upload_file(app, window, net_runtime, api_url, file, None).await
+}
+
+/// Cancel an upload started with `upload_id`; returns false if it already finished.
+#[command]
+pub async fn nip96_cancel_upload(upload_id: String) -> Result<bool, String> {
+    let senders = UPLOAD_CANCEL_SENDERS.lock().unwrap();
+    Ok(senders
+        .get(&upload_id)
+        .is_some_and(|sender| sender.send(true).is_ok()))
+}
+
+/// Response returned by `nip96_delete`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteResponse {
+    pub status: String, // "success" or "error"
+    pub http_status: u16,
+    pub message: Option<String>,
+}
+
+fn is_sha256_hex(value: &str) -> bool {
+    value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Uppercase `method` and accept it only if it is a standard HTTP verb.
+fn normalize_http_method(method: &str) -> Option<String> {
+    let upper = method.trim().to_ascii_uppercase();
+    HTTP_METHODS.contains(&upper.as_str()).then_some(upper)
+}
+
+fn invalid_input(message: &str) -> NativeError {
+    NativeError {
+        code: "INVALID_INPUT".to_string(),
+        message: message.to_string(),
+    }
+}
+
+/// Sign a NIP-98 `Authorization` header (`Nostr <base64>`) for any HTTP request,
+/// e.g. paid relay admin endpoints. `expiration_secs` defaults to 120s, capped at an hour.
+#[command]
+pub async fn sign_nip98(
+    app: tauri::AppHandle,
+    window: WebviewWindow,
+    url: String,
+    method: String,
+    payload_hash: Option<String>,
+    expiration_secs: Option<u64>,
+) -> Result<String, NativeError> {
+    let parsed =
+        url::Url::parse(url.trim()).map_err(|e| invalid_input(&format!("Invalid URL: {e}")))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(invalid_input("NIP-98 only applies to http(s) URLs"));
+    }
+    let method = normalize_http_method(&method)
+        .ok_or_else(|| invalid_input(&format!("'{}' is not an HTTP method", method.trim())))?;
+    let payload_hash = payload_hash.map(|hash| hash.trim().to_lowercase());
+    if payload_hash
+        .as_deref()
+        .is_some_and(|hash| !is_sha256_hex(hash))
+    {
+        return Err(invalid_input(
+            "payload_hash must be a 64-character hex digest",
+        ));
+    }
+    let expiration_secs = expiration_secs
+        .unwrap_or(NIP98_DEFAULT_EXPIRATION_SECS)
+        .clamp(1, NIP98_MAX_EXPIRATION_SECS);
+
+    let keys = session_keys_for_window(&app, &window).await?;
+    generate_nip98_auth_for_request(
+        url.trim(),
+        &method,
+        payload_hash.as_deref(),
+        expiration_secs,
+        &keys,
     )
     .await
+    .ok_or_else(|| NativeError {
+        code: "AUTH_ERROR".to_string(),
+        message: "Failed to generate NIP-98 authorization header.".to_string(),
+    })
+}
+
+/// Build the NIP-96 `DELETE <api_url>/<sha256>` target URL.
+fn build_delete_url(api_url: &str, sha256: &str) -> String {
+    format!(
+        "{}/{}",
+        api_url.trim().trim_end_matches('/'),
+        sha256.to_lowercase()
+    )
+}
+
+/// Delete a previously uploaded file (NIP-96 `DELETE /<sha256>` with NIP-98 auth)
+#[command]
+pub async fn nip96_delete(
+    app: tauri::AppHandle,
+    window: WebviewWindow,
+    net_runtime: State<'_, NativeNetworkRuntime>,
+    api_url: String,
+    sha256: String,
+) -> Result<DeleteResponse, NativeError> {
+    let sha256 = sha256.trim().to_string();
+    if !is_sha256_hex(&sha256) {
+        return Err(NativeError {
+            code: "INVALID_INPUT".to_string(),
+            message: "sha256 must be a 64-character hex digest".to_string(),
+        });
+    }
+    let delete_url = build_delete_url(&api_url, &sha256);
+    log::debug!("[NIP96-DELETE] URL: {}", delete_url);
+
+    let keys = session_keys_for_window(&app, &window).await?;
+    let client = UploadClient::new(&net_runtime, &keys)?;
+    let (status, body) = client
+        .send(
+            reqwest::Method::DELETE,
+            &delete_url,
+            None,
+            |request| async move { Ok(request.timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))) },
+        )
+        .await?;
+    log::debug!("[NIP96-DELETE] Status: {}", status);
+
+    // NIP-96 servers answer with `{ status, message }`; fall back to the raw body otherwise.
+    let parsed = serde_json::from_str::<serde_json::Value>(&body).ok();
+    let api_error = parsed
+        .as_ref()
+        .and_then(|json| json.get("status"))
+        .and_then(|s| s.as_str())
+        == Some("error");
+    let message = parsed
+        .as_ref()
+        .and_then(|json| json.get("message"))
+        .and_then(|m| m.as_str())
+        .map(|m| m.to_string())
+        .or_else(|| (!body.trim().is_empty()).then(|| body.clone()));
+
+    Ok(DeleteResponse {
+        status: if status.is_success() && !api_error {
+            "success".to_string()
+        } else {
+            "error".to_string()
+        },
+        http_status: status.as_u16(),
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn media_description_is_validated_and_mirrored_into_nip94() {
+        let description =
+            MediaDescription::new(Some("  A red kite  ".to_string()), Some(String::new()))
+                .expect("description");
+        assert_eq!(description.alt.as_deref(), Some("A red kite"));
+        assert_eq!(description.caption, None);
+        assert!(MediaDescription::new(Some("x".repeat(MEDIA_ALT_MAX_CHARS + 1)), None).is_err());
+
+        let description = MediaDescription::new(
+            Some("A red kite".to_string()),
+            Some("Over the ridge".to_string()),
+        )
+        .expect("description");
+        let mut event = serde_json::json!({
+            "tags": [["url", "https://cdn.example/k.png"]],
+            "content": "",
+        });
+        description.apply_to_nip94_event(&mut event);
+        assert_eq!(event["tags"][1], serde_json::json!(["alt", "A red kite"]));
+        assert_eq!(event["content"], "Over the ridge");
+
+        let mut server_set = serde_json::json!({ "tags": [["alt", "kept"]], "content": "kept" });
+        description.apply_to_nip94_event(&mut server_set);
+        assert_eq!(server_set["tags"].as_array().map(Vec::len), Some(1));
+        assert_eq!(server_set["content"], "kept");
+    }
+
+    #[test]
+    fn normalize_http_method_accepts_only_http_verbs() {
+        assert_eq!(normalize_http_method(" patch ").as_deref(), Some("PATCH"));
+        assert_eq!(normalize_http_method("get").as_deref(), Some("GET"));
+        assert_eq!(normalize_http_method("FETCH"), None);
+        assert_eq!(normalize_http_method(""), None);
+    }
+
+    #[test]
+    fn build_delete_url_strips_trailing_slash() {
+        let sha = "AB".repeat(32);
+        assert_eq!(
+            build_delete_url("https://media.example/api/v2/media/", &sha),
+            format!("https://media.example/api/v2/media/{}", "ab".repeat(32))
+        );
+    }
+
+    #[test]
+    fn upload_retry_overrides_are_clamped() {
+        let runtime_default = UploadRetryPolicy {
+            max_retries: 2,
+            base_delay_ms: None,
+        };
+        assert_eq!(runtime_default.with_overrides(None, None), runtime_default);
+        let overridden = runtime_default.with_overrides(Some(50), Some(1));
+        assert_eq!(overridden.max_retries, UPLOAD_MAX_RETRIES_CAP);
+        assert_eq!(overridden.base_delay_ms, Some(MIN_RETRY_BASE_DELAY_MS));
+        let overridden = runtime_default.with_overrides(Some(0), Some(60_000));
+        assert_eq!(overridden.max_retries, 0);
+        assert_eq!(overridden.base_delay_ms, Some(MAX_RETRY_BASE_DELAY_MS));
+    }
+
+    #[test]
+    fn upload_field_names_prefer_last_accepted_name() {
+        let api_url = "https://media.example/field-cache-test";
+        assert_eq!(upload_field_names_for(api_url), UPLOAD_FIELD_NAMES.to_vec());
+        remember_upload_field_name(api_url, "files[]");
+        assert_eq!(
+            upload_field_names_for(api_url),
+            vec!["files[]", "file", "files"]
+        );
+    }
+
+    #[test]
+    fn interpret_upload_response_separates_transient_from_fatal() {
+        let gateway = interpret_upload_response(reqwest::StatusCode::BAD_GATEWAY, "upstream down");
+        assert!(matches!(gateway, UploadAttemptOutcome::Transient(_)));
+        let unauthorized = interpret_upload_response(reqwest::StatusCode::UNAUTHORIZED, "bad auth");
+        assert!(matches!(unauthorized, UploadAttemptOutcome::Failed(_)));
+    }
+
+    #[test]
+    fn is_sha256_hex_rejects_malformed_digests() {
+        assert!(is_sha256_hex(&"0f".repeat(32)));
+        assert!(!is_sha256_hex("abc"));
+        assert!(!is_sha256_hex(&"zz".repeat(32)));
+    }
+
+    #[test]
+    fn allowlist_checks_sniffed_type_before_declared() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+        assert_eq!(sniff_content_type(png), Some("image/png"));
+        assert_eq!(
+            sniff_content_type(b"\0\0\0\x18ftypqt  "),
+            Some("video/quicktime")
+        );
+        assert_eq!(sniff_content_type(b"plain text"), None);
+        assert_eq!(
+            sniff_content_type(b"\0\0\0\x18ftypheic"),
+            Some("image/heic")
+        );
+        assert_eq!(
+            sniff_content_type(b"\0\0\0\x18ftypmif1"),
+            Some("image/heif")
+        );
+
+        let images = vec!["image/*".to_string()];
+        assert!(check_allowed_content_type("a.png", png, "video/mp4", Some(&images)).is_ok());
+        let err = check_allowed_content_type("a.pdf", b"%PDF-1.7", "image/png", Some(&images))
+            .unwrap_err();
+        assert_eq!(err.code, UNSUPPORTED_CONTENT_TYPE_CODE);
+        let text = vec!["text/plain".to_string()];
+        let declared = "Text/Plain; charset=utf-8";
+        assert!(check_allowed_content_type("a.txt", b"hi", declared, Some(&text)).is_ok());
+        assert!(check_allowed_content_type("a.txt", b"hi", "text/plain", Some(&[])).is_ok());
+        assert!(content_type_allowed("audio/ogg", &["*/*".to_string()]));
+    }
+
+    // Without a platform prompt the gate can only deny, so an enabled gate must
+    // stop every NIP-98 signature with AUTH_REQUIRED
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    #[tokio::test]
+    async fn upload_signing_fails_when_the_sign_gate_denies() {
+        use crate::models::sign_auth::{SignAuthSettings, SignAuthState, AUTH_REQUIRED_CODE};
+
+        let open = SignAuthState::new(SignAuthSettings::default(), Default::default());
+        assert!(authorize_upload_signing(&open).await.is_ok());
+
+        let gated = SignAuthState::new(
+            SignAuthSettings {
+                require_auth_to_sign: true,
+                ..Default::default()
+            },
+            Default::default(),
+        );
+        let err = authorize_upload_signing(&gated).await.unwrap_err();
+        assert_eq!(err.code, AUTH_REQUIRED_CODE);
+    }
+
+    #[test]
+    fn upload_redirects_stay_on_the_signed_host_and_on_https() {
+        let origin = url::Url::parse("https://media.example/upload").unwrap();
+        let location = |value: &str| reqwest::header::HeaderValue::from_str(value).unwrap();
+
+        let next = upload_redirect_target(&origin, &origin, Some(&location("/v2/upload")));
+        assert_eq!(next.unwrap().as_str(), "https://media.example/v2/upload");
+        assert!(upload_redirect_target(&origin, &origin, None).is_err());
+        let other_host = location("https://cdn.example/upload");
+        assert!(upload_redirect_target(&origin, &origin, Some(&other_host)).is_err());
+        let downgrade = location("http://media.example/upload");
+        assert!(upload_redirect_target(&origin, &origin, Some(&downgrade)).is_err());
+
+        let plain = url::Url::parse("http://media.example/upload").unwrap();
+        let upgrade = location("https://media.example/upload");
+        assert!(upload_redirect_target(&plain, &plain, Some(&upgrade)).is_ok());
+    }
+
+    /// Answer `hops` HTTP requests on `listener` with `respond(path)`, returning each
+    /// request's path, NIP-98 `u` tag and body length
+    async fn serve_upload_hops(
+        listener: tokio::net::TcpListener,
+        hops: usize,
+        respond: impl Fn(&str) -> String,
+    ) -> Vec<(String, String, usize)> {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let mut seen = Vec::new();
+        for _ in 0..hops {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = Vec::new();
+            let mut chunk = [0u8; 8192];
+            let head_end = loop {
+                let n = stream.read(&mut chunk).await.unwrap();
+                buf.extend_from_slice(&chunk[..n]);
+                if let Some(at) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                    break at + 4;
+                }
+            };
+            let head = String::from_utf8_lossy(&buf[..head_end]).to_string();
+            let header = |name: &str| {
+                head.lines().find_map(|line| {
+                    let (key, value) = line.split_once(':')?;
+                    key.eq_ignore_ascii_case(name)
+                        .then(|| value.trim().to_string())
+                })
+            };
+            let content_length: usize = header("content-length").unwrap().parse().unwrap();
+            while buf.len() < head_end + content_length {
+                let n = stream.read(&mut chunk).await.unwrap();
+                buf.extend_from_slice(&chunk[..n]);
+            }
+
+            let token = header("authorization").unwrap();
+            let event = base64::engine::general_purpose::STANDARD
+                .decode(token.trim_start_matches("Nostr "))
+                .unwrap();
+            let event: serde_json::Value = serde_json::from_slice(&event).unwrap();
+            let signed_url = event["tags"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|tag| tag[0] == "u")
+                .and_then(|tag| tag[1].as_str())
+                .unwrap()
+                .to_string();
+            let path = head.split(' ').nth(1).unwrap().to_string();
+            stream.write_all(respond(&path).as_bytes()).await.unwrap();
+            seen.push((path, signed_url, content_length));
+        }
+        seen
+    }
+
+    #[tokio::test]
+    async fn uploads_follow_redirects_with_a_fresh_signature_and_body() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let redirect = |status: &str, location: &str| {
+            format!("HTTP/1.1 {status}\r\nLocation: {location}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+        };
+        let final_location = format!("{base}/final");
+        let respond = |path: &str| match path {
+            "/upload" => redirect("307 Temporary Redirect", "/cdn/upload"),
+            "/cdn/upload" => redirect("302 Found", &final_location),
+            _ => "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok".to_string(),
+        };
+
+        let keys = Keys::generate();
+        let net_runtime = NativeNetworkRuntime::new(false, String::new());
+        let client = UploadClient::new(&net_runtime, &keys).unwrap();
+        let file = UploadFileBytes {
+            bytes: b"\x89PNG\r\n\x1a\n",
+            name: "a.png",
+            content_type: "image/png",
+        };
+        let (description, payload_hash) = (MediaDescription::default(), "0".repeat(64));
+        let api_url = format!("{base}/upload");
+        let upload = send_multipart_request(
+            &client,
+            &api_url,
+            "file",
+            &file,
+            &description,
+            &payload_hash,
+        );
+        let (result, seen) = tokio::join!(upload, serve_upload_hops(listener, 3, respond));
+
+        let (status, body) = result.unwrap_or_else(|e| panic!("{}: {}", e.code, e.message));
+        assert_eq!((status.as_u16(), body.as_str()), (200, "ok"));
+        let paths: Vec<_> = seen.iter().map(|(path, _, _)| path.as_str()).collect();
+        assert_eq!(paths, ["/upload", "/cdn/upload", "/final"]);
+        for (path, signed_url, body_len) in &seen {
+            assert_eq!(signed_url, &format!("{base}{path}"));
+            assert_eq!(*body_len, seen[0].2);
+            assert!(*body_len > file.bytes.len());
+        }
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let api_url = format!("http://{}/upload", listener.local_addr().unwrap());
+        let client = UploadClient {
+            max_redirects: 0,
+            ..client
+        };
+        let upload = send_multipart_request(
+            &client,
+            &api_url,
+            "file",
+            &file,
+            &description,
+            &payload_hash,
+        );
+        let (result, _) = tokio::join!(upload, serve_upload_hops(listener, 1, respond));
+        assert_eq!(result.unwrap_err().code, "REDIRECT_ERROR");
+    }
 }
@@ -1,13 +1,20 @@
 // Desktop-only wallet implementation with native keychain
 #[cfg(not(target_os = "android"))]
 mod desktop {
-    use crate::native_keychain;
-    use crate::profiles::{DesktopProfileState, resolve_profile_for_window};
+    use crate::account_index;
+    use crate::data_root::default_app_data_dir;
+    use crate::encrypted_key_file::{self, KEYFILE_PASSWORD_REQUIRED_CODE, NO_KEYCHAIN_WARNING};
+    use crate::models::sign_auth::SignAuthState;
+    use crate::native_keychain::{self, KeychainAccessFailed, KeychainErrorKind};
+    use crate::profiles::{resolve_profile_for_window, DesktopProfileState};
+    use crate::services::sign_auth::{
+        authorize_keychain_unlock, authorize_signing, private_created_at,
+    };
     use crate::session::SessionState;
     use nostr::prelude::*;
     use serde::{Deserialize, Serialize};
     use std::borrow::Cow;
-    use tauri::{AppHandle, State, WebviewWindow};
+    use tauri::{AppHandle, Emitter, Manager, State, WebviewWindow};
     use zeroize::Zeroizing;
 
     async fn resolve_profile_id(
@@ -24,6 +31,9 @@ mod desktop {
         pub content: String,
         pub tags: Vec<Vec<String>>,
         pub created_at: u64,
+        /// Optional NIP-26 delegation tag (`["delegation", <delegator>, <conditions>, <sig>]`).
+        #[serde(default)]
+        pub delegation: Option<Vec<String>>,
     }
 
     #[derive(Debug, Serialize, Deserialize)]
@@ -46,14 +56,14 @@ mod desktop {
         session: State<'_, SessionState>,
         profiles: State<'_, DesktopProfileState>,
     ) -> Result<Option<String>, String> {
-        match ensure_session(&app, &window, &profiles, &session).await {
+        match hydrate_session(&app, &window, &profiles, &session).await {
             Ok(keys) => Ok(Some(keys.public_key().to_string())),
             Err(_) => Ok(None),
         }
     }
 
     /// Ensure session is hydrated from keychain if not present
-    async fn ensure_session(
+    async fn hydrate_session(
         app: &AppHandle,
         window: &WebviewWindow,
         profiles: &State<'_, DesktopProfileState>,
@@ -64,24 +74,76 @@ mod desktop {
             return Ok(keys);
         }
 
-        match native_keychain::read_nsec_for_profile(&profile_id)? {
-            Some(nsec) => {
-                let nsec_zero = Zeroizing::new(nsec);
-                match session.set_keys(&profile_id, &*nsec_zero).await {
-                    Ok(_pubkey) => {
-                        eprintln!("[SESSION] Native session re-hydrated from OS keychain for profile {}", profile_id);
-                        session
-                            .get_keys(&profile_id)
-                            .await
-                            .ok_or_else(|| "Failed to hydrate session".to_string())
-                    }
-                    Err(e) => Err(format!("Failed to hydrate session from keychain: {}", e)),
+        authorize_keychain_unlock(&app.state::<SignAuthState>()).await?;
+        let stored = native_keychain::read_nsec_for_profile(&profile_id)
+            .inspect_err(|error| report_keychain_failure(window, &profile_id, error));
+        // Without a keyring the key lives in the password-encrypted file, which only
+        // `unlock_encrypted_key_file` can open.
+        let no_backend = matches!(
+            &stored,
+            Err(error) if KeychainErrorKind::from_error_message(error) == Some(KeychainErrorKind::NoBackend)
+        );
+        if (no_backend || matches!(stored, Ok(None)))
+            && encrypted_key_file::has_encrypted_key_file(&default_app_data_dir(app)?, &profile_id)
+        {
+            return Err(format!(
+                "{KEYFILE_PASSWORD_REQUIRED_CODE}: Enter the key file password to unlock this profile"
+            ));
+        }
+        match stored? {
+            Some(nsec_zero) => match session.set_keys(&profile_id, &*nsec_zero).await {
+                Ok(pubkey) => {
+                    log::info!(
+                        "[SESSION] Native session re-hydrated from OS keychain for profile {}",
+                        profile_id
+                    );
+                    account_index::note_stored_account(app, &profile_id, &pubkey);
+                    session
+                        .get_keys(&profile_id)
+                        .await
+                        .ok_or_else(|| "Failed to hydrate session".to_string())
                 }
+                Err(e) => Err(format!("Failed to hydrate session from keychain: {}", e)),
+            },
+            None => {
+                let error = format!(
+                    "{}: No active native session and no key in keychain",
+                    KeychainErrorKind::NoEntry.code()
+                );
+                report_keychain_failure(window, &profile_id, &error);
+                Err(error)
             }
-            None => Err("No active native session and no key in keychain".to_string()),
         }
     }
 
+    /// Emit `keychain-access-failed` so the UI can offer "import a key" vs "unlock your keyring"
+    fn report_keychain_failure(window: &WebviewWindow, profile_id: &str, error: &str) {
+        let Some(kind) = KeychainErrorKind::from_error_message(error) else {
+            return;
+        };
+        let _ = window.emit(
+            "keychain-access-failed",
+            KeychainAccessFailed {
+                profile_id: profile_id.to_string(),
+                kind,
+                message: error.to_string(),
+            },
+        );
+    }
+
+    /// Hydrate the session for anything that signs or uses the secret key, behind the
+    /// OS-auth gate when enabled
+    async fn ensure_session(
+        app: &AppHandle,
+        window: &WebviewWindow,
+        profiles: &State<'_, DesktopProfileState>,
+        session: &SessionState,
+    ) -> Result<Keys, String> {
+        let keys = hydrate_session(app, window, profiles, session).await?;
+        authorize_signing(&app.state::<SignAuthState>()).await?;
+        Ok(keys)
+    }
+
     /// Store an nsec in the native keychain and session.
     #[tauri::command]
     pub async fn import_native_nsec(
@@ -99,10 +161,60 @@ mod desktop {
         session.set_keys(&profile_id, &*nsec_zero).await?;
 
         native_keychain::write_nsec_for_profile(&profile_id, &*nsec_zero)?;
+        account_index::note_stored_account(&app, &profile_id, &keys.public_key());
 
         Ok(keys.public_key().to_string())
     }
 
+    /// Import a secret key from a backup file (nsec, hex, or password-protected ncryptsec)
+    /// into the session and native keychain.
+    #[tauri::command]
+    pub async fn import_nsec_from_file(
+        app: AppHandle,
+        window: WebviewWindow,
+        session: State<'_, SessionState>,
+        profiles: State<'_, DesktopProfileState>,
+        path: String,
+        password: Option<String>,
+    ) -> Result<String, String> {
+        let password = password.map(Zeroizing::new);
+        let secret = tauri::async_runtime::spawn_blocking(move || {
+            super::read_secret_key_file(&path, password.as_deref().map(String::as_str))
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+        let profile_id = resolve_profile_id(&app, &profiles, &window).await?;
+
+        let pubkey = session.set_keys(&profile_id, &secret).await?;
+        native_keychain::write_nsec_for_profile(&profile_id, &secret)?;
+        account_index::note_stored_account(&app, &profile_id, &pubkey);
+
+        Ok(pubkey.to_string())
+    }
+
+    /// Import a secret key from a scanned QR payload (`nsec1…`, hex, `nostr:nsec1…` or a
+    /// password-protected ncryptsec) into the session and native keychain. Returns the npub.
+    #[tauri::command]
+    pub async fn import_from_uri(
+        app: AppHandle,
+        window: WebviewWindow,
+        session: State<'_, SessionState>,
+        profiles: State<'_, DesktopProfileState>,
+        uri: String,
+        password: Option<String>,
+    ) -> Result<String, String> {
+        let uri = Zeroizing::new(uri);
+        let password = password.map(Zeroizing::new);
+        let secret = super::secret_from_uri(&uri, password.as_deref().map(String::as_str))?;
+        let profile_id = resolve_profile_id(&app, &profiles, &window).await?;
+
+        let pubkey = session.set_keys(&profile_id, &secret).await?;
+        native_keychain::write_nsec_for_profile(&profile_id, &secret)?;
+        account_index::note_stored_account(&app, &profile_id, &pubkey);
+
+        pubkey.to_bech32().map_err(|e| e.to_string())
+    }
+
     /// Generate a new nsec and store it in the native keychain and session.
     #[tauri::command]
     pub async fn generate_native_nsec(
@@ -120,40 +232,59 @@ mod desktop {
         session.set_keys(&profile_id, &*nsec_zero).await?;
 
         native_keychain::write_nsec_for_profile(&profile_id, &*nsec_zero)?;
+        account_index::note_stored_account(&app, &profile_id, &keys.public_key());
 
         Ok(keys.public_key().to_string())
     }
 
     /// Sign a Nostr event using the in-memory session.
+    /// `created_at` is blurred first when a timestamp privacy mode is set.
     #[tauri::command]
     pub async fn sign_event_native(
         app: AppHandle,
         window: WebviewWindow,
         session: State<'_, SessionState>,
         profiles: State<'_, DesktopProfileState>,
+        sign_auth: State<'_, SignAuthState>,
         req: NativeSignRequest,
     ) -> Result<NativeSignResponse, String> {
         let keys = ensure_session(&app, &window, &profiles, &session).await?;
+        // Blurred before the delegation check so its conditions see the signed timestamp
+        let created_at = private_created_at(&sign_auth, req.kind as u16, req.created_at).await?;
+
+        let mut tags = req
+            .tags
+            .iter()
+            .map(|t| {
+                Tag::parse(t).unwrap_or(Tag::custom(
+                    TagKind::Custom(Cow::Owned(t[0].clone())),
+                    t[1..].to_vec(),
+                ))
+            })
+            .collect::<Vec<_>>();
+        if let Some(delegation) = req.delegation.as_deref() {
+            tags = super::apply_delegation_tag(
+                &keys.public_key(),
+                req.kind as u16,
+                created_at,
+                tags,
+                delegation,
+            )?;
+        }
 
         let unsigned_event = EventBuilder::new(Kind::from(req.kind as u16), req.content.clone())
-            .tags(
-                req.tags
-                    .iter()
-                    .map(|t| {
-                        Tag::parse(t).unwrap_or(Tag::custom(
-                            TagKind::Custom(Cow::Owned(t[0].clone())),
-                            t[1..].to_vec(),
-                        ))
-                    })
-                    .collect::<Vec<_>>(),
-            )
-            .custom_created_at(Timestamp::from(req.created_at))
+            .tags(tags)
+            .custom_created_at(Timestamp::from(created_at))
             .build(keys.public_key());
 
-        let signed_event = unsigned_event
-            .sign(&keys)
-            .await
-            .map_err(|e| e.to_string())?;
+        sign_unsigned_event(&keys, unsigned_event).await
+    }
+
+    async fn sign_unsigned_event(
+        keys: &Keys,
+        unsigned_event: UnsignedEvent,
+    ) -> Result<NativeSignResponse, String> {
+        let signed_event = unsigned_event.sign(keys).await.map_err(|e| e.to_string())?;
 
         Ok(NativeSignResponse {
             id: signed_event.id.to_string(),
@@ -170,6 +301,117 @@ mod desktop {
         })
     }
 
+    /// Create a signed NIP-26 delegation tag granting `delegatee_pubkey` the right
+    /// to publish on behalf of the session key under `conditions`.
+    #[tauri::command]
+    pub async fn create_delegation(
+        app: AppHandle,
+        window: WebviewWindow,
+        session: State<'_, SessionState>,
+        profiles: State<'_, DesktopProfileState>,
+        delegatee_pubkey: String,
+        conditions: String,
+        expiry: Option<u64>,
+    ) -> Result<Vec<String>, String> {
+        let keys = ensure_session(&app, &window, &profiles, &session).await?;
+        super::build_delegation_tag(&keys, &delegatee_pubkey, &conditions, expiry)
+    }
+
+    /// Sign a NIP-89 handler information event (kind 31990) advertising which kinds
+    /// this app opens and its launch URL templates.
+    #[tauri::command]
+    pub async fn build_handler_info_event(
+        app: AppHandle,
+        window: WebviewWindow,
+        session: State<'_, SessionState>,
+        profiles: State<'_, DesktopProfileState>,
+        req: super::HandlerInfoRequest,
+    ) -> Result<NativeSignResponse, String> {
+        let keys = ensure_session(&app, &window, &profiles, &session).await?;
+        let unsigned_event = super::handler_info_builder(&req)?.build(keys.public_key());
+        sign_unsigned_event(&keys, unsigned_event).await
+    }
+
+    /// Sign a NIP-89 recommendation (kind 31989) pointing `kind` at a handler's 31990 event.
+    #[tauri::command]
+    pub async fn build_handler_recommendation_event(
+        app: AppHandle,
+        window: WebviewWindow,
+        session: State<'_, SessionState>,
+        profiles: State<'_, DesktopProfileState>,
+        req: super::HandlerRecommendationRequest,
+    ) -> Result<NativeSignResponse, String> {
+        let keys = ensure_session(&app, &window, &profiles, &session).await?;
+        let unsigned_event = super::handler_recommendation_builder(&req)?.build(keys.public_key());
+        sign_unsigned_event(&keys, unsigned_event).await
+    }
+
+    /// Sign a NIP-51 list (mute list, bookmarks, follow set, ...) with private entries
+    /// encrypted to the session's own key.
+    #[tauri::command]
+    pub async fn build_list_event(
+        app: AppHandle,
+        window: WebviewWindow,
+        session: State<'_, SessionState>,
+        profiles: State<'_, DesktopProfileState>,
+        req: super::ListEventRequest,
+    ) -> Result<NativeSignResponse, String> {
+        let keys = ensure_session(&app, &window, &profiles, &session).await?;
+        let unsigned_event = super::list_event_builder(&keys, &req)?.build(keys.public_key());
+        sign_unsigned_event(&keys, unsigned_event).await
+    }
+
+    /// Whether signing will work for this window's profile, without prompting or reading the secret
+    #[tauri::command]
+    pub async fn can_sign(
+        app: AppHandle,
+        window: WebviewWindow,
+        session: State<'_, SessionState>,
+        profiles: State<'_, DesktopProfileState>,
+    ) -> Result<super::SignCapability, String> {
+        let profile_id = resolve_profile_id(&app, &profiles, &window).await?;
+        let has_session = session.get_keys(&profile_id).await.is_some();
+        let app_data_dir = default_app_data_dir(&app)?;
+        let (entry_dir, entry_profile_id) = (app_data_dir.clone(), profile_id.clone());
+        let keychain_available = tauri::async_runtime::spawn_blocking(move || {
+            native_keychain::has_nsec_entry_for_profile(&entry_dir, &entry_profile_id)
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+        let key_file_exists =
+            encrypted_key_file::has_encrypted_key_file(&app_data_dir, &profile_id);
+        Ok(super::SignCapability::new(
+            has_session,
+            keychain_available,
+            key_file_exists,
+        ))
+    }
+
+    /// Move a key left under an old keychain service/key name (e.g. from an older build)
+    /// to this profile's current entry. Returns whether a key was migrated. Only the app's
+    /// own services and this profile's key names are accepted.
+    #[tauri::command]
+    pub async fn migrate_keychain(
+        app: AppHandle,
+        window: WebviewWindow,
+        profiles: State<'_, DesktopProfileState>,
+        old_service: String,
+        old_key: String,
+    ) -> Result<bool, String> {
+        let profile_id = resolve_profile_id(&app, &profiles, &window).await?;
+        let migrated =
+            native_keychain::migrate_nsec_from_entry(&profile_id, &old_service, &old_key)?;
+        if migrated {
+            // The write above cached the secret, so this does not hit the keychain again
+            if let Some(keys) = native_keychain::read_nsec_for_profile(&profile_id)?
+                .and_then(|secret| Keys::parse(&secret).ok())
+            {
+                account_index::note_stored_account(&app, &profile_id, &keys.public_key());
+            }
+        }
+        Ok(migrated)
+    }
+
     /// Delete the stored nsec from the keychain and clear session.
     #[tauri::command]
     pub async fn logout_native(
@@ -182,7 +424,113 @@ mod desktop {
         // Clear session
         session.clear(Some(&profile_id)).await;
 
-        native_keychain::delete_nsec_for_profile(&profile_id)
+        encrypted_key_file::delete_encrypted_key_file(&default_app_data_dir(&app)?, &profile_id)?;
+        native_keychain::delete_nsec_for_profile(&profile_id)?;
+        account_index::note_forgotten_account(&app, &profile_id);
+        Ok(())
+    }
+
+    /// Where this profile's key can be persisted
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct KeyStorageStatus {
+        pub os_keychain_available: bool,
+        pub encrypted_file_exists: bool,
+        /// Shown whenever the key has to live in the encrypted file
+        pub warning: Option<String>,
+    }
+
+    #[tauri::command]
+    pub async fn get_key_storage_status(
+        app: AppHandle,
+        window: WebviewWindow,
+        profiles: State<'_, DesktopProfileState>,
+    ) -> Result<KeyStorageStatus, String> {
+        let profile_id = resolve_profile_id(&app, &profiles, &window).await?;
+        let os_keychain_available =
+            tauri::async_runtime::spawn_blocking(native_keychain::keychain_backend_available)
+                .await
+                .map_err(|e| e.to_string())?;
+        Ok(KeyStorageStatus {
+            os_keychain_available,
+            encrypted_file_exists: encrypted_key_file::has_encrypted_key_file(
+                &default_app_data_dir(&app)?,
+                &profile_id,
+            ),
+            warning: (!os_keychain_available).then(|| NO_KEYCHAIN_WARNING.to_string()),
+        })
+    }
+
+    /// Probe the OS keychain with a throwaway entry; the UI runs this at startup and offers
+    /// the encrypted-file fallback when `available` is false.
+    #[tauri::command]
+    pub async fn check_keychain() -> Result<native_keychain::KeychainCheck, String> {
+        tauri::async_runtime::spawn_blocking(native_keychain::check_keychain)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Persist the active session key to the password-encrypted file. Used after an
+    /// import or generate failed with `KEYCHAIN_NO_BACKEND`, which leaves the key in the session.
+    #[tauri::command]
+    pub async fn store_key_in_encrypted_file(
+        app: AppHandle,
+        window: WebviewWindow,
+        session: State<'_, SessionState>,
+        profiles: State<'_, DesktopProfileState>,
+        password: String,
+    ) -> Result<String, String> {
+        let password = Zeroizing::new(password);
+        let profile_id = resolve_profile_id(&app, &profiles, &window).await?;
+        let keys = session
+            .get_keys(&profile_id)
+            .await
+            .ok_or_else(|| "No active native session to store".to_string())?;
+        log::warn!("[SESSION] {NO_KEYCHAIN_WARNING} (profile {profile_id})");
+        let secret = super::secret_hex(&keys);
+        let app_data_dir = default_app_data_dir(&app)?;
+        tauri::async_runtime::spawn_blocking(move || {
+            encrypted_key_file::write_encrypted_key_file(
+                &app_data_dir,
+                &profile_id,
+                &secret,
+                &password,
+            )
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+        Ok(keys.public_key().to_string())
+    }
+
+    /// Decrypt the key file with `password` and hydrate the session from it.
+    #[tauri::command]
+    pub async fn unlock_encrypted_key_file(
+        app: AppHandle,
+        window: WebviewWindow,
+        session: State<'_, SessionState>,
+        profiles: State<'_, DesktopProfileState>,
+        password: String,
+    ) -> Result<String, String> {
+        let password = Zeroizing::new(password);
+        let profile_id = resolve_profile_id(&app, &profiles, &window).await?;
+        let app_data_dir = default_app_data_dir(&app)?;
+        let lookup_profile_id = profile_id.clone();
+        let secret = tauri::async_runtime::spawn_blocking(move || {
+            encrypted_key_file::read_encrypted_key_file(
+                &app_data_dir,
+                &lookup_profile_id,
+                &password,
+            )
+        })
+        .await
+        .map_err(|e| e.to_string())??
+        .ok_or_else(|| "No encrypted key file for this profile".to_string())?;
+        let pubkey = session.set_keys(&profile_id, &secret).await?;
+        log::info!(
+            "[SESSION] Native session hydrated from encrypted key file for profile {}",
+            profile_id
+        );
+        Ok(pubkey.to_string())
     }
 
     /// Encrypt content using NIP-04 (Legacy)
@@ -196,7 +544,7 @@ mod desktop {
         content: String,
     ) -> Result<String, String> {
         let keys = ensure_session(&app, &window, &profiles, &session).await?;
-        let sk_hex = keys.secret_key().to_secret_hex();
+        let sk_hex = super::secret_hex(&keys);
 
         libobscur::crypto::nip04::encrypt_nip04(&sk_hex, &public_key, &content)
     }
@@ -212,11 +560,25 @@ mod desktop {
         ciphertext: String,
     ) -> Result<String, String> {
         let keys = ensure_session(&app, &window, &profiles, &session).await?;
-        let sk_hex = keys.secret_key().to_secret_hex();
+        let sk_hex = super::secret_hex(&keys);
 
         libobscur::crypto::nip04::decrypt_nip04(&sk_hex, &public_key, &ciphertext)
     }
 
+    /// ECDH shared secret (hex X-coordinate) between the active key and `peer_pubkey`
+    #[tauri::command]
+    pub async fn compute_shared_secret(
+        app: AppHandle,
+        window: WebviewWindow,
+        session: State<'_, SessionState>,
+        profiles: State<'_, DesktopProfileState>,
+        peer_pubkey: String,
+    ) -> Result<String, String> {
+        let keys = ensure_session(&app, &window, &profiles, &session).await?;
+        let shared = super::shared_secret_hex(&keys, &peer_pubkey)?;
+        Ok(shared.to_string())
+    }
+
     /// Encrypt content using NIP-44 (Modern)
     #[tauri::command]
     pub async fn encrypt_nip44(
@@ -228,7 +590,7 @@ mod desktop {
         content: String,
     ) -> Result<String, String> {
         let keys = ensure_session(&app, &window, &profiles, &session).await?;
-        let sk_hex = keys.secret_key().to_secret_hex();
+        let sk_hex = super::secret_hex(&keys);
 
         libobscur::crypto::nip44::encrypt_nip44(&sk_hex, &public_key, &content)
     }
@@ -244,11 +606,60 @@ mod desktop {
         payload: String,
     ) -> Result<String, String> {
         let keys = ensure_session(&app, &window, &profiles, &session).await?;
-        let sk_hex = keys.secret_key().to_secret_hex();
+        let sk_hex = super::secret_hex(&keys);
 
         libobscur::crypto::nip44::decrypt_nip44(&sk_hex, &public_key, &payload)
     }
 
+    /// Decrypt a DM payload without knowing its scheme (NIP-44 v2, then NIP-04)
+    #[tauri::command]
+    pub async fn decrypt_auto(
+        app: AppHandle,
+        window: WebviewWindow,
+        session: State<'_, SessionState>,
+        profiles: State<'_, DesktopProfileState>,
+        public_key: String,
+        ciphertext: String,
+    ) -> Result<super::AutoDecryptResult, String> {
+        let keys = ensure_session(&app, &window, &profiles, &session).await?;
+        super::decrypt_auto_payload(&keys, &public_key, &ciphertext)
+    }
+
+    /// Re-encrypt a NIP-04 ciphertext to NIP-44 v2 under the same session key
+    #[tauri::command]
+    pub async fn reencrypt_nip04_to_nip44(
+        app: AppHandle,
+        window: WebviewWindow,
+        session: State<'_, SessionState>,
+        profiles: State<'_, DesktopProfileState>,
+        public_key: String,
+        nip04_ciphertext: String,
+    ) -> Result<String, String> {
+        let keys = ensure_session(&app, &window, &profiles, &session).await?;
+        super::reencrypt_nip04_payload(&keys, &public_key, &nip04_ciphertext)
+    }
+
+    /// Alias of `reencrypt_nip04_to_nip44` for the DM migration tool, keyed by the peer
+    #[tauri::command]
+    pub async fn reencrypt_dm(
+        app: AppHandle,
+        window: WebviewWindow,
+        session: State<'_, SessionState>,
+        profiles: State<'_, DesktopProfileState>,
+        peer_pubkey: String,
+        nip04_ciphertext: String,
+    ) -> Result<String, String> {
+        reencrypt_nip04_to_nip44(
+            app,
+            window,
+            session,
+            profiles,
+            peer_pubkey,
+            nip04_ciphertext,
+        )
+        .await
+    }
+
     /// Encrypt content using NIP-17 Gift Wrap
     #[tauri::command]
     pub async fn encrypt_gift_wrap(
@@ -260,7 +671,7 @@ mod desktop {
         rumor: libobscur::crypto::nip17::Rumor,
     ) -> Result<String, String> {
         let keys = ensure_session(&app, &window, &profiles, &session).await?;
-        let sk_hex = keys.secret_key().to_secret_hex();
+        let sk_hex = super::secret_hex(&keys);
 
         libobscur::crypto::nip17::wrap_rumor(&sk_hex, &recipient_pk, &rumor, None)
     }
@@ -276,7 +687,7 @@ mod desktop {
         gift_wrap_sender_pk: String,
     ) -> Result<libobscur::crypto::nip17::Rumor, String> {
         let keys = ensure_session(&app, &window, &profiles, &session).await?;
-        let sk_hex = keys.secret_key().to_secret_hex();
+        let sk_hex = super::secret_hex(&keys);
 
         libobscur::crypto::nip17::unwrap_gift_wrap(
             &sk_hex,
@@ -292,15 +703,17 @@ mod desktop {
         window: WebviewWindow,
         session: State<'_, SessionState>,
         profiles: State<'_, DesktopProfileState>,
-    ) -> Result<String, String> {
+    ) -> Result<Zeroizing<String>, String> {
         let keys = ensure_session(&app, &window, &profiles, &session).await?;
-        Ok(keys.secret_key().to_secret_hex())
+        Ok(super::secret_hex(&keys))
     }
 }
 
 // Mobile implementations (secure-key scoped)
 #[cfg(any(target_os = "android", target_os = "ios"))]
 mod mobile {
+    use crate::models::sign_auth::SignAuthState;
+    use crate::services::sign_auth::private_created_at;
     use crate::session::SessionState;
     use libobscur::ffi::{delete_key, has_key, load_key, store_key};
     use nostr::prelude::*;
@@ -322,6 +735,9 @@ mod mobile {
         pub content: String,
         pub tags: Vec<Vec<String>>,
         pub created_at: u64,
+        /// Optional NIP-26 delegation tag (`["delegation", <delegator>, <conditions>, <sig>]`).
+        #[serde(default)]
+        pub delegation: Option<Vec<String>>,
     }
 
     #[derive(Debug, Serialize, Deserialize)]
@@ -347,13 +763,15 @@ mod mobile {
             return Err("locked_no_secure_key".to_string());
         }
         let key_bytes = load_key(key_id).map_err(|error| error.to_string())?;
-        let key_hex = String::from_utf8(key_bytes)
-            .map_err(|_| "integrity_mismatch: secure key payload is invalid".to_string())?;
+        let key_hex = Zeroizing::new(
+            String::from_utf8(key_bytes)
+                .map_err(|_| "integrity_mismatch: secure key payload is invalid".to_string())?,
+        );
         session
             .set_keys(MOBILE_PROFILE_ID, &key_hex)
             .await
             .map_err(|error| format!("failed_to_restore_secure_session: {error}"))?;
-        eprintln!("[SESSION] Mobile session re-hydrated from secure key store");
+        log::info!("[SESSION] Mobile session re-hydrated from secure key store");
         session
             .get_keys(MOBILE_PROFILE_ID)
             .await
@@ -379,27 +797,77 @@ mod mobile {
     ) -> Result<String, String> {
         let nsec_zero = Zeroizing::new(nsec);
         let keys = Keys::parse(&*nsec_zero).map_err(|e| e.to_string())?;
-        let key_hex = keys.secret_key().to_secret_hex();
+        let key_hex = super::secret_hex(&keys);
 
         session
             .set_keys(MOBILE_PROFILE_ID, &key_hex)
             .await
             .map_err(|error| format!("failed_to_set_secure_session: {error}"))?;
 
-        store_key(scoped_key_id(), key_hex.into_bytes())
+        store_key(scoped_key_id(), key_hex.as_bytes().to_vec())
             .map_err(|error| format!("rust_secure_store: {}", error.to_string()))?;
 
         Ok(keys.public_key().to_string())
     }
 
+    #[tauri::command]
+    pub async fn import_nsec_from_file(
+        app: AppHandle,
+        session: State<'_, SessionState>,
+        path: String,
+        password: Option<String>,
+    ) -> Result<String, String> {
+        let password = password.map(Zeroizing::new);
+        let secret = tauri::async_runtime::spawn_blocking(move || {
+            super::read_secret_key_file(&path, password.as_deref().map(String::as_str))
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+        let keys = Keys::parse(&*secret).map_err(|e| e.to_string())?;
+        let key_hex = super::secret_hex(&keys);
+
+        session
+            .set_keys(MOBILE_PROFILE_ID, &key_hex)
+            .await
+            .map_err(|error| format!("failed_to_set_secure_session: {error}"))?;
+
+        store_key(scoped_key_id(), key_hex.as_bytes().to_vec())
+            .map_err(|error| format!("rust_secure_store: {}", error.to_string()))?;
+
+        Ok(keys.public_key().to_string())
+    }
+
+    #[tauri::command]
+    pub async fn import_from_uri(
+        app: AppHandle,
+        session: State<'_, SessionState>,
+        uri: String,
+        password: Option<String>,
+    ) -> Result<String, String> {
+        let uri = Zeroizing::new(uri);
+        let password = password.map(Zeroizing::new);
+        let secret = super::secret_from_uri(&uri, password.as_deref().map(String::as_str))?;
+        let keys = Keys::parse(&*secret).map_err(|e| e.to_string())?;
+        let key_hex = super::secret_hex(&keys);
+
+        session
+            .set_keys(MOBILE_PROFILE_ID, &key_hex)
+            .await
+            .map_err(|error| format!("failed_to_set_secure_session: {error}"))?;
+
+        store_key(scoped_key_id(), key_hex.as_bytes().to_vec())
+            .map_err(|error| format!("rust_secure_store: {}", error.to_string()))?;
+
+        keys.public_key().to_bech32().map_err(|e| e.to_string())
+    }
+
     #[tauri::command]
     pub async fn generate_native_nsec(
         app: AppHandle,
         session: State<'_, SessionState>,
     ) -> Result<String, String> {
         let keys = Keys::generate();
-        let key_hex = keys.secret_key().to_secret_hex();
-        let key_hex_zero = Zeroizing::new(key_hex);
+        let key_hex_zero = super::secret_hex(&keys);
 
         session
             .set_keys(MOBILE_PROFILE_ID, &*key_hex_zero)
@@ -416,29 +884,45 @@ mod mobile {
     pub async fn sign_event_native(
         app: AppHandle,
         session: State<'_, SessionState>,
+        sign_auth: State<'_, SignAuthState>,
         req: NativeSignRequest,
     ) -> Result<NativeSignResponse, String> {
         let keys = ensure_session(&app, &session).await?;
+        let created_at = private_created_at(&sign_auth, req.kind as u16, req.created_at).await?;
+
+        let mut tags = req
+            .tags
+            .iter()
+            .map(|t| {
+                Tag::parse(t).unwrap_or(Tag::custom(
+                    TagKind::Custom(Cow::Owned(t[0].clone())),
+                    t[1..].to_vec(),
+                ))
+            })
+            .collect::<Vec<_>>();
+        if let Some(delegation) = req.delegation.as_deref() {
+            tags = super::apply_delegation_tag(
+                &keys.public_key(),
+                req.kind as u16,
+                created_at,
+                tags,
+                delegation,
+            )?;
+        }
 
         let unsigned_event = EventBuilder::new(Kind::from(req.kind as u16), req.content.clone())
-            .tags(
-                req.tags
-                    .iter()
-                    .map(|t| {
-                        Tag::parse(t).unwrap_or(Tag::custom(
-                            TagKind::Custom(Cow::Owned(t[0].clone())),
-                            t[1..].to_vec(),
-                        ))
-                    })
-                    .collect::<Vec<_>>(),
-            )
-            .custom_created_at(Timestamp::from(req.created_at))
+            .tags(tags)
+            .custom_created_at(Timestamp::from(created_at))
             .build(keys.public_key());
 
-        let signed_event = unsigned_event
-            .sign(&keys)
-            .await
-            .map_err(|e| e.to_string())?;
+        sign_unsigned_event(&keys, unsigned_event).await
+    }
+
+    async fn sign_unsigned_event(
+        keys: &Keys,
+        unsigned_event: UnsignedEvent,
+    ) -> Result<NativeSignResponse, String> {
+        let signed_event = unsigned_event.sign(keys).await.map_err(|e| e.to_string())?;
 
         Ok(NativeSignResponse {
             id: signed_event.id.to_string(),
@@ -455,6 +939,64 @@ mod mobile {
         })
     }
 
+    #[tauri::command]
+    pub async fn create_delegation(
+        app: AppHandle,
+        session: State<'_, SessionState>,
+        delegatee_pubkey: String,
+        conditions: String,
+        expiry: Option<u64>,
+    ) -> Result<Vec<String>, String> {
+        let keys = ensure_session(&app, &session).await?;
+        super::build_delegation_tag(&keys, &delegatee_pubkey, &conditions, expiry)
+    }
+
+    #[tauri::command]
+    pub async fn can_sign(
+        session: State<'_, SessionState>,
+    ) -> Result<super::SignCapability, String> {
+        let has_session = session.get_keys(MOBILE_PROFILE_ID).await.is_some();
+        let keychain_available = has_key(scoped_key_id()).unwrap_or(false);
+        Ok(super::SignCapability::new(
+            has_session,
+            keychain_available,
+            false,
+        ))
+    }
+
+    #[tauri::command]
+    pub async fn build_handler_info_event(
+        app: AppHandle,
+        session: State<'_, SessionState>,
+        req: super::HandlerInfoRequest,
+    ) -> Result<NativeSignResponse, String> {
+        let keys = ensure_session(&app, &session).await?;
+        let unsigned_event = super::handler_info_builder(&req)?.build(keys.public_key());
+        sign_unsigned_event(&keys, unsigned_event).await
+    }
+
+    #[tauri::command]
+    pub async fn build_handler_recommendation_event(
+        app: AppHandle,
+        session: State<'_, SessionState>,
+        req: super::HandlerRecommendationRequest,
+    ) -> Result<NativeSignResponse, String> {
+        let keys = ensure_session(&app, &session).await?;
+        let unsigned_event = super::handler_recommendation_builder(&req)?.build(keys.public_key());
+        sign_unsigned_event(&keys, unsigned_event).await
+    }
+
+    #[tauri::command]
+    pub async fn build_list_event(
+        app: AppHandle,
+        session: State<'_, SessionState>,
+        req: super::ListEventRequest,
+    ) -> Result<NativeSignResponse, String> {
+        let keys = ensure_session(&app, &session).await?;
+        let unsigned_event = super::list_event_builder(&keys, &req)?.build(keys.public_key());
+        sign_unsigned_event(&keys, unsigned_event).await
+    }
+
     #[tauri::command]
     pub async fn logout_native(
         app: AppHandle,
@@ -474,7 +1016,7 @@ mod mobile {
         content: String,
     ) -> Result<String, String> {
         let keys = ensure_session(&app, &session).await?;
-        let sk_hex = keys.secret_key().to_secret_hex();
+        let sk_hex = super::secret_hex(&keys);
 
         libobscur::crypto::nip04::encrypt_nip04(&sk_hex, &public_key, &content)
     }
@@ -487,11 +1029,22 @@ mod mobile {
         ciphertext: String,
     ) -> Result<String, String> {
         let keys = ensure_session(&app, &session).await?;
-        let sk_hex = keys.secret_key().to_secret_hex();
+        let sk_hex = super::secret_hex(&keys);
 
         libobscur::crypto::nip04::decrypt_nip04(&sk_hex, &public_key, &ciphertext)
     }
 
+    #[tauri::command]
+    pub async fn compute_shared_secret(
+        app: AppHandle,
+        session: State<'_, SessionState>,
+        peer_pubkey: String,
+    ) -> Result<String, String> {
+        let keys = ensure_session(&app, &session).await?;
+        let shared = super::shared_secret_hex(&keys, &peer_pubkey)?;
+        Ok(shared.to_string())
+    }
+
     /// Encrypt content using NIP-44 (Modern)
     #[tauri::command]
     pub async fn encrypt_nip44(
@@ -501,7 +1054,7 @@ mod mobile {
         content: String,
     ) -> Result<String, String> {
         let keys = ensure_session(&app, &session).await?;
-        let sk_hex = keys.secret_key().to_secret_hex();
+        let sk_hex = super::secret_hex(&keys);
 
         libobscur::crypto::nip44::encrypt_nip44(&sk_hex, &public_key, &content)
     }
@@ -515,11 +1068,46 @@ mod mobile {
         payload: String,
     ) -> Result<String, String> {
         let keys = ensure_session(&app, &session).await?;
-        let sk_hex = keys.secret_key().to_secret_hex();
+        let sk_hex = super::secret_hex(&keys);
 
         libobscur::crypto::nip44::decrypt_nip44(&sk_hex, &public_key, &payload)
     }
 
+    /// Decrypt a DM payload without knowing its scheme (NIP-44 v2, then NIP-04)
+    #[tauri::command]
+    pub async fn decrypt_auto(
+        app: AppHandle,
+        session: State<'_, SessionState>,
+        public_key: String,
+        ciphertext: String,
+    ) -> Result<super::AutoDecryptResult, String> {
+        let keys = ensure_session(&app, &session).await?;
+        super::decrypt_auto_payload(&keys, &public_key, &ciphertext)
+    }
+
+    /// Re-encrypt a NIP-04 ciphertext to NIP-44 v2 under the same session key
+    #[tauri::command]
+    pub async fn reencrypt_nip04_to_nip44(
+        app: AppHandle,
+        session: State<'_, SessionState>,
+        public_key: String,
+        nip04_ciphertext: String,
+    ) -> Result<String, String> {
+        let keys = ensure_session(&app, &session).await?;
+        super::reencrypt_nip04_payload(&keys, &public_key, &nip04_ciphertext)
+    }
+
+    /// Alias of `reencrypt_nip04_to_nip44` for the DM migration tool, keyed by the peer
+    #[tauri::command]
+    pub async fn reencrypt_dm(
+        app: AppHandle,
+        session: State<'_, SessionState>,
+        peer_pubkey: String,
+        nip04_ciphertext: String,
+    ) -> Result<String, String> {
+        reencrypt_nip04_to_nip44(app, session, peer_pubkey, nip04_ciphertext).await
+    }
+
     #[tauri::command]
     pub async fn encrypt_gift_wrap(
         app: AppHandle,
@@ -528,7 +1116,7 @@ mod mobile {
         rumor: libobscur::crypto::nip17::Rumor,
     ) -> Result<String, String> {
         let keys = ensure_session(&app, &session).await?;
-        let sk_hex = keys.secret_key().to_secret_hex();
+        let sk_hex = super::secret_hex(&keys);
 
         libobscur::crypto::nip17::wrap_rumor(&sk_hex, &recipient_pk, &rumor, None)
     }
@@ -541,7 +1129,7 @@ mod mobile {
         gift_wrap_sender_pk: String,
     ) -> Result<libobscur::crypto::nip17::Rumor, String> {
         let keys = ensure_session(&app, &session).await?;
-        let sk_hex = keys.secret_key().to_secret_hex();
+        let sk_hex = super::secret_hex(&keys);
 
         libobscur::crypto::nip17::unwrap_gift_wrap(
             &sk_hex,
@@ -554,10 +1142,602 @@ mod mobile {
     pub async fn get_session_nsec(
         app: AppHandle,
         session: State<'_, SessionState>,
-    ) -> Result<String, String> {
+    ) -> Result<Zeroizing<String>, String> {
         let keys = ensure_session(&app, &session).await?;
-        Ok(keys.secret_key().to_secret_hex())
+        Ok(super::secret_hex(&keys))
+    }
+}
+
+// NIP-26 delegation helpers shared by the desktop and mobile signing paths
+use nostr::nips::nip26::{Condition, Conditions, DelegationTag, EventProperties};
+use nostr::{Keys, PublicKey, Tag, TagKind};
+use std::str::FromStr;
+
+const DELEGATION_TAG_NAME: &str = "delegation";
+
+/// NIP-04 payloads are `<base64 ciphertext>?iv=<base64 iv>`.
+fn looks_like_nip04_payload(payload: &str) -> bool {
+    match payload.trim().split_once("?iv=") {
+        Some((ciphertext, iv)) => !ciphertext.is_empty() && !iv.is_empty(),
+        None => false,
+    }
+}
+
+/// NIP-44 v2 payloads are base64 with a leading version byte of 2.
+fn looks_like_nip44_v2_payload(payload: &str) -> bool {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD
+        .decode(payload.trim())
+        .map(|bytes| bytes.first() == Some(&2))
+        .unwrap_or(false)
+}
+
+/// Encryption scheme detected by `decrypt_auto`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DmEncryptionScheme {
+    Nip44,
+    Nip04,
+}
+
+/// Plaintext plus the scheme that decrypted it
+#[derive(Debug, serde::Serialize)]
+pub struct AutoDecryptResult {
+    pub plaintext: String,
+    pub scheme: DmEncryptionScheme,
+}
+
+/// Try NIP-44 v2 when the payload carries its version byte, then NIP-04 when it has the
+/// `?iv=` suffix; errors only once every applicable scheme has failed.
+fn decrypt_auto_payload(
+    keys: &Keys,
+    public_key: &str,
+    ciphertext: &str,
+) -> Result<AutoDecryptResult, String> {
+    let ciphertext = ciphertext.trim();
+    let sk_hex = secret_hex(keys);
+    let mut failures = Vec::new();
+
+    if looks_like_nip44_v2_payload(ciphertext) {
+        match libobscur::crypto::nip44::decrypt_nip44(&sk_hex, public_key, ciphertext) {
+            Ok(plaintext) => {
+                return Ok(AutoDecryptResult {
+                    plaintext,
+                    scheme: DmEncryptionScheme::Nip44,
+                })
+            }
+            Err(e) => failures.push(format!("NIP-44: {e}")),
+        }
+    }
+    if looks_like_nip04_payload(ciphertext) {
+        match libobscur::crypto::nip04::decrypt_nip04(&sk_hex, public_key, ciphertext) {
+            Ok(plaintext) => {
+                return Ok(AutoDecryptResult {
+                    plaintext,
+                    scheme: DmEncryptionScheme::Nip04,
+                })
+            }
+            Err(e) => failures.push(format!("NIP-04: {e}")),
+        }
+    }
+
+    if failures.is_empty() {
+        return Err("Ciphertext is neither a NIP-44 v2 nor a NIP-04 payload".to_string());
+    }
+    Err(format!(
+        "Failed to decrypt payload ({})",
+        failures.join("; ")
+    ))
+}
+
+/// Decrypt a NIP-04 payload and re-encrypt the plaintext with NIP-44 v2, borrowing the
+/// secret once for both steps and wiping the intermediate plaintext.
+fn reencrypt_nip04_payload(
+    keys: &Keys,
+    public_key: &str,
+    nip04_ciphertext: &str,
+) -> Result<String, String> {
+    if !looks_like_nip04_payload(nip04_ciphertext) {
+        return Err("Input is not a NIP-04 payload (expected '<ciphertext>?iv=<iv>')".to_string());
+    }
+    let sk_hex = secret_hex(keys);
+    let plaintext = zeroize::Zeroizing::new(
+        libobscur::crypto::nip04::decrypt_nip04(&sk_hex, public_key, nip04_ciphertext.trim())
+            .map_err(|e| format!("Failed to decrypt NIP-04 payload: {e}"))?,
+    );
+    libobscur::crypto::nip44::encrypt_nip44(&sk_hex, public_key, &plaintext)
+        .map_err(|e| format!("Failed to encrypt NIP-44 payload: {e}"))
+}
+
+/// Key backups are a single short line; anything larger is not a key file.
+const MAX_SECRET_KEY_FILE_BYTES: u64 = 4096;
+
+/// Normalize pasted or file-sourced key material into something `SessionState::set_keys`
+/// accepts: public keys are rejected, `ncryptsec` (NIP-49) is decrypted with `password`,
+/// and nsec/hex pass through for the usual validation.
+fn normalize_imported_secret(
+    raw: &str,
+    password: Option<&str>,
+) -> Result<zeroize::Zeroizing<String>, String> {
+    use nostr::nips::nip49::EncryptedSecretKey;
+    use nostr::FromBech32;
+
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return Err("Key file is empty".to_string());
+    }
+    if trimmed.starts_with("npub") {
+        return Err(
+            "This is a public key (npub); importing requires the secret key (nsec)".to_string(),
+        );
+    }
+    if trimmed.starts_with("ncryptsec") {
+        let password = password
+            .filter(|password| !password.is_empty())
+            .ok_or_else(|| {
+                "This key is password-protected (ncryptsec); a password is required".to_string()
+            })?;
+        let encrypted = EncryptedSecretKey::from_bech32(trimmed)
+            .map_err(|e| format!("Invalid ncryptsec: {e}"))?;
+        let secret_key = encrypted.to_secret_key(password).map_err(|_| {
+            "Could not decrypt ncryptsec: wrong password or corrupted key".to_string()
+        })?;
+        return Ok(zeroize::Zeroizing::new(secret_key.to_secret_hex()));
+    }
+    Keys::parse(trimmed).map_err(|e| format!("Invalid secret key: {e}"))?;
+    Ok(zeroize::Zeroizing::new(trimmed.to_string()))
+}
+
+/// Read and validate a key backup file chosen through the dialog plugin. Blocking
+/// (file I/O and ncryptsec key derivation); run it off the async runtime.
+fn read_secret_key_file(
+    path: &str,
+    password: Option<&str>,
+) -> Result<zeroize::Zeroizing<String>, String> {
+    let metadata = std::fs::metadata(path).map_err(|e| format!("Failed to read key file: {e}"))?;
+    if metadata.len() > MAX_SECRET_KEY_FILE_BYTES {
+        return Err("Key file is too large to contain a single key".to_string());
+    }
+    let contents = zeroize::Zeroizing::new(
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read key file: {e}"))?,
+    );
+    normalize_imported_secret(&contents, password)
+}
+
+/// Key material from a scanned QR payload: a bare or `nostr:`-prefixed nsec, hex, or
+/// ncryptsec, normalized the same way as a key file.
+fn secret_from_uri(
+    uri: &str,
+    password: Option<&str>,
+) -> Result<zeroize::Zeroizing<String>, String> {
+    let trimmed = uri.trim();
+    let payload = match trimmed.get(..6) {
+        Some(scheme) if scheme.eq_ignore_ascii_case("nostr:") => &trimmed[6..],
+        _ => trimmed,
+    };
+    if payload.trim().is_empty() {
+        return Err("Scanned payload is empty".to_string());
+    }
+    normalize_imported_secret(payload, password)
+}
+
+/// Secret key as hex in a buffer that is wiped when dropped; every crypto path
+/// borrows the key through this instead of holding a plain `String`.
+fn secret_hex(keys: &Keys) -> zeroize::Zeroizing<String> {
+    zeroize::Zeroizing::new(keys.secret_key().to_secret_hex())
+}
+
+/// Raw secp256k1 ECDH with `peer_pubkey` (hex or npub): the hex X-coordinate of the shared
+/// point, as used by NIP-04. The byte buffer is wiped before returning.
+fn shared_secret_hex(keys: &Keys, peer_pubkey: &str) -> Result<zeroize::Zeroizing<String>, String> {
+    let peer = PublicKey::parse(peer_pubkey.trim())
+        .map_err(|e| format!("Invalid peer public key: {e}"))?;
+    let shared =
+        zeroize::Zeroizing::new(nostr::util::generate_shared_key(keys.secret_key(), &peer));
+    Ok(zeroize::Zeroizing::new(nostr::util::hex::encode(*shared)))
+}
+
+/// Parse a NIP-26 conditions query string (`kind=1&created_at<1700000000`) and fold in `expiry`.
+fn parse_delegation_conditions(
+    conditions: &str,
+    expiry: Option<u64>,
+    now: u64,
+) -> Result<Conditions, String> {
+    let trimmed = conditions.trim();
+    if trimmed.is_empty() && expiry.is_none() {
+        return Err("Delegation conditions must not be empty".to_string());
+    }
+    let mut parsed = Conditions::from_str(trimmed)
+        .map_err(|e| format!("Invalid delegation conditions '{trimmed}': {e}"))?;
+    if let Some(expiry) = expiry {
+        if expiry <= now {
+            return Err("Delegation expiry must be in the future".to_string());
+        }
+        if !parsed.inner().contains(&Condition::CreatedBefore(expiry)) {
+            parsed.add(Condition::CreatedBefore(expiry));
+        }
+    }
+
+    let inner = parsed.inner();
+    let not_after = inner
+        .iter()
+        .filter_map(|c| match c {
+            Condition::CreatedBefore(t) => Some(*t),
+            _ => None,
+        })
+        .min();
+    let not_before = inner
+        .iter()
+        .filter_map(|c| match c {
+            Condition::CreatedAfter(t) => Some(*t),
+            _ => None,
+        })
+        .max();
+    if let (Some(before), Some(after)) = (not_after, not_before) {
+        if after >= before {
+            return Err("Delegation conditions describe an empty created_at window".to_string());
+        }
+    }
+    Ok(parsed)
+}
+
+fn build_delegation_tag(
+    delegator_keys: &Keys,
+    delegatee_pubkey: &str,
+    conditions: &str,
+    expiry: Option<u64>,
+) -> Result<Vec<String>, String> {
+    let delegatee = PublicKey::parse(delegatee_pubkey.trim())
+        .map_err(|e| format!("Invalid delegatee pubkey: {e}"))?;
+    let now = nostr::Timestamp::now().as_u64();
+    let conditions = parse_delegation_conditions(conditions, expiry, now)?;
+    let tag = DelegationTag::new(delegator_keys, &delegatee, conditions);
+    Ok(vec![
+        DELEGATION_TAG_NAME.to_string(),
+        tag.delegator_pubkey().to_string(),
+        tag.conditions().to_string(),
+        tag.signature().to_string(),
+    ])
+}
+
+/// Validate `delegation` against the signer and event, then attach it (replacing any prior delegation tag).
+fn apply_delegation_tag(
+    signer_pubkey: &PublicKey,
+    kind: u16,
+    created_at: u64,
+    tags: Vec<Tag>,
+    delegation: &[String],
+) -> Result<Vec<Tag>, String> {
+    let delegation_tag = DelegationTag::try_from(delegation.to_vec())
+        .map_err(|e| format!("Invalid delegation tag: {e}"))?;
+    delegation_tag
+        .validate(signer_pubkey, &EventProperties::new(kind, created_at))
+        .map_err(|e| format!("Delegation does not authorize this event: {e}"))?;
+
+    let mut tags = tags
+        .into_iter()
+        .filter(|tag| tag.as_slice().first().map(String::as_str) != Some(DELEGATION_TAG_NAME))
+        .collect::<Vec<_>>();
+    tags.push(Tag::custom(
+        TagKind::Custom(DELEGATION_TAG_NAME.into()),
+        delegation[1..].to_vec(),
+    ));
+    Ok(tags)
+}
+
+/// Result of `can_sign`, checked before opening a compose screen
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct SignCapability {
+    /// Keys are already unlocked in the session
+    pub has_session: bool,
+    /// No secret key is reachable for the profile (no session, keychain entry or key file)
+    pub watch_only: bool,
+    /// The OS keychain (or mobile secure store) holds a key for the profile
+    pub keychain_available: bool,
+    /// Signing can proceed without importing a key or entering a key file password
+    pub can_sign: bool,
+}
+
+impl SignCapability {
+    fn new(has_session: bool, keychain_available: bool, key_file_exists: bool) -> Self {
+        SignCapability {
+            has_session,
+            watch_only: !has_session && !keychain_available && !key_file_exists,
+            keychain_available,
+            can_sign: has_session || keychain_available,
+        }
+    }
+}
+
+// NIP-89 application handler events shared by the desktop and mobile signing paths
+const HANDLER_INFO_KIND: u16 = 31990;
+const HANDLER_RECOMMENDATION_KIND: u16 = 31989;
+/// Replaced by the entity's bech32 string when another client launches the handler
+const HANDLER_URL_PLACEHOLDER: &str = "<bech32>";
+const HANDLER_ENTITY_TYPES: &[&str] = &["npub", "note", "nprofile", "nevent", "naddr"];
+
+/// One launch template: `["<platform>", "<url containing <bech32>>", "<entity type>"]`
+#[derive(Debug, serde::Deserialize)]
+pub struct HandlerTemplate {
+    /// "web", "ios", "android", ... (the NIP-89 platform tag name)
+    pub platform: String,
+    pub url: String,
+    /// Entity type this template accepts; omitted means any
+    #[serde(default)]
+    pub entity: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct HandlerInfoRequest {
+    /// `d` tag, so the handler event can be replaced in place
+    pub identifier: String,
+    /// Event kinds the app handles (`k` tags)
+    pub kinds: Vec<u16>,
+    pub templates: Vec<HandlerTemplate>,
+    /// Optional kind-0 style profile (name, about, picture) used as the event content
+    #[serde(default)]
+    pub metadata: Option<serde_json::Value>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct HandlerRecommendationRequest {
+    /// Kind being recommended for (`d` tag)
+    pub kind: u16,
+    pub handler_pubkey: String,
+    pub handler_identifier: String,
+    #[serde(default)]
+    pub relay_hint: Option<String>,
+    #[serde(default)]
+    pub platform: Option<String>,
+}
+
+fn validate_handler_platform(platform: &str) -> Result<(), String> {
+    let valid = !platform.is_empty()
+        && platform
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if !valid {
+        return Err(format!("Invalid handler platform '{platform}'"));
+    }
+    Ok(())
+}
+
+fn handler_info_builder(req: &HandlerInfoRequest) -> Result<nostr::EventBuilder, String> {
+    let identifier = req.identifier.trim();
+    if identifier.is_empty() {
+        return Err("Handler identifier must not be empty".to_string());
+    }
+    if req.kinds.is_empty() {
+        return Err("Handler must support at least one kind".to_string());
+    }
+    if req.templates.is_empty() {
+        return Err("Handler needs at least one launch template".to_string());
+    }
+
+    let mut tags = vec![Tag::custom(
+        TagKind::Custom("d".into()),
+        [identifier.to_string()],
+    )];
+    let mut kinds = req.kinds.clone();
+    kinds.sort_unstable();
+    kinds.dedup();
+    tags.extend(
+        kinds
+            .iter()
+            .map(|kind| Tag::custom(TagKind::Custom("k".into()), [kind.to_string()])),
+    );
+    for template in &req.templates {
+        let platform = template.platform.trim();
+        validate_handler_platform(platform)?;
+        let url = template.url.trim();
+        if !url.contains(HANDLER_URL_PLACEHOLDER) {
+            return Err(format!(
+                "Handler URL '{url}' must contain the {HANDLER_URL_PLACEHOLDER} placeholder"
+            ));
+        }
+        let mut values = vec![url.to_string()];
+        if let Some(entity) = template.entity.as_deref().map(str::trim) {
+            if !HANDLER_ENTITY_TYPES.contains(&entity) {
+                return Err(format!("Unknown handler entity type '{entity}'"));
+            }
+            values.push(entity.to_string());
+        }
+        tags.push(Tag::custom(
+            TagKind::Custom(platform.to_string().into()),
+            values,
+        ));
+    }
+
+    let content = match &req.metadata {
+        Some(metadata) if !metadata.is_object() => {
+            return Err("Handler metadata must be a JSON object".to_string())
+        }
+        Some(metadata) => metadata.to_string(),
+        None => String::new(),
+    };
+    Ok(nostr::EventBuilder::new(nostr::Kind::from(HANDLER_INFO_KIND), content).tags(tags))
+}
+
+fn handler_recommendation_builder(
+    req: &HandlerRecommendationRequest,
+) -> Result<nostr::EventBuilder, String> {
+    let handler_pubkey = PublicKey::parse(req.handler_pubkey.trim())
+        .map_err(|e| format!("Invalid handler pubkey: {e}"))?;
+    let identifier = req.handler_identifier.trim();
+    if identifier.is_empty() {
+        return Err("Handler identifier must not be empty".to_string());
+    }
+
+    let mut address = vec![format!(
+        "{HANDLER_INFO_KIND}:{}:{identifier}",
+        handler_pubkey.to_hex()
+    )];
+    let relay_hint = req
+        .relay_hint
+        .as_deref()
+        .map(str::trim)
+        .filter(|hint| !hint.is_empty());
+    let platform = req
+        .platform
+        .as_deref()
+        .map(str::trim)
+        .filter(|platform| !platform.is_empty());
+    if let Some(platform) = platform {
+        validate_handler_platform(platform)?;
+    }
+    if relay_hint.is_some() || platform.is_some() {
+        address.push(relay_hint.unwrap_or_default().to_string());
     }
+    if let Some(platform) = platform {
+        address.push(platform.to_string());
+    }
+
+    let tags = vec![
+        Tag::custom(TagKind::Custom("d".into()), [req.kind.to_string()]),
+        Tag::custom(TagKind::Custom("a".into()), address),
+    ];
+    Ok(nostr::EventBuilder::new(nostr::Kind::from(HANDLER_RECOMMENDATION_KIND), "").tags(tags))
+}
+
+/// NIP-51 list types: (name, kind, whether it is a set addressed by a `d` tag)
+const LIST_TYPES: &[(&str, u16, bool)] = &[
+    ("mute", 10000, false),
+    ("pin", 10001, false),
+    ("bookmarks", 10003, false),
+    ("communities", 10004, false),
+    ("public_chats", 10005, false),
+    ("blocked_relays", 10006, false),
+    ("search_relays", 10007, false),
+    ("interests", 10015, false),
+    ("follow_set", 30000, true),
+    ("relay_set", 30002, true),
+    ("bookmark_set", 30003, true),
+];
+
+/// One list item as a tag, e.g. `{"tag": "p", "value": "<hex pubkey>"}`
+#[derive(Debug, serde::Deserialize)]
+pub struct ListEntry {
+    /// "p", "e", "a", "t", "word", "r" or "relay"
+    pub tag: String,
+    pub value: String,
+    /// Trailing tag values such as a relay hint or petname
+    #[serde(default)]
+    pub extra: Vec<String>,
+    /// Kept in the encrypted content instead of the public tags
+    #[serde(default)]
+    pub private: bool,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct ListEventRequest {
+    /// One of the `LIST_TYPES` names ("mute", "bookmarks", "follow_set", ...)
+    pub list_type: String,
+    /// `d` tag; required for sets, ignored for standard lists
+    #[serde(default)]
+    pub identifier: Option<String>,
+    /// Set title (`title` tag)
+    #[serde(default)]
+    pub title: Option<String>,
+    pub entries: Vec<ListEntry>,
+    /// Scheme for private entries; NIP-44 unless NIP-04 is asked for explicitly
+    #[serde(default)]
+    pub encryption: Option<DmEncryptionScheme>,
+}
+
+fn list_entry_tag(entry: &ListEntry) -> Result<Vec<String>, String> {
+    let tag = entry.tag.trim();
+    let value = entry.value.trim();
+    let value = match tag {
+        "p" => PublicKey::parse(value)
+            .map_err(|e| format!("Invalid pubkey in list entry: {e}"))?
+            .to_hex(),
+        "e" => nostr::EventId::parse(value)
+            .map_err(|e| format!("Invalid event id in list entry: {e}"))?
+            .to_hex(),
+        "a" => nostr::nips::nip01::Coordinate::parse(value)
+            .map_err(|e| format!("Invalid address in list entry: {e}"))?
+            .to_string(),
+        "r" | "relay" => nostr::RelayUrl::parse(value)
+            .map_err(|e| format!("Invalid relay URL in list entry: {e}"))?
+            .to_string(),
+        "t" => value.trim_start_matches('#').to_lowercase(),
+        "word" => value.to_lowercase(),
+        other => return Err(format!("Unsupported list entry tag '{other}'")),
+    };
+    if value.is_empty() {
+        return Err(format!("List entry '{tag}' must not be empty"));
+    }
+    let mut values = vec![tag.to_string(), value];
+    values.extend(entry.extra.iter().map(|extra| extra.trim().to_string()));
+    Ok(values)
+}
+
+/// Build a NIP-51 list: public entries become tags, private ones a JSON tag array
+/// encrypted to the author's own key as the content.
+fn list_event_builder(keys: &Keys, req: &ListEventRequest) -> Result<nostr::EventBuilder, String> {
+    let list_type = req.list_type.trim();
+    let &(_, kind, is_set) = LIST_TYPES
+        .iter()
+        .find(|(name, _, _)| *name == list_type)
+        .ok_or_else(|| format!("Unknown list type '{list_type}'"))?;
+
+    let mut tags = Vec::new();
+    if is_set {
+        let identifier = req.identifier.as_deref().map(str::trim).unwrap_or_default();
+        if identifier.is_empty() {
+            return Err(format!("A {list_type} needs an identifier"));
+        }
+        tags.push(Tag::custom(
+            TagKind::Custom("d".into()),
+            [identifier.to_string()],
+        ));
+        if let Some(title) = req
+            .title
+            .as_deref()
+            .map(str::trim)
+            .filter(|title| !title.is_empty())
+        {
+            tags.push(Tag::custom(
+                TagKind::Custom("title".into()),
+                [title.to_string()],
+            ));
+        }
+    }
+
+    let mut private_tags = Vec::new();
+    for entry in &req.entries {
+        let values = list_entry_tag(entry)?;
+        if entry.private {
+            private_tags.push(values);
+        } else {
+            let (name, rest) = values.split_first().expect("tag name");
+            tags.push(Tag::custom(
+                TagKind::Custom(name.clone().into()),
+                rest.to_vec(),
+            ));
+        }
+    }
+
+    let content = if private_tags.is_empty() {
+        String::new()
+    } else {
+        let plaintext = zeroize::Zeroizing::new(
+            serde_json::to_string(&private_tags).map_err(|e| e.to_string())?,
+        );
+        let sk_hex = secret_hex(keys);
+        let own_pubkey = keys.public_key().to_hex();
+        match req.encryption.unwrap_or(DmEncryptionScheme::Nip44) {
+            DmEncryptionScheme::Nip44 => {
+                libobscur::crypto::nip44::encrypt_nip44(&sk_hex, &own_pubkey, &plaintext)
+                    .map_err(|e| format!("Failed to encrypt private list entries: {e}"))?
+            }
+            DmEncryptionScheme::Nip04 => {
+                libobscur::crypto::nip04::encrypt_nip04(&sk_hex, &own_pubkey, &plaintext)
+                    .map_err(|e| format!("Failed to encrypt private list entries: {e}"))?
+            }
+        }
+    };
+    Ok(nostr::EventBuilder::new(nostr::Kind::from(kind), content).tags(tags))
 }
 
 // Re-export the appropriate implementation
@@ -566,3 +1746,352 @@ pub use desktop::*;
 
 #[cfg(any(target_os = "android", target_os = "ios"))]
 pub use mobile::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000;
+
+    #[test]
+    fn reencrypt_nip04_payload_round_trips_to_nip44() {
+        let sender = Keys::generate();
+        let recipient = Keys::generate();
+        let nip04 = libobscur::crypto::nip04::encrypt_nip04(
+            &secret_hex(&sender),
+            &recipient.public_key().to_hex(),
+            "hello from the archive",
+        )
+        .expect("nip04 encrypt");
+
+        let nip44 = reencrypt_nip04_payload(&sender, &recipient.public_key().to_hex(), &nip04)
+            .expect("reencrypt");
+        let plaintext = libobscur::crypto::nip44::decrypt_nip44(
+            &secret_hex(&recipient),
+            &sender.public_key().to_hex(),
+            &nip44,
+        )
+        .expect("nip44 decrypt");
+        assert_eq!(plaintext, "hello from the archive");
+    }
+
+    #[test]
+    fn reencrypt_nip04_payload_rejects_non_nip04_input() {
+        let keys = Keys::generate();
+        let peer = Keys::generate().public_key().to_hex();
+        let error = reencrypt_nip04_payload(&keys, &peer, "not-a-payload").unwrap_err();
+        assert!(error.contains("not a NIP-04 payload"));
+        let error = reencrypt_nip04_payload(&keys, &peer, "AAAA?iv=AAAA").unwrap_err();
+        assert!(error.contains("Failed to decrypt NIP-04"));
+    }
+
+    #[test]
+    fn normalize_imported_secret_accepts_nsec_and_hex_and_rejects_npub() {
+        let keys = Keys::generate();
+        let nsec = nostr::ToBech32::to_bech32(keys.secret_key()).expect("nsec");
+        let npub = nostr::ToBech32::to_bech32(&keys.public_key()).expect("npub");
+
+        assert_eq!(
+            &*normalize_imported_secret(&format!("  {nsec}\n"), None).unwrap(),
+            &nsec
+        );
+        assert!(normalize_imported_secret(&secret_hex(&keys), None).is_ok());
+        assert!(normalize_imported_secret(&npub, None)
+            .unwrap_err()
+            .contains("npub"));
+        assert!(normalize_imported_secret("   ", None).is_err());
+        assert!(normalize_imported_secret("not a key", None).is_err());
+    }
+
+    #[test]
+    fn normalize_imported_secret_decrypts_ncryptsec_with_password() {
+        use nostr::nips::nip49::{EncryptedSecretKey, KeySecurity};
+
+        let keys = Keys::generate();
+        let encrypted =
+            EncryptedSecretKey::new(keys.secret_key(), "hunter2", 4, KeySecurity::Medium)
+                .expect("encrypt");
+        let ncryptsec = nostr::ToBech32::to_bech32(&encrypted).expect("ncryptsec");
+
+        assert!(normalize_imported_secret(&ncryptsec, None).is_err());
+        assert!(normalize_imported_secret(&ncryptsec, Some("wrong")).is_err());
+        let secret = normalize_imported_secret(&ncryptsec, Some("hunter2")).expect("decrypt");
+        assert_eq!(*secret, *secret_hex(&keys));
+    }
+
+    #[test]
+    fn secret_from_uri_strips_nostr_scheme() {
+        let keys = Keys::generate();
+        let nsec = nostr::ToBech32::to_bech32(keys.secret_key()).expect("nsec");
+
+        assert_eq!(
+            &*secret_from_uri(&format!("nostr:{nsec}"), None).unwrap(),
+            &nsec
+        );
+        assert_eq!(
+            &*secret_from_uri(&format!(" NOSTR:{nsec} "), None).unwrap(),
+            &nsec
+        );
+        assert!(secret_from_uri(&secret_hex(&keys), None).is_ok());
+        assert!(secret_from_uri("nostr:", None).is_err());
+        let npub = nostr::ToBech32::to_bech32(&keys.public_key()).expect("npub");
+        assert!(secret_from_uri(&format!("nostr:{npub}"), None)
+            .unwrap_err()
+            .contains("npub"));
+    }
+
+    #[test]
+    fn decrypt_auto_payload_detects_each_scheme() {
+        let sender = Keys::generate();
+        let recipient = Keys::generate();
+        let recipient_hex = recipient.public_key().to_hex();
+        let sender_hex = sender.public_key().to_hex();
+
+        let nip44 =
+            libobscur::crypto::nip44::encrypt_nip44(&secret_hex(&sender), &recipient_hex, "modern")
+                .expect("nip44 encrypt");
+        let decrypted =
+            decrypt_auto_payload(&recipient, &sender_hex, &nip44).expect("nip44 decrypt");
+        assert_eq!(
+            (decrypted.plaintext.as_str(), decrypted.scheme),
+            ("modern", DmEncryptionScheme::Nip44)
+        );
+
+        let nip04 =
+            libobscur::crypto::nip04::encrypt_nip04(&secret_hex(&sender), &recipient_hex, "legacy")
+                .expect("nip04 encrypt");
+        let decrypted =
+            decrypt_auto_payload(&recipient, &sender_hex, &nip04).expect("nip04 decrypt");
+        assert_eq!(
+            (decrypted.plaintext.as_str(), decrypted.scheme),
+            ("legacy", DmEncryptionScheme::Nip04)
+        );
+
+        assert!(decrypt_auto_payload(&recipient, &sender_hex, "plain text").is_err());
+    }
+
+    #[test]
+    fn shared_secret_is_symmetric() {
+        let alice = Keys::generate();
+        let bob = Keys::generate();
+        let from_alice = shared_secret_hex(&alice, &bob.public_key().to_hex()).expect("alice");
+        let alice_npub = nostr::ToBech32::to_bech32(&alice.public_key()).expect("npub");
+        let from_bob = shared_secret_hex(&bob, &alice_npub).expect("bob");
+        assert_eq!(from_alice.len(), 64);
+        assert_eq!(*from_alice, *from_bob);
+        assert!(shared_secret_hex(&alice, "not a key").is_err());
+    }
+
+    #[test]
+    fn secret_hex_buffer_is_wiped_on_zeroize() {
+        use zeroize::Zeroize;
+
+        let keys = Keys::parse("0000000000000000000000000000000000000000000000000000000000000001")
+            .expect("keys");
+        let mut secret = secret_hex(&keys);
+        assert!(secret.ends_with("0001"));
+        // A slice is wiped in place and keeps its length, so the live bytes can be read back
+        let mut bytes = std::mem::take(&mut *secret).into_bytes();
+        bytes.as_mut_slice().zeroize();
+        assert!(bytes.iter().all(|byte| *byte == 0));
+
+        // What `Zeroizing` runs on drop: the `String` is wiped, then cleared
+        let mut secret = secret_hex(&keys);
+        secret.zeroize();
+        assert!(secret.is_empty());
+    }
+
+    #[test]
+    fn sign_capability_flags_watch_only_when_no_key_is_reachable() {
+        let locked = SignCapability::new(false, true, false);
+        assert!(locked.can_sign && !locked.watch_only);
+        let key_file_only = SignCapability::new(false, false, true);
+        assert!(!key_file_only.can_sign && !key_file_only.watch_only);
+        let none = SignCapability::new(false, false, false);
+        assert!(!none.can_sign && none.watch_only);
+    }
+
+    #[test]
+    fn handler_info_builder_tags_kinds_and_templates() {
+        let req = HandlerInfoRequest {
+            identifier: "obscur".to_string(),
+            kinds: vec![4, 1, 4],
+            templates: vec![HandlerTemplate {
+                platform: "web".to_string(),
+                url: "https://obscur.app/open/<bech32>".to_string(),
+                entity: Some("nevent".to_string()),
+            }],
+            metadata: Some(serde_json::json!({ "name": "Obscur" })),
+        };
+        let keys = Keys::generate();
+        let event = handler_info_builder(&req)
+            .expect("builder")
+            .build(keys.public_key());
+        let tags: Vec<Vec<String>> = event.tags.iter().map(|tag| tag.clone().to_vec()).collect();
+        assert_eq!(event.kind.as_u16(), HANDLER_INFO_KIND);
+        assert_eq!(tags[0], vec!["d", "obscur"]);
+        assert_eq!(tags[1..3], [vec!["k", "1"], vec!["k", "4"]]);
+        assert_eq!(
+            tags[3],
+            vec!["web", "https://obscur.app/open/<bech32>", "nevent"]
+        );
+        assert_eq!(event.content, r#"{"name":"Obscur"}"#);
+    }
+
+    #[test]
+    fn handler_info_builder_rejects_bad_templates() {
+        let mut req = HandlerInfoRequest {
+            identifier: "obscur".to_string(),
+            kinds: vec![1],
+            templates: vec![HandlerTemplate {
+                platform: "web".to_string(),
+                url: "https://obscur.app/open".to_string(),
+                entity: None,
+            }],
+            metadata: None,
+        };
+        assert!(handler_info_builder(&req)
+            .unwrap_err()
+            .contains("placeholder"));
+        req.templates[0].url = "https://obscur.app/open/<bech32>".to_string();
+        req.templates[0].entity = Some("nsec".to_string());
+        assert!(handler_info_builder(&req).is_err());
+        req.templates[0].entity = None;
+        req.kinds.clear();
+        assert!(handler_info_builder(&req).is_err());
+    }
+
+    #[test]
+    fn handler_recommendation_builder_points_at_handler_address() {
+        let handler = Keys::generate().public_key();
+        let req = HandlerRecommendationRequest {
+            kind: 31337,
+            handler_pubkey: handler.to_hex(),
+            handler_identifier: "obscur".to_string(),
+            relay_hint: None,
+            platform: Some("web".to_string()),
+        };
+        let event = handler_recommendation_builder(&req)
+            .expect("builder")
+            .build(handler);
+        let tags: Vec<Vec<String>> = event.tags.iter().map(|tag| tag.clone().to_vec()).collect();
+        assert_eq!(event.kind.as_u16(), HANDLER_RECOMMENDATION_KIND);
+        assert_eq!(tags[0], vec!["d", "31337"]);
+        assert_eq!(
+            tags[1],
+            vec![
+                "a".to_string(),
+                format!("31990:{}:obscur", handler.to_hex()),
+                String::new(),
+                "web".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn list_event_builder_encrypts_private_entries_to_self() {
+        let keys = Keys::generate();
+        let muted = Keys::generate().public_key();
+        let req = ListEventRequest {
+            list_type: "mute".to_string(),
+            identifier: None,
+            title: None,
+            entries: vec![
+                ListEntry {
+                    tag: "t".to_string(),
+                    value: "#Spam".to_string(),
+                    extra: Vec::new(),
+                    private: false,
+                },
+                ListEntry {
+                    tag: "p".to_string(),
+                    value: muted.to_hex(),
+                    extra: Vec::new(),
+                    private: true,
+                },
+            ],
+            encryption: None,
+        };
+        let event = list_event_builder(&keys, &req)
+            .expect("builder")
+            .build(keys.public_key());
+        let tags: Vec<Vec<String>> = event.tags.iter().map(|tag| tag.clone().to_vec()).collect();
+        assert_eq!(event.kind.as_u16(), 10000);
+        assert_eq!(tags, vec![vec!["t", "spam"]]);
+
+        let plaintext = libobscur::crypto::nip44::decrypt_nip44(
+            &secret_hex(&keys),
+            &keys.public_key().to_hex(),
+            &event.content,
+        )
+        .expect("decrypt");
+        let private: Vec<Vec<String>> = serde_json::from_str(&plaintext).expect("tags");
+        assert_eq!(private, vec![vec!["p".to_string(), muted.to_hex()]]);
+    }
+
+    #[test]
+    fn list_event_builder_requires_identifier_for_sets() {
+        let keys = Keys::generate();
+        let mut req = ListEventRequest {
+            list_type: "follow_set".to_string(),
+            identifier: None,
+            title: Some("Friends".to_string()),
+            entries: Vec::new(),
+            encryption: None,
+        };
+        assert!(list_event_builder(&keys, &req).is_err());
+        req.identifier = Some("friends".to_string());
+        let event = list_event_builder(&keys, &req)
+            .expect("builder")
+            .build(keys.public_key());
+        let tags: Vec<Vec<String>> = event.tags.iter().map(|tag| tag.clone().to_vec()).collect();
+        assert_eq!(event.kind.as_u16(), 30000);
+        assert_eq!(tags, vec![vec!["d", "friends"], vec!["title", "Friends"]]);
+        req.list_type = "favorites".to_string();
+        assert!(list_event_builder(&keys, &req).is_err());
+    }
+
+    #[test]
+    fn parse_delegation_conditions_appends_expiry() {
+        let parsed =
+            parse_delegation_conditions("kind=1", Some(NOW + 60), NOW).expect("conditions");
+        assert_eq!(
+            parsed.to_string(),
+            format!("kind=1&created_at<{}", NOW + 60)
+        );
+    }
+
+    #[test]
+    fn parse_delegation_conditions_rejects_invalid_input() {
+        assert!(parse_delegation_conditions("", None, NOW).is_err());
+        assert!(parse_delegation_conditions("kind=abc", None, NOW).is_err());
+        assert!(parse_delegation_conditions("kind=1", Some(NOW - 1), NOW).is_err());
+        assert!(parse_delegation_conditions("created_at>20&created_at<10", None, NOW).is_err());
+    }
+
+    #[test]
+    fn delegation_tag_round_trips_into_delegatee_event() {
+        let delegator = Keys::generate();
+        let delegatee = Keys::generate();
+        let expiry = nostr::Timestamp::now().as_u64() + 3_600;
+        let tag = build_delegation_tag(
+            &delegator,
+            &delegatee.public_key().to_hex(),
+            "kind=1",
+            Some(expiry),
+        )
+        .expect("delegation tag");
+        assert_eq!(tag[0], DELEGATION_TAG_NAME);
+        assert_eq!(tag[1], delegator.public_key().to_hex());
+
+        let created_at = expiry - 60;
+        let tags = apply_delegation_tag(&delegatee.public_key(), 1, created_at, Vec::new(), &tag)
+            .expect("delegated tags");
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].as_slice(), tag.as_slice());
+
+        let wrong_kind =
+            apply_delegation_tag(&delegatee.public_key(), 4, created_at, Vec::new(), &tag);
+        assert!(wrong_kind.is_err());
+    }
+}